@@ -0,0 +1,118 @@
+//! Compares the flat `Grid` with the nested vectors maps used before.
+//!
+//! Run with `cargo bench`. Each case runs a fixed number of times on a
+//! generated level and prints the average time per run.
+
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use kd_rusty_crawler::{
+    grid::{Grid, Point},
+    level,
+    map::Tile,
+    rng::Rng,
+};
+
+/// Number of runs of every case.
+const RUNS: u32 = 2000;
+
+/// Runs a case and prints its average time per run.
+fn bench(name: &str, mut case: impl FnMut() -> usize) {
+    let start = Instant::now();
+    for _ in 0..RUNS {
+        black_box(case());
+    }
+    let average = start.elapsed() / RUNS;
+    println!(
+        "{:<36} {:>10.2?}",
+        name,
+        average.max(Duration::from_nanos(1))
+    );
+}
+
+fn main() {
+    let grid: Grid<Tile> = level::generate(3, &mut Rng::new(42)).map.tiles;
+    let (width, height) = (grid.width(), grid.height());
+    let nested: Vec<Vec<Tile>> = grid.rows().map(<[Tile]>::to_vec).collect();
+    let points: Vec<Point> = {
+        let mut rng = Rng::new(7);
+        (0..1000)
+            .map(|_| Point::new(rng.range(-1, width + 1), rng.range(-1, height + 1)))
+            .collect()
+    };
+    let nested_get = |x: i32, y: i32| {
+        if x >= 0 && x < width && y >= 0 && y < height {
+            Some(&nested[y as usize][x as usize])
+        } else {
+            None
+        }
+    };
+
+    println!("{}x{} level, average of {} runs", width, height, RUNS);
+    bench("scan floor: nested vectors", || {
+        nested
+            .iter()
+            .flatten()
+            .filter(|t| **t == Tile::Floor)
+            .count()
+    });
+    bench("scan floor: grid", || {
+        grid.iter().filter(|t| **t == Tile::Floor).count()
+    });
+
+    bench("random access: nested vectors", || {
+        points
+            .iter()
+            .filter(|p| nested_get(p.x, p.y) == Some(&Tile::Floor))
+            .count()
+    });
+    bench("random access: grid get", || {
+        points
+            .iter()
+            .filter(|p| grid.get(**p) == Some(&Tile::Floor))
+            .count()
+    });
+
+    bench("walls around floor: nested vectors", || {
+        let mut walls = 0;
+        for y in 0..height {
+            for x in 0..width {
+                if nested[y as usize][x as usize] != Tile::Floor {
+                    continue;
+                }
+                walls += Point::AROUND
+                    .iter()
+                    .filter(|o| nested_get(x + o.x, y + o.y) == Some(&Tile::Wall))
+                    .count();
+            }
+        }
+        walls
+    });
+    bench("walls around floor: grid get", || {
+        let mut walls = 0;
+        for y in 0..height {
+            for x in 0..width {
+                if *grid.get_wrapping((x, y)) != Tile::Floor {
+                    continue;
+                }
+                walls += Point::AROUND
+                    .iter()
+                    .filter(|o| grid.get((x + o.x, y + o.y)) == Some(&Tile::Wall))
+                    .count();
+            }
+        }
+        walls
+    });
+    bench("walls around floor: grid neighbours", || {
+        grid.iter_points()
+            .filter(|(_, tile)| **tile == Tile::Floor)
+            .map(|(point, _)| {
+                grid.neighbours_around(point)
+                    .filter(|n| *grid.get_wrapping(*n) == Tile::Wall)
+                    .count()
+            })
+            .sum()
+    });
+}
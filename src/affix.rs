@@ -0,0 +1,319 @@
+//! Affix module giving equipment a rarity and random bonuses.
+//!
+//! Weapons, armor and jewelry found in the dungeon roll a [`Rarity`] by the
+//! loot level. The rarity decides how many affixes the item gets and how
+//! strong they are. Jewelry is always at least uncommon. Prefixes go in
+//! front of the item name, suffixes after it, e.g. "Sharp Sword of Haste".
+
+use crate::{character::Stats, rng::Rng, Item, ItemType};
+
+/// Represents how rare and strong an item is, most common first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Rarity {
+    /// No affixes
+    Common,
+    /// One affix
+    Uncommon,
+    /// A prefix and a suffix
+    Rare,
+    /// A prefix and a suffix of double strength
+    Epic,
+    /// A prefix and a suffix of triple strength
+    Legendary,
+}
+
+impl Rarity {
+    /// All rarities, most common first.
+    pub const ALL: [Rarity; 5] = [
+        Rarity::Common,
+        Rarity::Uncommon,
+        Rarity::Rare,
+        Rarity::Epic,
+        Rarity::Legendary,
+    ];
+
+    /// Gets the display name of the rarity.
+    ///
+    /// # Returns
+    /// The capitalized name of the rarity
+    pub fn name(&self) -> &'static str {
+        match self {
+            Rarity::Common => "Common",
+            Rarity::Uncommon => "Uncommon",
+            Rarity::Rare => "Rare",
+            Rarity::Epic => "Epic",
+            Rarity::Legendary => "Legendary",
+        }
+    }
+
+    /// Gets the weight of the rarity when rolling loot.
+    ///
+    /// # Arguments
+    /// * `level` - Loot level, the dungeon depth
+    ///
+    /// # Returns
+    /// The relative chance to be rolled, rarer tiers grow with the level
+    pub fn weight(&self, level: i32) -> i32 {
+        match self {
+            Rarity::Common => 60,
+            Rarity::Uncommon => 25 + level * 2,
+            Rarity::Rare => 8 + level * 2,
+            Rarity::Epic => (level - 2).max(0) * 2,
+            Rarity::Legendary => (level - 5).max(0),
+        }
+    }
+
+    /// Rolls a rarity for a loot level.
+    ///
+    /// # Arguments
+    /// * `level` - Loot level, the dungeon depth
+    /// * `rng` - Random number generator
+    pub fn roll(level: i32, rng: &mut Rng) -> Rarity {
+        let total: i32 = Rarity::ALL.iter().map(|r| r.weight(level)).sum();
+        let mut pick = rng.range(0, total);
+        for rarity in Rarity::ALL {
+            if pick < rarity.weight(level) {
+                return rarity;
+            }
+            pick -= rarity.weight(level);
+        }
+        Rarity::Common
+    }
+
+    /// Gets the number of affixes an item of this rarity gets.
+    pub fn affix_count(&self) -> usize {
+        match self {
+            Rarity::Common => 0,
+            Rarity::Uncommon => 1,
+            _ => 2,
+        }
+    }
+
+    /// Gets how many times its base bonus each affix gives.
+    pub fn power(&self) -> i32 {
+        match self {
+            Rarity::Epic => 2,
+            Rarity::Legendary => 3,
+            _ => 1,
+        }
+    }
+}
+
+/// Represents the different affixes an item can have.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AffixKind {
+    /// Prefix adding attack
+    Sharp,
+    /// Prefix adding defense
+    Sturdy,
+    /// Prefix adding health
+    Hale,
+    /// Prefix adding speed
+    Nimble,
+    /// Suffix adding speed
+    Haste,
+    /// Suffix adding attack
+    Might,
+    /// Suffix adding defense
+    Protection,
+    /// Suffix adding health
+    Vitality,
+}
+
+impl AffixKind {
+    /// All affixes, prefixes first.
+    pub const ALL: [AffixKind; 8] = [
+        AffixKind::Sharp,
+        AffixKind::Sturdy,
+        AffixKind::Hale,
+        AffixKind::Nimble,
+        AffixKind::Haste,
+        AffixKind::Might,
+        AffixKind::Protection,
+        AffixKind::Vitality,
+    ];
+
+    /// Gets the text the affix adds to an item name.
+    ///
+    /// # Returns
+    /// A word for prefixes, an "of" phrase for suffixes
+    pub fn name(&self) -> &'static str {
+        match self {
+            AffixKind::Sharp => "Sharp",
+            AffixKind::Sturdy => "Sturdy",
+            AffixKind::Hale => "Hale",
+            AffixKind::Nimble => "Nimble",
+            AffixKind::Haste => "of Haste",
+            AffixKind::Might => "of Might",
+            AffixKind::Protection => "of Protection",
+            AffixKind::Vitality => "of Vitality",
+        }
+    }
+
+    /// Checks whether the affix goes in front of the item name.
+    pub fn is_prefix(&self) -> bool {
+        matches!(
+            self,
+            AffixKind::Sharp | AffixKind::Sturdy | AffixKind::Hale | AffixKind::Nimble
+        )
+    }
+
+    /// Gets the bonus of the affix at power 1.
+    pub fn bonus(&self) -> Stats {
+        match self {
+            AffixKind::Sharp => Stats::new(0, 2, 0, 0),
+            AffixKind::Sturdy => Stats::new(0, 0, 2, 0),
+            AffixKind::Hale => Stats::new(10, 0, 0, 0),
+            AffixKind::Nimble => Stats::new(0, 0, 0, 1),
+            AffixKind::Haste => Stats::new(0, 0, 0, 2),
+            AffixKind::Might => Stats::new(0, 3, 0, 0),
+            AffixKind::Protection => Stats::new(0, 0, 3, 0),
+            AffixKind::Vitality => Stats::new(15, 0, 0, 0),
+        }
+    }
+
+    /// Checks whether the affix can appear on a type of item.
+    ///
+    /// # Returns
+    /// `true` if the affix suits the item, attack affixes only suit weapons
+    /// and jewelry and defense affixes only armor and jewelry
+    pub fn suits(&self, item_type: &ItemType) -> bool {
+        match item_type {
+            ItemType::Weapon { .. } | ItemType::RangedWeapon { .. } => {
+                !matches!(self, AffixKind::Sturdy | AffixKind::Protection)
+            }
+            ItemType::Armor(_) => !matches!(self, AffixKind::Sharp | AffixKind::Might),
+            ItemType::Jewelry(_) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Represents an affix on an item.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Affix {
+    /// The kind of affix
+    pub kind: AffixKind,
+    /// How many times its base bonus the affix gives
+    pub power: i32,
+}
+
+impl Affix {
+    /// Gets the stats the affix adds.
+    pub fn bonus(&self) -> Stats {
+        let base = self.kind.bonus();
+        Stats::new(
+            base.health * self.power,
+            base.attack * self.power,
+            base.defense * self.power,
+            base.speed * self.power,
+        )
+    }
+}
+
+/// Describes a set of stats as a list of bonuses.
+///
+/// # Returns
+/// The non-zero stats, e.g. "+2 attack, +1 speed"
+pub fn describe_bonus(bonus: Stats) -> String {
+    [
+        (bonus.health, "health"),
+        (bonus.attack, "attack"),
+        (bonus.defense, "defense"),
+        (bonus.speed, "speed"),
+    ]
+    .iter()
+    .filter(|(value, _)| *value != 0)
+    .map(|(value, name)| format!("{:+} {}", value, name))
+    .collect::<Vec<_>>()
+    .join(", ")
+}
+
+/// Gives a piece of equipment a random rarity, random affixes and a
+/// slightly random base value. Other items are returned unchanged.
+///
+/// # Arguments
+/// * `item` - The item as created by its `create_` function
+/// * `level` - Loot level, the dungeon depth
+/// * `rng` - Random number generator
+///
+/// # Returns
+/// The item with its rarity, affixes and description filled in
+pub fn enchant(mut item: Item, level: i32, rng: &mut Rng) -> Item {
+    if !AffixKind::ALL
+        .iter()
+        .any(|kind| kind.suits(&item.item_type))
+    {
+        return item;
+    }
+
+    let spread = item.value / 5;
+    item.value += rng.range(-spread, spread + 1);
+    item.rarity = Rarity::roll(level, rng);
+    if matches!(item.item_type, ItemType::Jewelry(_)) {
+        item.rarity = item.rarity.max(Rarity::Uncommon);
+    }
+
+    let power = item.rarity.power();
+    let prefixes: Vec<AffixKind> = AffixKind::ALL
+        .into_iter()
+        .filter(|kind| kind.is_prefix() && kind.suits(&item.item_type))
+        .collect();
+    let suffixes: Vec<AffixKind> = AffixKind::ALL
+        .into_iter()
+        .filter(|kind| !kind.is_prefix() && kind.suits(&item.item_type))
+        .collect();
+    let pools = match item.rarity.affix_count() {
+        0 => vec![],
+        1 if rng.chance(50) => vec![prefixes],
+        1 => vec![suffixes],
+        _ => vec![prefixes, suffixes],
+    };
+    for pool in pools {
+        let kind = pool[rng.range(0, pool.len() as i32) as usize];
+        item.affixes.push(Affix { kind, power });
+    }
+
+    if item.rarity > Rarity::Common {
+        let bonus = describe_bonus(item.bonus());
+        item.description = format!("{} {}: {}.", item.description, item.rarity.name(), bonus);
+    }
+    item
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::equipment::Slot;
+
+    #[test]
+    fn test_enchant_names_and_sums_affixes() {
+        let mut sword = Item::create_sword();
+        sword.rarity = Rarity::Epic;
+        sword.affixes = vec![
+            Affix {
+                kind: AffixKind::Sharp,
+                power: 2,
+            },
+            Affix {
+                kind: AffixKind::Haste,
+                power: 2,
+            },
+        ];
+        assert_eq!(sword.display_name(), "Sharp Sword of Haste");
+        assert_eq!(describe_bonus(sword.bonus()), "+4 attack, +4 speed");
+
+        let mut rng = Rng::new(3);
+        let potion = enchant(Item::create_health_potion(), 20, &mut rng);
+        assert_eq!(potion.rarity, Rarity::Common);
+        for _ in 0..50 {
+            let armor = enchant(Item::create_chain_mail(), 20, &mut rng);
+            assert_eq!(armor.affixes.len(), armor.rarity.affix_count());
+            assert!((5..=7).contains(&armor.value));
+            assert!(armor
+                .affixes
+                .iter()
+                .all(|affix| affix.kind.suits(&ItemType::Armor(Slot::Body))));
+        }
+    }
+}
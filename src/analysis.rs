@@ -0,0 +1,257 @@
+//! Analysis module measuring the layout of a map.
+//!
+//! The measures work on the walkable graph of the map: floor, stairs and
+//! doors of any state, joined in straight steps. Level generation uses them
+//! to reject poor layouts, and `--map-report` prints them.
+
+use std::fmt;
+
+use crate::{grid::Grid, map::Tile, mapgen, Map};
+
+/// Straight steps between neighbouring tiles.
+const STEPS: [(i32, i32); 4] = [(0, -1), (0, 1), (-1, 0), (1, 0)];
+
+/// Represents the measures of a map layout.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    /// Number of separate walkable regions
+    pub components: usize,
+    /// Walkable tiles with a single walkable neighbour
+    pub dead_ends: Vec<(i32, i32)>,
+    /// Walkable tiles that split a region in two when blocked
+    pub chokepoints: Vec<(i32, i32)>,
+    /// For every room, the rooms reachable without crossing a third one
+    pub room_links: Vec<Vec<usize>>,
+    /// Steps from the spawn to the stairs, `None` if they cannot be reached
+    pub stairs_distance: Option<i32>,
+    /// Share of walkable tiles among all tiles, from 0.0 to 1.0
+    pub open_floor_ratio: f64,
+}
+
+/// Measures a map.
+///
+/// # Arguments
+/// * `map` - The map to measure
+/// * `spawn` - Where the player starts
+///
+/// # Returns
+/// A report with every measure of this module
+pub fn analyze(map: &Map, spawn: (i32, i32)) -> Report {
+    Report {
+        components: mapgen::regions(map).len(),
+        dead_ends: dead_ends(map),
+        chokepoints: chokepoints(map),
+        room_links: room_links(map),
+        stairs_distance: stairs_distance(map, spawn),
+        open_floor_ratio: open_floor_ratio(map),
+    }
+}
+
+/// Lists every walkable tile of a map.
+fn walkable_tiles(map: &Map) -> Vec<(i32, i32)> {
+    (0..map.height)
+        .flat_map(|y| (0..map.width).map(move |x| (x, y)))
+        .filter(|&(x, y)| mapgen::is_passable(map, x, y))
+        .collect()
+}
+
+/// Finds the dead ends of a map.
+///
+/// # Returns
+/// Every walkable tile with exactly one walkable neighbour
+pub fn dead_ends(map: &Map) -> Vec<(i32, i32)> {
+    walkable_tiles(map)
+        .into_iter()
+        .filter(|&(x, y)| {
+            STEPS
+                .iter()
+                .filter(|(dx, dy)| mapgen::is_passable(map, x + dx, y + dy))
+                .count()
+                == 1
+        })
+        .collect()
+}
+
+/// Finds the chokepoints of a map.
+///
+/// # Returns
+/// Every walkable tile whose removal splits its region, in row order
+pub fn chokepoints(map: &Map) -> Vec<(i32, i32)> {
+    let mut order = Grid::new(map.width, map.height, 0);
+    let mut low = Grid::new(map.width, map.height, 0);
+    let mut is_chokepoint = Grid::new(map.width, map.height, false);
+    let mut counter = 0;
+
+    for root in walkable_tiles(map) {
+        if order[root] != 0 {
+            continue;
+        }
+        counter += 1;
+        order[root] = counter;
+        low[root] = counter;
+        let mut root_children = 0;
+        // Depth-first search without recursion: tile, parent, next step
+        let mut stack = vec![(root, None, 0)];
+        while let Some(&mut (tile, parent, ref mut step)) = stack.last_mut() {
+            if *step < STEPS.len() {
+                let (dx, dy) = STEPS[*step];
+                *step += 1;
+                let next = (tile.0 + dx, tile.1 + dy);
+                if !mapgen::is_passable(map, next.0, next.1) || Some(next) == parent {
+                    continue;
+                }
+                if order[next] == 0 {
+                    counter += 1;
+                    order[next] = counter;
+                    low[next] = counter;
+                    if tile == root {
+                        root_children += 1;
+                    }
+                    stack.push((next, Some(tile), 0));
+                } else {
+                    low[tile] = low[tile].min(order[next]);
+                }
+                continue;
+            }
+
+            stack.pop();
+            if let Some(parent) = parent {
+                low[parent] = low[parent].min(low[tile]);
+                if parent != root && low[tile] >= order[parent] {
+                    is_chokepoint[parent] = true;
+                }
+            }
+        }
+        if root_children > 1 {
+            is_chokepoint[root] = true;
+        }
+    }
+
+    walkable_tiles(map)
+        .into_iter()
+        .filter(|&tile| is_chokepoint[tile])
+        .collect()
+}
+
+/// Builds the adjacency graph of the rooms of a map.
+///
+/// Two rooms are adjacent if one can walk from the inside of one to the
+/// inside of the other without entering a third room.
+///
+/// # Returns
+/// For every room in `map.rooms`, the indices of its adjacent rooms
+pub fn room_links(map: &Map) -> Vec<Vec<usize>> {
+    let room_of = |x: i32, y: i32| map.rooms.iter().position(|room| room.contains(x, y));
+    let mut links = Vec::new();
+
+    for (start, room) in map.rooms.iter().enumerate() {
+        let mut seen = Grid::new(map.width, map.height, false);
+        let mut stack: Vec<(i32, i32)> = (room.y..room.y + room.height)
+            .flat_map(|y| (room.x..room.x + room.width).map(move |x| (x, y)))
+            .filter(|&(x, y)| room.contains(x, y) && mapgen::is_passable(map, x, y))
+            .collect();
+        for &position in &stack {
+            seen[position] = true;
+        }
+
+        let mut adjacent = Vec::new();
+        while let Some((x, y)) = stack.pop() {
+            for (dx, dy) in STEPS {
+                let (nx, ny) = (x + dx, y + dy);
+                if !mapgen::is_passable(map, nx, ny) || seen[(nx, ny)] {
+                    continue;
+                }
+                seen[(nx, ny)] = true;
+                match room_of(nx, ny) {
+                    Some(other) if other != start => {
+                        if !adjacent.contains(&other) {
+                            adjacent.push(other);
+                        }
+                    }
+                    _ => stack.push((nx, ny)),
+                }
+            }
+        }
+        adjacent.sort_unstable();
+        links.push(adjacent);
+    }
+    links
+}
+
+/// Measures how far the stairs are from the spawn.
+///
+/// # Returns
+/// * `Some(i32)` steps to the closest stairs
+/// * `None` if the map has no stairs or they cannot be reached
+pub fn stairs_distance(map: &Map, spawn: (i32, i32)) -> Option<i32> {
+    let steps = mapgen::distances(map, spawn);
+    walkable_tiles(map)
+        .into_iter()
+        .filter(|&(x, y)| map.get_tile(x, y) == Some(&Tile::StairsDown))
+        .filter_map(|position| steps[position])
+        .min()
+}
+
+/// Measures how much of a map is walkable.
+///
+/// # Returns
+/// The share of walkable tiles among all tiles, 0.0 for an empty map
+pub fn open_floor_ratio(map: &Map) -> f64 {
+    let total = map.width * map.height;
+    if total <= 0 {
+        return 0.0;
+    }
+    walkable_tiles(map).len() as f64 / total as f64
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let distance = self
+            .stairs_distance
+            .map_or("unreachable".to_string(), |steps| {
+                format!("{} steps", steps)
+            });
+        writeln!(f, "Regions:         {}", self.components)?;
+        writeln!(f, "Dead ends:       {}", self.dead_ends.len())?;
+        writeln!(f, "Chokepoints:     {}", self.chokepoints.len())?;
+        writeln!(f, "Spawn to stairs: {}", distance)?;
+        writeln!(f, "Open floor:      {:.0}%", self.open_floor_ratio * 100.0)?;
+        for (room, links) in self.room_links.iter().enumerate() {
+            let links: Vec<String> = links.iter().map(|other| other.to_string()).collect();
+            writeln!(f, "Room {} links:    {}", room, links.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Room;
+
+    #[test]
+    fn test_analyze_small_map() {
+        let mut map = Map::from_ascii(
+            "#####   #####\n\
+             #...#####...#\n\
+             #...+.......#\n\
+             #...#####.>.#\n\
+             #####   #####\n\
+             \x20          \n\
+             #..#",
+        )
+        .unwrap();
+        map.rooms = vec![Room::at(0, 0, 5, 5), Room::at(8, 0, 5, 5)];
+
+        let report = analyze(&map, (2, 2));
+        assert_eq!(report.components, 2);
+        assert_eq!(report.stairs_distance, Some(9));
+        assert_eq!(report.room_links, vec![vec![1], vec![0]]);
+        assert_eq!(report.dead_ends, vec![(1, 6), (2, 6)]);
+        assert_eq!(
+            report.chokepoints,
+            vec![(3, 2), (4, 2), (5, 2), (6, 2), (7, 2), (8, 2), (9, 2)]
+        );
+        assert!((report.open_floor_ratio - 25.0 / 91.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,355 @@
+//! Backend module with the devices the UI draws on and reads input from.
+//!
+//! The UI draws single cells through the [`Backend`] trait. The
+//! [`AnsiBackend`] writes them to the terminal with ANSI escape codes, the
+//! [`MemoryBackend`] keeps them in a grid so screens can be checked as text.
+
+use std::{collections::VecDeque, io::Write, time::Duration};
+
+use crate::utils::get_terminal_size_or_default;
+
+/// Represents the colour a cell is drawn in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Color {
+    /// The terminal's default colour
+    #[default]
+    Default,
+    /// Bold white, used for the player
+    White,
+    /// Red, used for monsters
+    Red,
+    /// Bright yellow, used for items
+    BrightYellow,
+    /// Yellow, used for chests and doors
+    Yellow,
+    /// Magenta, used for traps
+    Magenta,
+    /// Cyan, used for stairs
+    Cyan,
+    /// Dark gray, used for the floor
+    Gray,
+}
+
+impl Color {
+    /// Gets the ANSI code selecting the colour.
+    ///
+    /// # Returns
+    /// The code, empty for the default colour
+    pub fn ansi_code(&self) -> &'static str {
+        match self {
+            Color::Default => "",
+            Color::White => "1;37",
+            Color::Red => "31",
+            Color::BrightYellow => "93",
+            Color::Yellow => "33",
+            Color::Magenta => "35",
+            Color::Cyan => "36",
+            Color::Gray => "90",
+        }
+    }
+}
+
+/// Represents one character on the screen with its looks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cell {
+    /// The character shown
+    pub symbol: char,
+    /// Colour of the character
+    pub color: Color,
+    /// Whether foreground and background are swapped, e.g. for a cursor
+    pub reversed: bool,
+    /// Whether the character is drawn faint, e.g. for poorly lit tiles
+    pub dim: bool,
+}
+
+impl Cell {
+    /// Creates a cell in the default colour.
+    ///
+    /// # Arguments
+    /// * `symbol` - The character shown
+    ///
+    /// # Returns
+    /// A new Cell that is neither reversed nor dim
+    pub fn new(symbol: char) -> Self {
+        Cell {
+            symbol,
+            color: Color::Default,
+            reversed: false,
+            dim: false,
+        }
+    }
+}
+
+/// Trait for devices the UI draws on and reads input from.
+///
+/// Positions are 0-based columns and rows from the top left corner.
+pub trait Backend {
+    /// Gets the size of the screen.
+    ///
+    /// # Returns
+    /// A `(width, height)` pair in characters
+    fn size(&self) -> (u16, u16);
+
+    /// Draws a cell, cells outside the screen are ignored.
+    ///
+    /// # Arguments
+    /// * `x` - Column of the cell
+    /// * `y` - Row of the cell
+    /// * `cell` - The cell to draw
+    fn draw_cell(&mut self, x: u16, y: u16, cell: Cell);
+
+    /// Moves the input cursor.
+    ///
+    /// # Arguments
+    /// * `x` - Column of the cursor
+    /// * `y` - Row of the cursor
+    fn set_cursor(&mut self, x: u16, y: u16);
+
+    /// Shows everything drawn since the last flush.
+    fn flush(&mut self);
+
+    /// Waits for the player to type a line.
+    ///
+    /// # Returns
+    /// The trimmed line, empty if no more input is available
+    fn read_key(&mut self) -> String;
+
+    /// Waits for the player to type a line, but no longer than a timeout.
+    ///
+    /// # Arguments
+    /// * `timeout` - How long to wait at most
+    ///
+    /// # Returns
+    /// * `Some(String)` with the trimmed line if one was typed in time
+    /// * `None` otherwise
+    fn poll_key(&mut self, timeout: Duration) -> Option<String>;
+
+    /// Waits so the player can read the screen.
+    ///
+    /// # Arguments
+    /// * `duration` - How long to wait
+    fn sleep(&mut self, duration: Duration);
+}
+
+/// Backend drawing on the terminal with ANSI escape codes.
+///
+/// Output is collected and written to stdout in one go on every flush.
+pub struct AnsiBackend {
+    width: u16,
+    height: u16,
+    /// Escape codes and characters not written yet
+    buffer: String,
+    /// Position the terminal cursor is at after the buffered output
+    cursor: Option<(u16, u16)>,
+}
+
+impl AnsiBackend {
+    /// Creates a backend for the current terminal.
+    ///
+    /// # Returns
+    /// A new AnsiBackend sized to the terminal, or 80x24 if the size cannot
+    /// be determined, e.g. when the output is not a terminal
+    pub fn new() -> Self {
+        let (width, height) = get_terminal_size_or_default();
+        AnsiBackend {
+            width,
+            height,
+            buffer: String::new(),
+            cursor: None,
+        }
+    }
+}
+
+impl Default for AnsiBackend {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Backend for AnsiBackend {
+    fn size(&self) -> (u16, u16) {
+        (self.width, self.height)
+    }
+
+    fn draw_cell(&mut self, x: u16, y: u16, cell: Cell) {
+        if x >= self.width || y >= self.height {
+            return;
+        }
+        if self.cursor != Some((x, y)) {
+            self.buffer.push_str(&format!("\x1B[{};{}H", y + 1, x + 1));
+        }
+
+        let code = cell.color.ansi_code();
+        let styled = cell.reversed || cell.dim || !code.is_empty();
+        if cell.reversed {
+            self.buffer.push_str("\x1B[7m");
+        }
+        if cell.dim {
+            self.buffer.push_str("\x1B[2m");
+        }
+        if !code.is_empty() {
+            self.buffer.push_str(&format!("\x1B[{}m", code));
+        }
+        self.buffer.push(cell.symbol);
+        if styled {
+            self.buffer.push_str("\x1B[0m");
+        }
+        self.cursor = Some((x + 1, y));
+    }
+
+    fn set_cursor(&mut self, x: u16, y: u16) {
+        self.buffer.push_str(&format!("\x1B[{};{}H", y + 1, x + 1));
+        self.cursor = Some((x, y));
+    }
+
+    fn flush(&mut self) {
+        let mut stdout = std::io::stdout();
+        let _ = stdout.write_all(self.buffer.as_bytes());
+        let _ = stdout.flush();
+        self.buffer.clear();
+    }
+
+    fn read_key(&mut self) -> String {
+        self.flush();
+        let mut input = String::new();
+        let _ = std::io::stdin().read_line(&mut input);
+        // The typed line is echoed, so the cursor position is unknown now.
+        self.cursor = None;
+        input.trim().to_string()
+    }
+
+    fn poll_key(&mut self, timeout: Duration) -> Option<String> {
+        self.flush();
+        #[cfg(unix)]
+        {
+            let mut stdin = libc::pollfd {
+                fd: libc::STDIN_FILENO,
+                events: libc::POLLIN,
+                revents: 0,
+            };
+            let millis = timeout.as_millis().min(i32::MAX as u128) as i32;
+            if unsafe { libc::poll(&mut stdin, 1, millis) } > 0 {
+                return Some(self.read_key());
+            }
+            None
+        }
+        #[cfg(not(unix))]
+        {
+            std::thread::sleep(timeout);
+            None
+        }
+    }
+
+    fn sleep(&mut self, duration: Duration) {
+        self.flush();
+        std::thread::sleep(duration);
+    }
+}
+
+impl Drop for AnsiBackend {
+    /// Clears the screen and restores cursor visibility.
+    fn drop(&mut self) {
+        self.buffer.push_str("\x1B[2J\x1B[H\x1B[?25h");
+        self.flush();
+    }
+}
+
+/// Backend keeping the screen in memory, for tests and headless runs.
+#[derive(Debug, Clone)]
+pub struct MemoryBackend {
+    width: u16,
+    height: u16,
+    /// All cells, row by row
+    cells: Vec<Cell>,
+    /// Position of the input cursor
+    pub cursor: (u16, u16),
+    /// Lines returned by `read_key`, oldest first
+    input: VecDeque<String>,
+}
+
+impl MemoryBackend {
+    /// Creates an empty screen.
+    ///
+    /// # Arguments
+    /// * `width` - Width of the screen in characters
+    /// * `height` - Height of the screen in characters
+    ///
+    /// # Returns
+    /// A new MemoryBackend filled with spaces
+    pub fn new(width: u16, height: u16) -> Self {
+        MemoryBackend {
+            width,
+            height,
+            cells: vec![Cell::new(' '); width as usize * height as usize],
+            cursor: (0, 0),
+            input: VecDeque::new(),
+        }
+    }
+
+    /// Queues a line for `read_key` to return.
+    ///
+    /// # Arguments
+    /// * `line` - The line the player types
+    pub fn push_input(&mut self, line: &str) {
+        self.input.push_back(line.to_string());
+    }
+
+    /// Gets the cell at a position.
+    ///
+    /// # Returns
+    /// * `Some(Cell)` if the position is on the screen
+    /// * `None` otherwise
+    pub fn cell(&self, x: u16, y: u16) -> Option<Cell> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        Some(self.cells[y as usize * self.width as usize + x as usize])
+    }
+
+    /// Gets the characters of one row, without trailing spaces.
+    pub fn row(&self, y: u16) -> String {
+        let start = y as usize * self.width as usize;
+        self.cells[start..start + self.width as usize]
+            .iter()
+            .map(|cell| cell.symbol)
+            .collect::<String>()
+            .trim_end()
+            .to_string()
+    }
+
+    /// Gets the whole screen as text, one line per row.
+    pub fn text(&self) -> String {
+        (0..self.height)
+            .map(|y| self.row(y))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl Backend for MemoryBackend {
+    fn size(&self) -> (u16, u16) {
+        (self.width, self.height)
+    }
+
+    fn draw_cell(&mut self, x: u16, y: u16, cell: Cell) {
+        if x < self.width && y < self.height {
+            self.cells[y as usize * self.width as usize + x as usize] = cell;
+        }
+    }
+
+    fn set_cursor(&mut self, x: u16, y: u16) {
+        self.cursor = (x, y);
+    }
+
+    fn flush(&mut self) {}
+
+    fn read_key(&mut self) -> String {
+        self.input.pop_front().unwrap_or_default()
+    }
+
+    fn poll_key(&mut self, _timeout: Duration) -> Option<String> {
+        self.input.pop_front()
+    }
+
+    fn sleep(&mut self, _duration: Duration) {}
+}
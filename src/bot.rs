@@ -0,0 +1,168 @@
+//! Bot module with a simple scripted player for headless runs.
+//!
+//! The bot only looks at the [`Observation`] a real player would see: it
+//! fights adjacent monsters, walks to the stairs once it has found them and
+//! otherwise explores the edge of the known map and every item it has seen,
+//! since items drawn on the stairs hide them.
+
+use std::collections::VecDeque;
+
+use crate::{
+    command::{Command, Direction},
+    engine::{Event, Observation},
+    game::{Game, GameState},
+};
+
+/// Represents the memory of the bot between two commands.
+#[derive(Debug, Clone, Default)]
+pub struct Bot {
+    /// Depth the memory belongs to
+    depth: i32,
+    /// Position of the stairs down, once seen
+    stairs: Option<(i32, i32)>,
+    /// Positions the bot has stood on
+    visited: Vec<(i32, i32)>,
+    /// Positions items were seen at, they may hide the stairs
+    items: Vec<(i32, i32)>,
+    /// Item positions already checked for stairs
+    probed: Vec<(i32, i32)>,
+}
+
+/// Map symbols of items lying on the floor.
+const ITEM_GLYPHS: [char; 10] = [')', '}', '/', '[', '!', '?', '-', '(', '%', '"'];
+
+impl Bot {
+    /// Creates a bot that knows nothing about the dungeon yet.
+    pub fn new() -> Self {
+        Bot::default()
+    }
+
+    /// Picks the next command for the situation the player is in.
+    ///
+    /// # Arguments
+    /// * `obs` - What the player knows about the game
+    ///
+    /// # Returns
+    /// The command to apply, `Wait` if the bot does not know what to do
+    pub fn next_command(&mut self, obs: &Observation) -> Command {
+        if obs.depth != self.depth {
+            *self = Bot {
+                depth: obs.depth,
+                ..Bot::default()
+            };
+        }
+        self.stairs = obs.find('>').or(self.stairs);
+        self.visited.push(obs.position);
+        for (y, row) in obs.map.iter().enumerate() {
+            for (x, glyph) in row.chars().enumerate() {
+                let position = (x as i32, y as i32);
+                if ITEM_GLYPHS.contains(&glyph) && !self.items.contains(&position) {
+                    self.items.push(position);
+                }
+            }
+        }
+
+        let (px, py) = obs.position;
+        if let Some(monster) = obs
+            .monsters
+            .iter()
+            .find(|m| (m.x - px).abs() <= 1 && (m.y - py).abs() <= 1)
+        {
+            let delta = (monster.x - px, monster.y - py);
+            let direction = Direction::ALL.into_iter().find(|d| d.delta() == delta);
+            return direction.map_or(Command::Wait, Command::Move);
+        }
+        if self.stairs == Some(obs.position) {
+            return Command::Descend;
+        }
+        if self.items.contains(&obs.position) && !self.probed.contains(&obs.position) {
+            self.probed.push(obs.position);
+            // The item may hide the stairs, descending elsewhere costs no turn.
+            return Command::Descend;
+        }
+
+        // Stairs seen through a gap may not be reachable over known tiles yet,
+        // and known traps are only crossed if there is no other way.
+        let stairs = |x: i32, y: i32| Some((x, y)) == self.stairs;
+        let frontier = |x: i32, y: i32| {
+            (self.items.contains(&(x, y)) && !self.probed.contains(&(x, y)))
+                || (!self.visited.contains(&(x, y))
+                    && Direction::ALL.into_iter().any(|d| {
+                        let (dx, dy) = d.delta();
+                        obs.glyph_at(x + dx, y + dy) == Some(' ')
+                    }))
+        };
+        let step = [true, false].into_iter().find_map(|avoid_traps| {
+            step_towards(obs, avoid_traps, stairs)
+                .or_else(|| step_towards(obs, avoid_traps, frontier))
+        });
+        step.map_or(Command::Wait, Command::Move)
+    }
+}
+
+/// Lets a bot play until the game ends or a number of turns is played.
+///
+/// The bot gets ten commands per turn at most, so a bot that keeps trying
+/// something impossible cannot loop forever.
+///
+/// # Arguments
+/// * `game` - The game to play
+/// * `bot` - The bot choosing the commands
+/// * `turns` - Number of turns to play
+///
+/// # Returns
+/// Every event of the run, in order
+pub fn play(game: &mut Game, bot: &mut Bot, turns: u32) -> Vec<Event> {
+    let mut events = Vec::new();
+    let last_turn = game.turns.saturating_add(turns);
+
+    for _ in 0..turns.saturating_mul(10) {
+        if game.state != GameState::Running || game.turns >= last_turn {
+            break;
+        }
+        let command = bot.next_command(&game.observe());
+        events.extend(game.apply(command));
+    }
+    events
+}
+
+/// Finds the first step of a shortest walk to a tile matching the goal.
+///
+/// Walls, braziers, blank tiles and chests are avoided.
+///
+/// # Arguments
+/// * `obs` - What the player knows about the game
+/// * `avoid_traps` - Whether known traps are avoided as well
+/// * `goal` - Checks whether a tile is a goal
+///
+/// # Returns
+/// * `Some(Direction)` of the first step
+/// * `None` if no matching tile can be reached
+fn step_towards(
+    obs: &Observation,
+    avoid_traps: bool,
+    goal: impl Fn(i32, i32) -> bool,
+) -> Option<Direction> {
+    let passable = |x: i32, y: i32| match obs.glyph_at(x, y) {
+        None | Some('#' | '*' | ' ' | '=') => false,
+        Some('^') => !avoid_traps,
+        Some(_) => true,
+    };
+    let mut seen = vec![obs.position];
+    let mut queue = VecDeque::from([(obs.position, None)]);
+
+    while let Some(((x, y), first)) = queue.pop_front() {
+        if first.is_some() && goal(x, y) {
+            return first;
+        }
+        for direction in Direction::ALL {
+            let (dx, dy) = direction.delta();
+            let next = (x + dx, y + dy);
+            if passable(next.0, next.1) && !seen.contains(&next) {
+                seen.push(next);
+                queue.push_back((next, first.or(Some(direction))));
+            }
+        }
+    }
+    None
+}
@@ -0,0 +1,263 @@
+//! Character module defining classes and backgrounds for character creation.
+
+use crate::{spell::SpellKind, Item};
+
+/// Represents a set of the four core character attributes.
+///
+/// Used both for starting values and for the gain per level.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    /// Health points
+    pub health: i32,
+    /// Attack power
+    pub attack: i32,
+    /// Defense value
+    pub defense: i32,
+    /// Movement and action speed
+    pub speed: i32,
+}
+
+impl Stats {
+    /// Creates a new set of stats.
+    pub fn new(health: i32, attack: i32, defense: i32, speed: i32) -> Self {
+        Stats {
+            health,
+            attack,
+            defense,
+            speed,
+        }
+    }
+
+    /// Adds two sets of stats together.
+    ///
+    /// # Returns
+    /// A new Stats instance holding the sum of both
+    pub fn add(&self, other: Stats) -> Stats {
+        Stats::new(
+            self.health + other.health,
+            self.attack + other.attack,
+            self.defense + other.defense,
+            self.speed + other.speed,
+        )
+    }
+
+    /// Subtracts one set of stats from another.
+    ///
+    /// # Returns
+    /// A new Stats instance holding the difference
+    pub fn sub(&self, other: Stats) -> Stats {
+        Stats::new(
+            self.health - other.health,
+            self.attack - other.attack,
+            self.defense - other.defense,
+            self.speed - other.speed,
+        )
+    }
+}
+
+/// Represents the character classes available at character creation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Class {
+    /// Sturdy melee fighter
+    Warrior,
+    /// Fast and evasive skirmisher
+    Rogue,
+    /// Frail scholar of the arcane
+    Mage,
+}
+
+impl Class {
+    /// All classes in the order they are offered to the player.
+    pub const ALL: [Class; 3] = [Class::Warrior, Class::Rogue, Class::Mage];
+
+    /// Gets the display name of the class.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Class::Warrior => "Warrior",
+            Class::Rogue => "Rogue",
+            Class::Mage => "Mage",
+        }
+    }
+
+    /// Gets a short description of the class.
+    pub fn description(&self) -> &'static str {
+        match self {
+            Class::Warrior => "Strong and tough, starts with sword and armor.",
+            Class::Rogue => "Quick on their feet, starts with dagger and bow.",
+            Class::Mage => "Weak in melee, casts spells and carries potions.",
+        }
+    }
+
+    /// Gets the starting stats of the class.
+    pub fn base_stats(&self) -> Stats {
+        match self {
+            Class::Warrior => Stats::new(100, 10, 10, 10),
+            Class::Rogue => Stats::new(85, 9, 8, 14),
+            Class::Mage => Stats::new(70, 7, 7, 11),
+        }
+    }
+
+    /// Gets the stats gained on every level up.
+    pub fn growth(&self) -> Stats {
+        match self {
+            Class::Warrior => Stats::new(10, 2, 2, 2),
+            Class::Rogue => Stats::new(8, 2, 1, 3),
+            Class::Mage => Stats::new(6, 1, 1, 2),
+        }
+    }
+
+    /// Gets the mana pool of the class at level 1.
+    pub fn base_mana(&self) -> i32 {
+        match self {
+            Class::Warrior => 10,
+            Class::Rogue => 20,
+            Class::Mage => 50,
+        }
+    }
+
+    /// Gets the maximum mana gained on every level up.
+    pub fn mana_growth(&self) -> i32 {
+        match self {
+            Class::Warrior => 1,
+            Class::Rogue => 3,
+            Class::Mage => 8,
+        }
+    }
+
+    /// Gets the spells the class knows from the start.
+    pub fn starting_spells(&self) -> Vec<SpellKind> {
+        match self {
+            Class::Warrior => Vec::new(),
+            Class::Rogue => vec![SpellKind::Blink],
+            Class::Mage => vec![SpellKind::Bolt, SpellKind::Heal, SpellKind::Detect],
+        }
+    }
+
+    /// Gets the weapon the class starts with equipped.
+    pub fn starting_weapon(&self) -> Option<Item> {
+        match self {
+            Class::Warrior => Some(Item::create_sword()),
+            Class::Rogue | Class::Mage => Some(Item::create_dagger()),
+        }
+    }
+
+    /// Gets the armor the class starts with equipped.
+    pub fn starting_armor(&self) -> Option<Item> {
+        match self {
+            Class::Warrior | Class::Rogue => Some(Item::create_leather_armor()),
+            Class::Mage => None,
+        }
+    }
+
+    /// Gets the items the class starts with in the inventory.
+    pub fn starting_inventory(&self) -> Vec<Item> {
+        match self {
+            Class::Warrior => vec![Item::create_health_potion()],
+            Class::Rogue => vec![
+                Item::create_health_potion(),
+                Item::create_short_bow(),
+                Item::create_arrows(20),
+            ],
+            Class::Mage => vec![
+                Item::create_health_potion(),
+                Item::create_health_potion(),
+                Item::create_fire_potion(),
+                Item::create_poison_potion(),
+            ],
+        }
+    }
+}
+
+/// Represents the backgrounds available at character creation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Background {
+    /// No particular past, no bonuses
+    Wanderer,
+    /// Former soldier with extra defense
+    Soldier,
+    /// Noble with extra supplies
+    Noble,
+    /// Street urchin with extra speed
+    Urchin,
+}
+
+impl Background {
+    /// All backgrounds in the order they are offered to the player.
+    pub const ALL: [Background; 4] = [
+        Background::Wanderer,
+        Background::Soldier,
+        Background::Noble,
+        Background::Urchin,
+    ];
+
+    /// Gets the display name of the background.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Background::Wanderer => "Wanderer",
+            Background::Soldier => "Soldier",
+            Background::Noble => "Noble",
+            Background::Urchin => "Urchin",
+        }
+    }
+
+    /// Gets a short description of the background.
+    pub fn description(&self) -> &'static str {
+        match self {
+            Background::Wanderer => "No past to speak of. No bonuses.",
+            Background::Soldier => "+10 health, +2 defense.",
+            Background::Noble => "Carries a greater health potion.",
+            Background::Urchin => "+3 speed, +1 attack.",
+        }
+    }
+
+    /// Gets the stat bonus granted by the background.
+    pub fn bonus(&self) -> Stats {
+        match self {
+            Background::Wanderer | Background::Noble => Stats::new(0, 0, 0, 0),
+            Background::Soldier => Stats::new(10, 0, 2, 0),
+            Background::Urchin => Stats::new(0, 1, 0, 3),
+        }
+    }
+
+    /// Gets the extra items the background starts with.
+    pub fn starting_inventory(&self) -> Vec<Item> {
+        match self {
+            Background::Noble => vec![Item::create_greater_health_potion()],
+            _ => Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Player;
+
+    #[test]
+    fn test_creation_and_level_ups_set_every_stat() {
+        let mut player = Player::create("Ann".to_string(), Class::Rogue, Background::Urchin);
+        let stats = (
+            player.max_health,
+            player.attack,
+            player.defense,
+            player.speed,
+        );
+        assert_eq!(stats, (85, 16, 11, 17));
+        assert_eq!((player.health, player.max_mana), (85, 20));
+
+        player.gain_experience(210);
+        assert_eq!(player.level, 3);
+        assert_eq!(
+            (player.experience, player.experience_to_next_level),
+            (0, 121)
+        );
+        let stats = (
+            player.max_health,
+            player.attack,
+            player.defense,
+            player.speed,
+        );
+        assert_eq!(stats, (101, 20, 13, 23));
+        assert_eq!((player.health, player.max_mana, player.mana), (101, 26, 26));
+    }
+}
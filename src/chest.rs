@@ -0,0 +1,98 @@
+//! Chest module for lootable containers placed on the map.
+
+use crate::{loot::find_table, rng::Rng, Item};
+
+/// Represents a chest on the map.
+#[derive(Debug, Clone)]
+pub struct Chest {
+    /// X-coordinate on the map
+    pub x: i32,
+    /// Y-coordinate on the map
+    pub y: i32,
+    /// Id of the key needed to unlock the chest, `None` if unlocked
+    pub lock: Option<u32>,
+    /// Name of the loot table rolled when the chest is opened
+    pub loot_table: String,
+    /// Whether the chest has already been emptied
+    pub opened: bool,
+}
+
+impl Chest {
+    /// Creates a new unlocked chest.
+    ///
+    /// # Arguments
+    /// * `x` - The x-coordinate
+    /// * `y` - The y-coordinate
+    /// * `loot_table` - Name of the loot table for the contents
+    ///
+    /// # Returns
+    /// A new closed Chest instance
+    pub fn new(x: i32, y: i32, loot_table: String) -> Self {
+        Chest {
+            x,
+            y,
+            lock: None,
+            loot_table,
+            opened: false,
+        }
+    }
+
+    /// Creates a new chest that needs a key to open.
+    ///
+    /// # Arguments
+    /// * `x` - The x-coordinate
+    /// * `y` - The y-coordinate
+    /// * `loot_table` - Name of the loot table for the contents
+    /// * `key_id` - Id of the key that unlocks the chest
+    ///
+    /// # Returns
+    /// A new locked Chest instance
+    pub fn new_locked(x: i32, y: i32, loot_table: String, key_id: u32) -> Self {
+        Chest {
+            lock: Some(key_id),
+            ..Chest::new(x, y, loot_table)
+        }
+    }
+
+    /// Unlocks the chest.
+    ///
+    /// # Arguments
+    /// * `key_id` - Id of the key used on the lock
+    ///
+    /// # Returns
+    /// * `Ok(())` if the chest was unlocked
+    /// * `Err(String)` if the chest is not locked or the key does not fit
+    pub fn unlock(&mut self, key_id: u32) -> Result<(), String> {
+        match self.lock {
+            Some(id) if id == key_id => {
+                self.lock = None;
+                Ok(())
+            }
+            Some(_) => Err("The key does not fit.".to_string()),
+            None => Err("The chest is not locked.".to_string()),
+        }
+    }
+
+    /// Opens the chest and rolls its contents.
+    ///
+    /// # Arguments
+    /// * `depth` - Current dungeon depth used to scale the loot
+    /// * `rng` - Random number generator
+    ///
+    /// # Returns
+    /// * `Ok(Vec<Item>)` with the contents of the chest
+    /// * `Err(String)` if the chest is locked or already empty
+    pub fn open(&mut self, depth: i32, rng: &mut Rng) -> Result<Vec<Item>, String> {
+        if self.lock.is_some() {
+            return Err("The chest is locked.".to_string());
+        }
+        if self.opened {
+            return Err("The chest is empty.".to_string());
+        }
+
+        self.opened = true;
+        Ok(find_table(&self.loot_table)
+            .map(|table| table.roll(depth, rng))
+            .unwrap_or_default())
+    }
+}
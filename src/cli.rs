@@ -0,0 +1,229 @@
+//! CLI module parsing the command line arguments.
+//!
+//! Flags take their value either as the next argument (`--seed 42`) or after
+//! an equals sign (`--seed=42`).
+
+use crate::save::SAVE_SLOTS;
+
+/// Help text printed for `--help`.
+pub const USAGE: &str = "\
+Usage: kd-rusty-crawler [OPTIONS]
+
+Options:
+  --seed <N>          Start new games from this seed
+  --name <NAME>       Name new heroes without asking
+  --load <SLOT>       Load a saved game at once, 0 is the auto-save
+  --replay <FILE>     Play a recorded session back
+  --no-color          Draw without colours
+  --config <FILE>     Read and write options from this file
+  --headless <N>      Let a bot play N turns without drawing and print stats
+  --map-report <N>    Print the first N levels with an analysis of each
+  --export-map <FILE> Write the first level of a new game as JSON to FILE
+  --version           Print the version and exit
+  --help              Print this help and exit
+";
+
+/// Represents the settings given on the command line.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Args {
+    /// Seed for new games, a random one if `None`
+    pub seed: Option<u64>,
+    /// Name for new heroes, asked for if `None`
+    pub name: Option<String>,
+    /// Slot to load at start
+    pub load: Option<u32>,
+    /// Replay file to play back instead of playing
+    pub replay: Option<String>,
+    /// Whether colours are turned off regardless of the options
+    pub no_color: bool,
+    /// Options file to use instead of the default one
+    pub config: Option<String>,
+    /// Number of turns for a bot to play without the UI
+    pub headless: Option<u32>,
+    /// Number of levels to generate and analyze without the UI
+    pub map_report: Option<u32>,
+    /// File to write the first level of a new game to as JSON
+    pub export_map: Option<String>,
+}
+
+/// Represents what the program was asked to do.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    /// Run the game with the given settings
+    Run(Args),
+    /// Print the help text
+    Help,
+    /// Print the version
+    Version,
+}
+
+/// Parses the command line arguments, without the program name.
+///
+/// # Arguments
+/// * `args` - The arguments in the order given
+///
+/// # Returns
+/// * `Ok(Action)` to perform
+/// * `Err(String)` describing the first invalid argument
+pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Action, String> {
+    let mut parsed = Args::default();
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value)),
+            _ => (arg.clone(), None),
+        };
+        let mut value = || -> Result<String, String> {
+            match inline {
+                Some(value) => Ok(value.to_string()),
+                None => args.next().ok_or_else(|| format!("{} needs a value", flag)),
+            }
+        };
+
+        match flag.as_str() {
+            "--help" | "-h" => return Ok(Action::Help),
+            "--version" | "-V" => return Ok(Action::Version),
+            "--seed" => {
+                let seed = value()?;
+                parsed.seed = Some(
+                    seed.parse()
+                        .map_err(|_| format!("invalid seed '{}', expected a number", seed))?,
+                );
+            }
+            "--name" => {
+                let name = value()?;
+                if name.trim().is_empty() {
+                    return Err("--name needs a non-empty name".to_string());
+                }
+                parsed.name = Some(name);
+            }
+            "--load" => {
+                let slot = value()?;
+                parsed.load = Some(
+                    slot.parse()
+                        .ok()
+                        .filter(|slot| *slot <= SAVE_SLOTS)
+                        .ok_or_else(|| {
+                            format!("invalid slot '{}', expected 0-{}", slot, SAVE_SLOTS)
+                        })?,
+                );
+            }
+            "--replay" => parsed.replay = Some(value()?),
+            "--config" => parsed.config = Some(value()?),
+            "--headless" => {
+                let turns = value()?;
+                let invalid = || format!("invalid turn count '{}', expected 1 or more", turns);
+                parsed.headless = Some(
+                    turns
+                        .parse()
+                        .ok()
+                        .filter(|turns| *turns > 0)
+                        .ok_or_else(invalid)?,
+                );
+            }
+            "--map-report" => {
+                let levels = value()?;
+                let invalid = || format!("invalid level count '{}', expected 1 or more", levels);
+                parsed.map_report = Some(
+                    levels
+                        .parse()
+                        .ok()
+                        .filter(|levels| *levels > 0)
+                        .ok_or_else(invalid)?,
+                );
+            }
+            "--export-map" => parsed.export_map = Some(value()?),
+            "--no-color" if inline.is_none() => parsed.no_color = true,
+            "--no-color" => return Err("--no-color does not take a value".to_string()),
+            _ if flag.starts_with('-') => return Err(format!("unknown flag '{}'", flag)),
+            _ => return Err(format!("unexpected argument '{}'", arg)),
+        }
+    }
+
+    let modes = [
+        ("--load", parsed.load.is_some()),
+        ("--replay", parsed.replay.is_some()),
+        ("--headless", parsed.headless.is_some()),
+        ("--map-report", parsed.map_report.is_some()),
+        ("--export-map", parsed.export_map.is_some()),
+    ];
+    let given: Vec<&str> = modes
+        .iter()
+        .filter(|(_, given)| *given)
+        .map(|(flag, _)| *flag)
+        .collect();
+    if let [first, second, ..] = given[..] {
+        return Err(format!("{} cannot be combined with {}", first, second));
+    }
+    Ok(Action::Run(parsed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_str(line: &str) -> Result<Action, String> {
+        parse(line.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn test_parse_flags() {
+        assert_eq!(parse_str(""), Ok(Action::Run(Args::default())));
+        assert_eq!(parse_str("--load 2 --help"), Ok(Action::Help));
+        assert_eq!(
+            parse_str("--seed 42 --name=Ann --no-color --config my.cfg"),
+            Ok(Action::Run(Args {
+                seed: Some(42),
+                name: Some("Ann".to_string()),
+                no_color: true,
+                config: Some("my.cfg".to_string()),
+                ..Args::default()
+            }))
+        );
+        assert_eq!(
+            parse_str("--export-map=level.json --seed 7"),
+            Ok(Action::Run(Args {
+                seed: Some(7),
+                export_map: Some("level.json".to_string()),
+                ..Args::default()
+            }))
+        );
+        assert_eq!(
+            parse_str("--headless=500 --seed 7"),
+            Ok(Action::Run(Args {
+                seed: Some(7),
+                headless: Some(500),
+                ..Args::default()
+            }))
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            parse_str("--fast"),
+            Err("unknown flag '--fast'".to_string())
+        );
+        assert_eq!(parse_str("--seed"), Err("--seed needs a value".to_string()));
+        assert_eq!(
+            parse_str("--seed abc"),
+            Err("invalid seed 'abc', expected a number".to_string())
+        );
+        assert!(parse_str("--load 9").is_err());
+        assert!(parse_str("--headless 0").is_err());
+        assert_eq!(
+            parse_str("--headless 10 --map-report 2"),
+            Err("--headless cannot be combined with --map-report".to_string())
+        );
+        assert_eq!(
+            parse_str("--map-report 2 --export-map level.json"),
+            Err("--map-report cannot be combined with --export-map".to_string())
+        );
+        assert!(parse_str("save.txt").is_err());
+        assert_eq!(
+            parse_str("--replay a.replay --load 1"),
+            Err("--load cannot be combined with --replay".to_string())
+        );
+    }
+}
@@ -0,0 +1,254 @@
+//! Command module defining the actions the player can trigger with keys.
+//!
+//! Commands are bound to keys without their arguments, e.g. `Open(None)`.
+//! The UI fills the arguments in before the command is applied to the game
+//! with [`Game::apply`](crate::game::Game::apply).
+
+/// Represents one of the eight directions on the map.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// Up
+    North,
+    /// Down
+    South,
+    /// Left
+    West,
+    /// Right
+    East,
+    /// Up and left
+    NorthWest,
+    /// Up and right
+    NorthEast,
+    /// Down and left
+    SouthWest,
+    /// Down and right
+    SouthEast,
+}
+
+impl Direction {
+    /// All directions, straight ones first.
+    pub const ALL: [Direction; 8] = [
+        Direction::North,
+        Direction::South,
+        Direction::West,
+        Direction::East,
+        Direction::NorthWest,
+        Direction::NorthEast,
+        Direction::SouthWest,
+        Direction::SouthEast,
+    ];
+
+    /// Gets the step on the map for this direction.
+    ///
+    /// # Returns
+    /// A `(dx, dy)` pair with each part in `-1..=1`
+    pub fn delta(&self) -> (i32, i32) {
+        match self {
+            Direction::North => (0, -1),
+            Direction::South => (0, 1),
+            Direction::West => (-1, 0),
+            Direction::East => (1, 0),
+            Direction::NorthWest => (-1, -1),
+            Direction::NorthEast => (1, -1),
+            Direction::SouthWest => (-1, 1),
+            Direction::SouthEast => (1, 1),
+        }
+    }
+
+    /// Gets the name used for the direction in the config file.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Direction::North => "north",
+            Direction::South => "south",
+            Direction::West => "west",
+            Direction::East => "east",
+            Direction::NorthWest => "north_west",
+            Direction::NorthEast => "north_east",
+            Direction::SouthWest => "south_west",
+            Direction::SouthEast => "south_east",
+        }
+    }
+}
+
+/// Represents an action the player can bind to a key.
+///
+/// Commands that act on a direction or an inventory item read it from the
+/// word following the key, e.g. `o w` or `e 2`. Inventory and spell indices
+/// are 0-based. A missing target is replaced by the closest visible monster
+/// when the command is applied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Command {
+    /// Move, attack or open in a direction
+    Move(Direction),
+    /// Open a door or chest in a direction
+    Open(Option<Direction>),
+    /// Close a door in a direction
+    Close(Option<Direction>),
+    /// Unlock a door or chest in a direction
+    Unlock(Option<Direction>),
+    /// Pick up the items on the player's tile
+    PickUp,
+    /// Search the surroundings for hidden traps
+    Search,
+    /// Take the stairs down
+    Descend,
+    /// Equip an inventory item, or show the equipment screen
+    Equip(Option<usize>),
+    /// Use an inventory item, e.g. drink a potion or read a scroll at a target
+    Use(Option<usize>, Option<(i32, i32)>),
+    /// Throw an inventory item at a target
+    Throw(Option<usize>, Option<(i32, i32)>),
+    /// Fire the equipped ranged weapon at a target
+    Fire(Option<(i32, i32)>),
+    /// List the known spells, or cast one at a target
+    Cast(Option<usize>, Option<(i32, i32)>),
+    /// Move a cursor over the map to examine what is there
+    Look,
+    /// Let a turn pass
+    Wait,
+    /// Jump to the next target while aiming
+    NextTarget,
+    /// Open the in-game menu, or cancel aiming
+    Menu,
+    /// Show the list of commands
+    Help,
+}
+
+impl Command {
+    /// All commands in the order they are listed on the help screen.
+    pub const ALL: [Command; 24] = [
+        Command::Move(Direction::North),
+        Command::Move(Direction::South),
+        Command::Move(Direction::West),
+        Command::Move(Direction::East),
+        Command::Move(Direction::NorthWest),
+        Command::Move(Direction::NorthEast),
+        Command::Move(Direction::SouthWest),
+        Command::Move(Direction::SouthEast),
+        Command::Open(None),
+        Command::Close(None),
+        Command::Unlock(None),
+        Command::PickUp,
+        Command::Search,
+        Command::Descend,
+        Command::Equip(None),
+        Command::Use(None, None),
+        Command::Throw(None, None),
+        Command::Fire(None),
+        Command::Cast(None, None),
+        Command::Look,
+        Command::Wait,
+        Command::NextTarget,
+        Command::Menu,
+        Command::Help,
+    ];
+
+    /// Gets the name used for the command in the config file.
+    pub fn name(&self) -> String {
+        let name = match self {
+            Command::Move(direction) => return format!("move_{}", direction.name()),
+            Command::Open(_) => "open",
+            Command::Close(_) => "close",
+            Command::Unlock(_) => "unlock",
+            Command::PickUp => "pick_up",
+            Command::Search => "search",
+            Command::Descend => "descend",
+            Command::Equip(_) => "equip",
+            Command::Use(..) => "use",
+            Command::Throw(..) => "throw",
+            Command::Fire(_) => "fire",
+            Command::Cast(..) => "cast",
+            Command::Look => "look",
+            Command::Wait => "wait",
+            Command::NextTarget => "next_target",
+            Command::Menu => "menu",
+            Command::Help => "help",
+        };
+        name.to_string()
+    }
+
+    /// Finds a command by its config file name.
+    ///
+    /// # Returns
+    /// * `Some(Command)` if a command with this name exists
+    /// * `None` otherwise
+    pub fn from_name(name: &str) -> Option<Command> {
+        Command::ALL
+            .into_iter()
+            .find(|command| command.name() == name)
+    }
+
+    /// Gets the command without its arguments, as it is bound to a key.
+    pub fn unbound(&self) -> Command {
+        match self {
+            Command::Open(_) => Command::Open(None),
+            Command::Close(_) => Command::Close(None),
+            Command::Unlock(_) => Command::Unlock(None),
+            Command::Equip(_) => Command::Equip(None),
+            Command::Use(..) => Command::Use(None, None),
+            Command::Throw(..) => Command::Throw(None, None),
+            Command::Fire(_) => Command::Fire(None),
+            Command::Cast(..) => Command::Cast(None, None),
+            command => *command,
+        }
+    }
+
+    /// Gets the target of a command that is aimed.
+    ///
+    /// # Returns
+    /// * `Some((x, y))` if the command has a target
+    /// * `None` if it has none, or is never aimed
+    pub fn target(&self) -> Option<(i32, i32)> {
+        match *self {
+            Command::Use(_, target)
+            | Command::Throw(_, target)
+            | Command::Fire(target)
+            | Command::Cast(_, target) => target,
+            _ => None,
+        }
+    }
+
+    /// Sets the target of a command that is aimed.
+    ///
+    /// # Arguments
+    /// * `target` - Position the command is aimed at
+    ///
+    /// # Returns
+    /// The command with the target, commands that are never aimed unchanged
+    pub fn with_target(self, target: (i32, i32)) -> Command {
+        match self {
+            Command::Use(index, _) => Command::Use(index, Some(target)),
+            Command::Throw(index, _) => Command::Throw(index, Some(target)),
+            Command::Fire(_) => Command::Fire(Some(target)),
+            Command::Cast(index, _) => Command::Cast(index, Some(target)),
+            command => command,
+        }
+    }
+
+    /// Gets a short description of the command for the help screen.
+    pub fn description(&self) -> &'static str {
+        match self {
+            Command::Move(Direction::North) => "Move up, attack or open",
+            Command::Move(Direction::South) => "Move down, attack or open",
+            Command::Move(Direction::West) => "Move left, attack or open",
+            Command::Move(Direction::East) => "Move right, attack or open",
+            Command::Move(_) => "Move diagonally, attack or open",
+            Command::Open(_) => "Open a door or chest: <key> <dir>",
+            Command::Close(_) => "Close a door: <key> <dir>",
+            Command::Unlock(_) => "Unlock a door or chest: <key> <dir>",
+            Command::PickUp => "Pick up items",
+            Command::Search => "Search for hidden traps",
+            Command::Descend => "Descend the stairs",
+            Command::Equip(_) => "Show equipment, or equip item: <key> <n>",
+            Command::Use(..) => "Show inventory, or drink or read item: <key> <n>",
+            Command::Throw(..) => "Show inventory, or throw item: <key> <n>",
+            Command::Fire(_) => "Fire the ranged weapon",
+            Command::Cast(..) => "List spells, or cast one: <key> <n>",
+            Command::Look => "Look around with a cursor",
+            Command::Wait => "Wait a turn",
+            Command::NextTarget => "Next target while aiming",
+            Command::Menu => "Game menu, cancel aiming",
+            Command::Help => "Show this list",
+        }
+    }
+}
@@ -0,0 +1,303 @@
+//! Engine module with the headless interface to a running game.
+//!
+//! Bots, tests and the terminal frontend all drive a game the same way:
+//! they send a [`Command`] to [`Game::apply`] and get back the [`Event`]s it
+//! caused, and they read the state through an [`Observation`]. Nothing here
+//! touches the terminal.
+
+use crate::{
+    command::Command,
+    game::{Game, GameState, THROW_RANGE},
+    hunger::HungerState,
+    item::ScrollKind,
+    ItemType,
+};
+
+/// Represents something that happened while a command was applied.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    /// A message was added to the message log
+    Message(String),
+    /// The player moved to a new position
+    Moved { x: i32, y: i32 },
+    /// A projectile flew along a path, drawn with a glyph
+    Projectile { path: Vec<(i32, i32)>, glyph: char },
+    /// A monster died at a position
+    MonsterKilled { name: String, x: i32, y: i32 },
+    /// The player lost health
+    PlayerHurt { damage: i32, health: i32 },
+    /// The player arrived on a new level
+    LevelEntered { depth: i32 },
+    /// A turn was played, giving the number of turns played so far
+    TurnEnded { turn: u32 },
+    /// The player died
+    GameOver { cause: String },
+}
+
+/// Represents a monster as the player sees it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonsterView {
+    /// Name of the monster
+    pub name: String,
+    /// Symbol the monster is drawn with
+    pub glyph: char,
+    /// X-coordinate of the monster
+    pub x: i32,
+    /// Y-coordinate of the monster
+    pub y: i32,
+    /// Current health of the monster
+    pub health: i32,
+    /// Maximum health of the monster
+    pub max_health: i32,
+}
+
+/// Represents a snapshot of everything the player knows about the game.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Observation {
+    /// Current state of the game
+    pub state: GameState,
+    /// Current dungeon depth
+    pub depth: i32,
+    /// Number of turns played
+    pub turns: u32,
+    /// Number of monsters killed
+    pub kills: u32,
+    /// Position of the player
+    pub position: (i32, i32),
+    /// Current and maximum health of the player
+    pub health: (i32, i32),
+    /// Current and maximum mana of the player
+    pub mana: (i32, i32),
+    /// Experience level of the player
+    pub level: i32,
+    /// How hungry the player is
+    pub hunger: HungerState,
+    /// Names of the inventory items as the player knows them, in inventory
+    /// order
+    pub inventory: Vec<String>,
+    /// Monsters in the player's field of view
+    pub monsters: Vec<MonsterView>,
+    /// The map as drawn on screen, one string per row
+    pub map: Vec<String>,
+}
+
+impl Observation {
+    /// Gets the symbol drawn at a map position.
+    ///
+    /// # Returns
+    /// * `Some(char)` with the symbol, a space for unexplored tiles
+    /// * `None` if the position is outside the map
+    pub fn glyph_at(&self, x: i32, y: i32) -> Option<char> {
+        if x < 0 || y < 0 {
+            return None;
+        }
+        self.map.get(y as usize)?.chars().nth(x as usize)
+    }
+
+    /// Finds the first map position showing a symbol, row by row.
+    ///
+    /// # Returns
+    /// * `Some((x, y))` if the symbol is on the map
+    /// * `None` otherwise
+    pub fn find(&self, glyph: char) -> Option<(i32, i32)> {
+        self.map.iter().enumerate().find_map(|(y, row)| {
+            row.chars()
+                .position(|c| c == glyph)
+                .map(|x| (x as i32, y as i32))
+        })
+    }
+}
+
+impl Game {
+    /// Applies a player command and plays the turn if it used one.
+    ///
+    /// Aimed commands without a target are aimed at the closest visible
+    /// monster in range. Commands that only affect the UI, like `Menu`, do
+    /// nothing here. No commands are applied unless the game is running.
+    ///
+    /// # Arguments
+    /// * `command` - The command with its arguments
+    ///
+    /// # Returns
+    /// Everything that happened, in order. A `TurnEnded` event tells that the
+    /// command was accepted and the monsters have moved.
+    pub fn apply(&mut self, command: Command) -> Vec<Event> {
+        self.collect_events(|game| game.play(command))
+    }
+
+    /// Plays a command, see [`Game::apply`].
+    fn play(&mut self, command: Command) {
+        if self.state != GameState::Running {
+            return;
+        }
+
+        let mut command = command;
+        if command.target().is_none() {
+            if let Some(range) = self.target_range(command) {
+                match self.visible_targets(range).first() {
+                    Some(&target) => command = command.with_target(target),
+                    None => {
+                        self.log("There is nothing to aim at.");
+                        return;
+                    }
+                }
+            }
+        }
+
+        let turn_used = match command {
+            Command::Move(direction) => {
+                let (dx, dy) = direction.delta();
+                self.move_player(dx, dy)
+            }
+            Command::Open(Some(direction)) => {
+                let (dx, dy) = direction.delta();
+                self.open(dx, dy)
+            }
+            Command::Close(Some(direction)) => {
+                let (dx, dy) = direction.delta();
+                self.close_door(dx, dy)
+            }
+            Command::Unlock(Some(direction)) => {
+                let (dx, dy) = direction.delta();
+                self.unlock(dx, dy)
+            }
+            Command::Open(None) | Command::Close(None) | Command::Unlock(None) => {
+                self.log("Which direction?");
+                false
+            }
+            Command::PickUp => self.pick_up(),
+            Command::Search => self.search(),
+            Command::Descend => self.descend(),
+            Command::Equip(Some(index)) => self.equip(index),
+            Command::Use(Some(index), target) => match self.player.inventory.get(index) {
+                Some(item) if matches!(item.item_type, ItemType::Scroll(_)) => {
+                    let path = self.read_scroll(index, target);
+                    self.launched(path, '*')
+                }
+                _ => self.use_item(index),
+            },
+            Command::Throw(Some(index), target) => {
+                let target = target.unwrap_or((self.player.x, self.player.y));
+                let path = self.throw_at(index, target);
+                self.launched(path, '!')
+            }
+            Command::Equip(None) | Command::Use(None, _) | Command::Throw(None, _) => {
+                self.log("Which item?");
+                false
+            }
+            Command::Fire(target) => {
+                let target = target.unwrap_or((self.player.x, self.player.y));
+                let path = self.fire_at(target);
+                self.launched(path, '*')
+            }
+            Command::Cast(Some(index), target) => {
+                let path = self.cast_spell(index, target);
+                self.launched(path, '*')
+            }
+            Command::Cast(None, _) => {
+                self.list_spells();
+                false
+            }
+            Command::Wait => true,
+            Command::Look | Command::NextTarget | Command::Menu | Command::Help => false,
+        };
+
+        if turn_used && self.state == GameState::Running {
+            self.update();
+            self.emit(Event::TurnEnded { turn: self.turns });
+        }
+    }
+
+    /// Gets the range of an aimed command.
+    ///
+    /// Commands whose item or spell cannot be used, or does not need a
+    /// target, are not aimed.
+    ///
+    /// # Arguments
+    /// * `command` - The command to aim
+    ///
+    /// # Returns
+    /// * `Some(i32)` with the maximum distance in tiles to the target
+    /// * `None` if the command is not aimed
+    pub fn target_range(&self, command: Command) -> Option<i32> {
+        let item = |index: Option<usize>| index.and_then(|index| self.player.inventory.get(index));
+        match command {
+            Command::Fire(_) => self.player.ranged_weapon().map(|(_, range)| range),
+            Command::Throw(index, _) => item(index)
+                .filter(|item| item.is_throwable())
+                .map(|_| THROW_RANGE),
+            Command::Use(index, _) => match item(index).map(|item| &item.item_type) {
+                Some(&ItemType::Scroll(ScrollKind::Cast(kind))) if kind.spell().needs_target() => {
+                    Some(kind.spell().range)
+                }
+                _ => None,
+            },
+            Command::Cast(index, _) => index
+                .and_then(|index| self.player.spellbook.spells.get(index))
+                .filter(|known| known.spell.needs_target())
+                .map(|known| known.spell.range),
+            _ => None,
+        }
+    }
+
+    /// Takes a snapshot of what the player knows about the game.
+    ///
+    /// # Returns
+    /// An Observation with the player's stats, the visible monsters and the
+    /// map as drawn on screen
+    pub fn observe(&self) -> Observation {
+        let player = &self.player;
+        Observation {
+            state: self.state,
+            depth: self.depth,
+            turns: self.turns,
+            kills: self.kills,
+            position: (player.x, player.y),
+            health: (player.health, player.max_health),
+            mana: (player.mana, player.max_mana),
+            level: player.level,
+            hunger: player.hunger(),
+            inventory: player
+                .inventory
+                .iter()
+                .map(|i| self.identification.name(i))
+                .collect(),
+            monsters: self
+                .monsters
+                .iter()
+                .filter(|m| self.can_see_monster(m))
+                .map(|m| MonsterView {
+                    name: m.name.clone(),
+                    glyph: m.glyph,
+                    x: m.x,
+                    y: m.y,
+                    health: m.health,
+                    max_health: m.max_health,
+                })
+                .collect(),
+            map: (0..self.map.height)
+                .map(|y| (0..self.map.width).map(|x| self.symbol_at(x, y)).collect())
+                .collect(),
+        }
+    }
+
+    /// Records the flight path of a projectile.
+    ///
+    /// # Arguments
+    /// * `path` - The path returned by the action, `None` if it failed
+    /// * `glyph` - Symbol the projectile is drawn with
+    ///
+    /// # Returns
+    /// `true` if the action succeeded and used up the player's turn
+    fn launched(&mut self, path: Option<Vec<(i32, i32)>>, glyph: char) -> bool {
+        match path {
+            Some(path) => {
+                if !path.is_empty() {
+                    self.emit(Event::Projectile { path, glyph });
+                }
+                true
+            }
+            None => false,
+        }
+    }
+}
@@ -0,0 +1,208 @@
+//! Equipment module with the slots the player wears items in.
+//!
+//! Every wearable item declares the [`Slot`] it goes in, see
+//! [`Item::slot`]. Rings fit on either hand. A two-handed weapon takes the
+//! main hand and blocks the off hand while it is wielded.
+
+use crate::{character::Stats, Item, ItemType};
+
+/// Represents a place on the body an item can be worn in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Slot {
+    /// Helmets
+    Head,
+    /// Body armor
+    Body,
+    /// Gloves and gauntlets
+    Hands,
+    /// Boots
+    Feet,
+    /// Weapons
+    MainHand,
+    /// Shields
+    OffHand,
+    /// First ring
+    LeftRing,
+    /// Second ring
+    RightRing,
+    /// Amulets
+    Amulet,
+}
+
+impl Slot {
+    /// All slots in the order they are listed on the equipment screen.
+    pub const ALL: [Slot; 9] = [
+        Slot::Head,
+        Slot::Body,
+        Slot::Hands,
+        Slot::Feet,
+        Slot::MainHand,
+        Slot::OffHand,
+        Slot::LeftRing,
+        Slot::RightRing,
+        Slot::Amulet,
+    ];
+
+    /// Gets the display name of the slot.
+    ///
+    /// # Returns
+    /// The capitalized name of the slot
+    pub fn name(&self) -> &'static str {
+        match self {
+            Slot::Head => "Head",
+            Slot::Body => "Body",
+            Slot::Hands => "Hands",
+            Slot::Feet => "Feet",
+            Slot::MainHand => "Main hand",
+            Slot::OffHand => "Off hand",
+            Slot::LeftRing => "Left ring",
+            Slot::RightRing => "Right ring",
+            Slot::Amulet => "Amulet",
+        }
+    }
+}
+
+/// Represents the items the player wears, at most one per slot.
+#[derive(Debug, Clone, Default)]
+pub struct Equipment {
+    /// Worn items in the order of [`Slot::ALL`]
+    items: [Option<Item>; 9],
+}
+
+impl Equipment {
+    /// Gets the item worn in a slot.
+    pub fn get(&self, slot: Slot) -> Option<&Item> {
+        self.items[slot as usize].as_ref()
+    }
+
+    /// Puts an item into a slot without any checks.
+    ///
+    /// # Arguments
+    /// * `slot` - The slot to fill
+    /// * `item` - The item to wear, `None` to empty the slot
+    ///
+    /// # Returns
+    /// The item that was worn in the slot before, if any
+    pub fn set(&mut self, slot: Slot, item: Option<Item>) -> Option<Item> {
+        std::mem::replace(&mut self.items[slot as usize], item)
+    }
+
+    /// Lists the worn items.
+    ///
+    /// # Returns
+    /// Every filled slot with its item, in the order of [`Slot::ALL`]
+    pub fn iter(&self) -> impl Iterator<Item = (Slot, &Item)> {
+        Slot::ALL
+            .into_iter()
+            .zip(&self.items)
+            .filter_map(|(slot, item)| item.as_ref().map(|item| (slot, item)))
+    }
+
+    /// Finds the slot an item would be worn in.
+    ///
+    /// A ring goes on the right hand if only the left one is taken.
+    ///
+    /// # Returns
+    /// * `Ok(Slot)` with the slot the item fits in
+    /// * `Err(String)` if the item cannot be worn, or is a shield while a
+    ///   two-handed weapon is wielded
+    pub fn slot_for(&self, item: &Item) -> Result<Slot, String> {
+        let slot = item
+            .slot()
+            .ok_or_else(|| "This item cannot be used".to_string())?;
+        match slot {
+            Slot::OffHand => match self.get(Slot::MainHand) {
+                Some(weapon) if weapon.is_two_handed() => Err(format!(
+                    "You need both hands for the {}.",
+                    weapon.display_name()
+                )),
+                _ => Ok(slot),
+            },
+            Slot::LeftRing | Slot::RightRing => match self.get(Slot::LeftRing) {
+                Some(_) if self.get(Slot::RightRing).is_none() => Ok(Slot::RightRing),
+                _ => Ok(Slot::LeftRing),
+            },
+            _ => Ok(slot),
+        }
+    }
+
+    /// Wears an item in the slot it fits in.
+    ///
+    /// A two-handed weapon also frees the off hand.
+    ///
+    /// # Arguments
+    /// * `item` - The item to wear
+    ///
+    /// # Returns
+    /// * `Ok(Vec<Item>)` with the items taken off to make room
+    /// * `Err(String)` if the item cannot be worn, see [`Equipment::slot_for`]
+    pub fn equip(&mut self, item: Item) -> Result<Vec<Item>, String> {
+        let slot = self.slot_for(&item)?;
+        let mut removed = Vec::new();
+        if item.is_two_handed() {
+            removed.extend(self.set(Slot::OffHand, None));
+        }
+        removed.extend(self.set(slot, Some(item)));
+        Ok(removed)
+    }
+
+    /// Gets the stats all worn items add together: weapon damage to attack,
+    /// armor to defense, and the bonuses of their affixes.
+    pub fn bonus(&self) -> Stats {
+        self.iter().fold(Stats::new(0, 0, 0, 0), |sum, (_, item)| {
+            let base = match item.item_type {
+                ItemType::Weapon { .. } | ItemType::RangedWeapon { .. } => {
+                    Stats::new(0, item.value, 0, 0)
+                }
+                ItemType::Armor(_) => Stats::new(0, 0, item.value, 0),
+                _ => Stats::new(0, 0, 0, 0),
+            };
+            sum.add(base).add(item.bonus())
+        })
+    }
+
+    /// Works out how the stats would change by wearing an item.
+    ///
+    /// # Arguments
+    /// * `item` - The item to try on
+    ///
+    /// # Returns
+    /// * `Ok(Stats)` with the change, negative where the stats would drop
+    /// * `Err(String)` if the item cannot be worn
+    pub fn change(&self, item: &Item) -> Result<Stats, String> {
+        let mut after = self.clone();
+        after.equip(item.clone())?;
+        Ok(after.bonus().sub(self.bonus()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_two_handed_weapons_block_the_off_hand() {
+        let mut equipment = Equipment::default();
+        equipment.equip(Item::create_sword()).unwrap();
+        equipment.equip(Item::create_wooden_shield()).unwrap();
+        assert_eq!(equipment.bonus(), Stats::new(0, 10, 2, 0));
+
+        let axe = Item::create_battle_axe();
+        assert_eq!(equipment.change(&axe), Ok(Stats::new(0, 5, -2, 0)));
+        let removed = equipment.equip(axe).unwrap();
+        let names: Vec<&str> = removed.iter().map(|item| item.name.as_str()).collect();
+        assert_eq!(names, ["Wooden Shield", "Sword"]);
+        assert!(equipment
+            .slot_for(&Item::create_wooden_shield())
+            .unwrap_err()
+            .contains("both hands"));
+
+        assert_eq!(equipment.slot_for(&Item::create_ring()), Ok(Slot::LeftRing));
+        equipment.equip(Item::create_ring()).unwrap();
+        assert_eq!(
+            equipment.slot_for(&Item::create_ring()),
+            Ok(Slot::RightRing)
+        );
+        assert!(equipment.slot_for(&Item::create_apple()).is_err());
+    }
+}
@@ -0,0 +1,181 @@
+//! Export module writing maps and games as JSON for debugging.
+//!
+//! Tiles are written as one string per row with the glyphs of
+//! [`Tile::glyph`], so the output stays readable. Locked doors are listed
+//! separately with their key id. `--export-map` writes a new game with
+//! [`game_json`].
+
+use crate::{
+    map::{DoorState, Tile},
+    Game, Map,
+};
+
+/// Writes a map as a JSON object.
+///
+/// # Arguments
+/// * `map` - The map to export
+///
+/// # Returns
+/// An object with the size, tile rows, rooms and locked doors of the map
+pub fn map_json(map: &Map) -> String {
+    let rows: Vec<String> = map
+        .to_ascii()
+        .lines()
+        .map(|row| format!("    {}", quote(row)))
+        .collect();
+    let rooms: Vec<String> = map
+        .rooms
+        .iter()
+        .map(|room| {
+            format!(
+                "    {{\"x\": {}, \"y\": {}, \"width\": {}, \"height\": {}}}",
+                room.x, room.y, room.width, room.height
+            )
+        })
+        .collect();
+    let mut locks = Vec::new();
+    for y in 0..map.height {
+        for x in 0..map.width {
+            if let Some(Tile::Door(DoorState::Locked(key_id))) = map.get_tile(x, y) {
+                locks.push(format!(
+                    "    {{\"x\": {}, \"y\": {}, \"key\": {}}}",
+                    x, y, key_id
+                ));
+            }
+        }
+    }
+
+    format!(
+        "{{\n  \"width\": {},\n  \"height\": {},\n  \"tiles\": {},\n  \"rooms\": {},\n  \"locks\": {}\n}}",
+        map.width,
+        map.height,
+        array(&rows),
+        array(&rooms),
+        array(&locks)
+    )
+}
+
+/// Writes a game as a JSON object.
+///
+/// # Arguments
+/// * `game` - The game to export
+///
+/// # Returns
+/// An object with the depth, the map as written by [`map_json`], the
+/// player and every monster, item, chest and trap on the level
+pub fn game_json(game: &Game) -> String {
+    let player = &game.player;
+    let monsters: Vec<String> = game
+        .monsters
+        .iter()
+        .map(|monster| {
+            format!(
+                "    {{\"name\": {}, \"glyph\": {}, \"x\": {}, \"y\": {}, \"health\": {}, \"alerted\": {}}}",
+                quote(&monster.name),
+                quote(&monster.glyph.to_string()),
+                monster.x,
+                monster.y,
+                monster.health,
+                monster.alerted
+            )
+        })
+        .collect();
+    let items: Vec<String> = game
+        .items
+        .iter()
+        .map(|map_item| {
+            format!(
+                "    {{\"name\": {}, \"x\": {}, \"y\": {}}}",
+                quote(&map_item.item.display_name()),
+                map_item.x,
+                map_item.y
+            )
+        })
+        .collect();
+    let chests: Vec<String> = game
+        .chests
+        .iter()
+        .map(|chest| {
+            format!(
+                "    {{\"x\": {}, \"y\": {}, \"loot_table\": {}, \"locked\": {}, \"opened\": {}}}",
+                chest.x,
+                chest.y,
+                quote(&chest.loot_table),
+                chest.lock.is_some(),
+                chest.opened
+            )
+        })
+        .collect();
+    let traps: Vec<String> = game
+        .traps
+        .iter()
+        .map(|trap| {
+            format!(
+                "    {{\"kind\": {}, \"x\": {}, \"y\": {}, \"hidden\": {}}}",
+                quote(trap.kind.name()),
+                trap.x,
+                trap.y,
+                trap.hidden
+            )
+        })
+        .collect();
+
+    format!(
+        "{{\n  \"depth\": {},\n  \"map\": {},\n  \"player\": {{\"name\": {}, \"x\": {}, \"y\": {}, \"health\": {}}},\n  \"monsters\": {},\n  \"items\": {},\n  \"chests\": {},\n  \"traps\": {}\n}}\n",
+        game.depth,
+        map_json(&game.map).replace('\n', "\n  "),
+        quote(&player.name),
+        player.x,
+        player.y,
+        player.health,
+        array(&monsters),
+        array(&items),
+        array(&chests),
+        array(&traps)
+    )
+}
+
+/// Joins already indented values into a JSON array, one value per line.
+fn array(values: &[String]) -> String {
+    if values.is_empty() {
+        "[]".to_string()
+    } else {
+        format!("[\n{}\n  ]", values.join(",\n"))
+    }
+}
+
+/// Quotes a string for JSON, escaping quotes, backslashes and control
+/// characters.
+fn quote(text: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Room;
+
+    #[test]
+    fn test_map_json_lists_tiles_rooms_and_locks() {
+        let mut map = Map::from_ascii("#####\n#..'#\n#####").unwrap();
+        map.rooms.push(Room::at(0, 0, 5, 3));
+        map.set_tile(4, 1, Tile::Door(DoorState::Locked(7)));
+
+        let json = map_json(&map);
+        assert!(json.contains("\"tiles\": [\n    \"#####\",\n    \"#..'+\",\n    \"#####\"\n  ]"));
+        assert!(json.contains("{\"x\": 0, \"y\": 0, \"width\": 5, \"height\": 3}"));
+        assert!(json.contains("{\"x\": 4, \"y\": 1, \"key\": 7}"));
+        assert_eq!(quote("a \"b\"\\\n"), "\"a \\\"b\\\"\\\\\\n\"");
+    }
+}
@@ -0,0 +1,156 @@
+//! Line of sight module for tracing lines across the map.
+
+use crate::{light::LightLevel, Map};
+
+/// Traces a straight line between two points using Bresenham's algorithm.
+///
+/// # Arguments
+/// * `from` - Start point (x, y)
+/// * `to` - End point (x, y)
+///
+/// # Returns
+/// All points on the line, including both end points
+pub fn line(from: (i32, i32), to: (i32, i32)) -> Vec<(i32, i32)> {
+    let (mut x, mut y) = from;
+    let dx = (to.0 - x).abs();
+    let dy = -(to.1 - y).abs();
+    let step_x = if x < to.0 { 1 } else { -1 };
+    let step_y = if y < to.1 { 1 } else { -1 };
+    let mut error = dx + dy;
+    let mut points = Vec::new();
+
+    loop {
+        points.push((x, y));
+        if (x, y) == to {
+            break;
+        }
+        let doubled = 2 * error;
+        if doubled >= dy {
+            error += dy;
+            x += step_x;
+        }
+        if doubled <= dx {
+            error += dx;
+            y += step_y;
+        }
+    }
+
+    points
+}
+
+/// Checks whether one point can see another.
+///
+/// Walls and closed doors between the two points block sight, the end
+/// points themselves are never considered blocking.
+///
+/// # Arguments
+/// * `map` - The map to check against
+/// * `from` - Point of the viewer
+/// * `to` - Point being looked at
+///
+/// # Returns
+/// `true` if nothing blocks the line between both points
+pub fn has_line_of_sight(map: &Map, from: (i32, i32), to: (i32, i32)) -> bool {
+    let points = line(from, to);
+    points
+        .iter()
+        .skip(1)
+        .take(points.len().saturating_sub(2))
+        .all(|&(x, y)| !map.blocks_sight(x, y))
+}
+
+/// Recomputes which tiles are visible from a point.
+///
+/// Every tile within the radius that has a clear line of sight to the
+/// origin and is not dark becomes visible and explored. Walls and closed
+/// doors are visible themselves but hide what lies behind them. Tiles next
+/// to the origin are visible even in the dark.
+///
+/// # Arguments
+/// * `map` - The map whose visibility layers are updated
+/// * `origin` - Point of the viewer
+/// * `radius` - Maximum sight distance in tiles
+pub fn compute_fov(map: &mut Map, origin: (i32, i32), radius: i32) {
+    map.visible.fill(false);
+
+    for y in origin.1 - radius..=origin.1 + radius {
+        for x in origin.0 - radius..=origin.0 + radius {
+            if map.get_tile(x, y).is_none() {
+                continue;
+            }
+            let (dx, dy) = (x - origin.0, y - origin.1);
+            if dx * dx + dy * dy > radius * radius {
+                continue;
+            }
+            let lit = map.light[(x, y)] != LightLevel::Dark || dx.abs().max(dy.abs()) <= 1;
+            if lit && has_line_of_sight(map, origin, (x, y)) {
+                map.visible[(x, y)] = true;
+                map.explored[(x, y)] = true;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        light::{compute_light, LightSource},
+        map::{DoorState, Tile},
+    };
+
+    #[test]
+    fn test_closed_door_blocks_sight() {
+        let mut map = Map::new(5, 1);
+        for x in 0..5 {
+            map.set_tile(x, 0, Tile::Floor);
+        }
+        assert!(has_line_of_sight(&map, (0, 0), (4, 0)));
+
+        map.set_tile(2, 0, Tile::Door(DoorState::Closed));
+        assert!(!has_line_of_sight(&map, (0, 0), (4, 0)));
+
+        map.open_door(2, 0).unwrap();
+        assert!(has_line_of_sight(&map, (0, 0), (4, 0)));
+    }
+
+    #[test]
+    fn test_fov_stops_at_walls_and_radius() {
+        let mut map = Map::from_ascii("....#....").unwrap();
+        compute_fov(&mut map, (0, 0), 2);
+        assert!(map.is_visible(2, 0));
+        assert!(!map.is_visible(3, 0));
+
+        compute_fov(&mut map, (1, 0), 8);
+        assert!(map.is_visible(3, 0) && map.is_visible(4, 0));
+        assert!(!map.is_visible(5, 0) && !map.is_explored(5, 0));
+
+        compute_fov(&mut map, (8, 0), 1);
+        assert!(!map.is_visible(0, 0));
+        assert!(map.is_explored(0, 0));
+        assert!(map.is_visible(7, 0));
+    }
+
+    #[test]
+    fn test_dark_tiles_are_only_seen_up_close() {
+        let mut map = Map::from_ascii("..........").unwrap();
+        map.ambient_light = LightLevel::Dark;
+        compute_light(&mut map, &[]);
+        compute_fov(&mut map, (0, 0), 8);
+        assert!(map.is_visible(1, 0));
+        assert!(!map.is_visible(2, 0));
+
+        compute_light(
+            &mut map,
+            &[LightSource {
+                x: 0,
+                y: 0,
+                radius: 4,
+            }],
+        );
+        compute_fov(&mut map, (0, 0), 8);
+        assert!(map.is_visible(4, 0));
+        assert!(!map.is_visible(5, 0));
+        assert!(!map.is_explored(5, 0));
+    }
+}
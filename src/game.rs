@@ -1,9 +1,41 @@
 //! Game module containing core game logic and state management.
 
-use crate::{Map, Player};
+use crate::{
+    engine::Event,
+    fov::{compute_fov, has_line_of_sight, line},
+    highscore::HighScore,
+    hunger::HungerState,
+    identify::Identification,
+    item::{MapItem, PotionKind, ScrollKind},
+    level,
+    light::{compute_light, LightLevel, LightSource},
+    loot::find_table,
+    map::{DoorState, Tile},
+    mapgen,
+    rng::Rng,
+    spell::Spell,
+    spell::SpellKind,
+    status::{StatusEffect, StatusKind},
+    trap::{Trap, TrapKind},
+    Chest, Item, ItemType, Map, Monster, Player,
+};
+
+/// Distance in tiles at which monsters notice the player, halved while
+/// the player stands in dim light or darkness.
+const MONSTER_SIGHT_RANGE: i32 = 8;
+/// Chance in percent that a fainting player passes out for a turn.
+const FAINT_CHANCE: i32 = 10;
+/// Distance in tiles a brazier lights up.
+const BRAZIER_RADIUS: i32 = 4;
+/// Distance in tiles the player can see.
+const PLAYER_SIGHT_RANGE: i32 = 8;
+/// Distance in tiles covered by an active search.
+const SEARCH_RADIUS: i32 = 2;
+/// Distance in tiles the player can throw items.
+pub const THROW_RANGE: i32 = 6;
 
 /// Represents the current state of the game.
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum GameState {
     /// Game is actively running
     Running,
@@ -22,6 +54,31 @@ pub struct Game {
     pub state: GameState,
     /// Game world map
     pub map: Map,
+    /// Monsters currently alive on the map
+    pub monsters: Vec<Monster>,
+    /// Chests placed on the map
+    pub chests: Vec<Chest>,
+    /// Items lying on the map
+    pub items: Vec<MapItem>,
+    /// Traps placed on the map
+    pub traps: Vec<Trap>,
+    /// Current dungeon depth, starting at 1
+    pub depth: i32,
+    /// Random number generator for loot and combat
+    pub rng: Rng,
+    /// Appearances of potions and scrolls in this run, and which are known
+    pub identification: Identification,
+    /// Message log, newest message last
+    pub messages: Vec<String>,
+    /// Number of turns played
+    pub turns: u32,
+    /// Number of monsters killed
+    pub kills: u32,
+    /// What killed the player, set when the game is over
+    pub cause_of_death: Option<String>,
+    /// Events of the command being applied by [`Game::apply`], `None`
+    /// while no command is applied
+    events: Option<Vec<Event>>,
 }
 
 impl Game {
@@ -32,17 +89,1513 @@ impl Game {
     /// * `map` - Initial game map
     ///
     /// # Returns
-    /// A new Game instance with the specified player and map
+    /// A new Game instance with the specified player and map. The player is
+    /// placed on the walkable tile closest to the map center and knows every
+    /// item.
     pub fn new(player_name: String, map: Map) -> Self {
-        Game {
-            player: Player::new(player_name),
+        let mut player = Player::new(player_name);
+        if let Some((x, y)) = map.spawn_point() {
+            player.x = x;
+            player.y = y;
+        }
+
+        let mut game = Game {
+            player,
             state: GameState::Running,
             map,
+            monsters: Vec::new(),
+            chests: Vec::new(),
+            items: Vec::new(),
+            traps: Vec::new(),
+            depth: 1,
+            rng: Rng::from_time(),
+            identification: Identification::default(),
+            messages: Vec::new(),
+            turns: 0,
+            kills: 0,
+            cause_of_death: None,
+            events: None,
+        };
+        game.refresh_fov();
+        game
+    }
+
+    /// Creates a new game on a generated first dungeon level.
+    ///
+    /// # Arguments
+    /// * `player` - The player character from character creation
+    /// * `rng` - Random number generator used for the whole run
+    ///
+    /// # Returns
+    /// A new Game instance with the player placed on depth 1. Potions and
+    /// scrolls are unknown, except for those the player starts with
+    pub fn generate(player: Player, rng: Rng) -> Self {
+        let mut game = Game::new(String::new(), Map::new(0, 0));
+        game.player = player;
+        game.rng = rng;
+        game.identification = Identification::new(&mut game.rng);
+        for item in &game.player.inventory {
+            game.identification.identify(item);
+        }
+        game.enter_level(1);
+        game
+    }
+
+    /// Replaces the current level with a newly generated one.
+    ///
+    /// # Arguments
+    /// * `depth` - Depth of the new level
+    pub fn enter_level(&mut self, depth: i32) {
+        let level = level::generate(depth, &mut self.rng);
+        self.depth = depth;
+        self.map = level.map;
+        self.monsters = level.monsters;
+        self.chests = level.chests;
+        self.items = level.items;
+        self.traps = level.traps;
+        self.player.x = level.spawn.0;
+        self.player.y = level.spawn.1;
+        self.refresh_fov();
+        self.emit(Event::LevelEntered { depth });
+    }
+
+    /// Pauses a running game, freezing all turn processing.
+    pub fn pause(&mut self) {
+        if self.state == GameState::Running {
+            self.state = GameState::Paused;
+        }
+    }
+
+    /// Resumes a paused game.
+    pub fn resume(&mut self) {
+        if self.state == GameState::Paused {
+            self.state = GameState::Running;
+        }
+    }
+
+    /// Recomputes the light on the level and what the player can see from
+    /// the current position.
+    pub fn refresh_fov(&mut self) {
+        let sources = self.light_sources();
+        compute_light(&mut self.map, &sources);
+        let origin = (self.player.x, self.player.y);
+        compute_fov(&mut self.map, origin, PLAYER_SIGHT_RANGE);
+    }
+
+    /// Collects everything that gives off light on the level.
+    ///
+    /// # Returns
+    /// The player's light if it has fuel left, every brazier and every
+    /// glowing monster
+    pub fn light_sources(&self) -> Vec<LightSource> {
+        let mut sources = Vec::new();
+        if let Some(radius) = self.player.light_radius() {
+            sources.push(LightSource {
+                x: self.player.x,
+                y: self.player.y,
+                radius,
+            });
+        }
+        for (point, tile) in self.map.tiles.iter_points() {
+            if *tile == Tile::Brazier {
+                sources.push(LightSource {
+                    x: point.x,
+                    y: point.y,
+                    radius: BRAZIER_RADIUS,
+                });
+            }
+        }
+        for monster in self.monsters.iter().filter(|m| m.glow > 0) {
+            sources.push(LightSource {
+                x: monster.x,
+                y: monster.y,
+                radius: monster.glow,
+            });
+        }
+        sources
+    }
+
+    /// Checks whether the player can see a monster.
+    ///
+    /// Stealthy monsters stay hidden in dim light unless they stand next to
+    /// the player.
+    ///
+    /// # Returns
+    /// `true` if the monster's tile is in view and lit well enough
+    pub fn can_see_monster(&self, monster: &Monster) -> bool {
+        if !self.map.is_visible(monster.x, monster.y) {
+            return false;
+        }
+        let adjacent = distance((self.player.x, self.player.y), (monster.x, monster.y)) <= 1;
+        !monster.stealthy || adjacent || self.map.light[(monster.x, monster.y)] >= LightLevel::Lit
+    }
+
+    /// Takes the stairs down if the player stands on them.
+    ///
+    /// # Returns
+    /// `true` if the action used up the player's turn
+    pub fn descend(&mut self) -> bool {
+        if self.map.get_tile(self.player.x, self.player.y) != Some(&Tile::StairsDown) {
+            self.log("There are no stairs here.");
+            return false;
+        }
+
+        self.enter_level(self.depth + 1);
+        self.log(format!("You descend to depth {}.", self.depth));
+        true
+    }
+
+    /// Records an event for the caller of [`Game::apply`].
+    ///
+    /// Events outside of `apply` have nobody to collect them and are
+    /// dropped.
+    ///
+    /// # Arguments
+    /// * `event` - What happened
+    pub fn emit(&mut self, event: Event) {
+        if let Some(events) = &mut self.events {
+            events.push(event);
+        }
+    }
+
+    /// Runs an action and collects the events it causes.
+    ///
+    /// # Arguments
+    /// * `action` - The action to run on the game
+    ///
+    /// # Returns
+    /// The events, in order
+    pub fn collect_events(&mut self, action: impl FnOnce(&mut Game)) -> Vec<Event> {
+        let outer = self.events.replace(Vec::new());
+        action(self);
+        std::mem::replace(&mut self.events, outer).unwrap_or_default()
+    }
+
+    /// Adds a message to the message log.
+    ///
+    /// # Arguments
+    /// * `message` - The message to add
+    pub fn log(&mut self, message: impl Into<String>) {
+        let message = message.into();
+        self.emit(Event::Message(message.clone()));
+        self.messages.push(message);
+    }
+
+    /// Finds the monster standing on a tile.
+    ///
+    /// # Returns
+    /// * `Some(usize)` with the index into `monsters`
+    /// * `None` if the tile is free
+    pub fn monster_at(&self, x: i32, y: i32) -> Option<usize> {
+        self.monsters.iter().position(|m| m.x == x && m.y == y)
+    }
+
+    /// Finds the trap placed on a tile.
+    ///
+    /// # Returns
+    /// * `Some(usize)` with the index into `traps`
+    /// * `None` if there is no trap on the tile
+    pub fn trap_at(&self, x: i32, y: i32) -> Option<usize> {
+        self.traps.iter().position(|t| t.x == x && t.y == y)
+    }
+
+    /// Gets the symbol drawn for a map position.
+    ///
+    /// The player, monsters in view, items, chests and discovered traps are
+    /// drawn above the tile. Unexplored positions are blank.
+    ///
+    /// # Returns
+    /// The symbol shown on screen for the position
+    pub fn symbol_at(&self, x: i32, y: i32) -> char {
+        let monster = self
+            .monster_at(x, y)
+            .map(|i| &self.monsters[i])
+            .filter(|monster| self.can_see_monster(monster));
+
+        if !self.map.is_explored(x, y) {
+            ' '
+        } else if (x, y) == (self.player.x, self.player.y) {
+            '@'
+        } else if let Some(monster) = monster {
+            monster.glyph
+        } else if let Some(map_item) = self.items.iter().find(|i| i.x == x && i.y == y) {
+            map_item.item.glyph()
+        } else if self.chest_at(x, y).is_some() {
+            '='
+        } else if self.trap_at(x, y).is_some_and(|i| !self.traps[i].hidden) {
+            '^'
+        } else {
+            self.map.get_tile(x, y).unwrap_or(&Tile::Empty).glyph()
+        }
+    }
+
+    /// Finds the chest standing on a tile.
+    ///
+    /// # Returns
+    /// * `Some(usize)` with the index into `chests`
+    /// * `None` if there is no chest on the tile
+    pub fn chest_at(&self, x: i32, y: i32) -> Option<usize> {
+        self.chests.iter().position(|c| c.x == x && c.y == y)
+    }
+
+    /// Moves the player one step, attacking or opening doors on the way.
+    ///
+    /// # Arguments
+    /// * `dx` - Step on the x-axis
+    /// * `dy` - Step on the y-axis
+    ///
+    /// # Returns
+    /// `true` if the action used up the player's turn
+    pub fn move_player(&mut self, dx: i32, dy: i32) -> bool {
+        let (x, y) = (self.player.x + dx, self.player.y + dy);
+
+        if let Some(index) = self.monster_at(x, y) {
+            self.attack_monster(index);
+            return true;
+        }
+
+        if self.chest_at(x, y).is_some() {
+            return self.open_chest(dx, dy);
+        }
+
+        match self.map.get_tile(x, y) {
+            Some(Tile::Door(DoorState::Closed)) => self.open_door(dx, dy),
+            Some(Tile::Door(DoorState::Locked(_))) => {
+                self.log("The door is locked.");
+                false
+            }
+            _ if self.map.is_walkable(x, y) => {
+                self.player.x = x;
+                self.player.y = y;
+                self.emit(Event::Moved { x, y });
+                if let Some(index) = self.trap_at(x, y) {
+                    if self.traps[index].hidden {
+                        self.trigger_trap(index);
+                    } else {
+                        self.log(format!(
+                            "You carefully step around the {}.",
+                            self.traps[index].kind.name()
+                        ));
+                    }
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Opens an adjacent chest or door.
+    ///
+    /// # Returns
+    /// `true` if the action used up the player's turn
+    pub fn open(&mut self, dx: i32, dy: i32) -> bool {
+        if self
+            .chest_at(self.player.x + dx, self.player.y + dy)
+            .is_some()
+        {
+            self.open_chest(dx, dy)
+        } else {
+            self.open_door(dx, dy)
+        }
+    }
+
+    /// Unlocks an adjacent chest or door.
+    ///
+    /// # Returns
+    /// `true` if the action used up the player's turn
+    pub fn unlock(&mut self, dx: i32, dy: i32) -> bool {
+        if self
+            .chest_at(self.player.x + dx, self.player.y + dy)
+            .is_some()
+        {
+            self.unlock_chest(dx, dy)
+        } else {
+            self.unlock_door(dx, dy)
+        }
+    }
+
+    /// Opens an adjacent chest and puts its contents into the inventory.
+    ///
+    /// # Returns
+    /// `true` if the action used up the player's turn
+    pub fn open_chest(&mut self, dx: i32, dy: i32) -> bool {
+        let Some(index) = self.chest_at(self.player.x + dx, self.player.y + dy) else {
+            self.log("There is no chest there.");
+            return false;
+        };
+
+        match self.chests[index].open(self.depth, &mut self.rng) {
+            Ok(items) if items.is_empty() => {
+                self.log("You open the chest. It is empty.");
+                true
+            }
+            Ok(items) => {
+                let names: Vec<String> = items
+                    .iter()
+                    .map(|item| self.identification.name(item))
+                    .collect();
+                self.log(format!(
+                    "You open the chest and find: {}.",
+                    names.join(", ")
+                ));
+                for item in items {
+                    self.player.store_item(item);
+                }
+                true
+            }
+            Err(e) => {
+                self.log(e);
+                false
+            }
+        }
+    }
+
+    /// Unlocks an adjacent chest with a matching key from the inventory.
+    ///
+    /// # Returns
+    /// `true` if the action used up the player's turn
+    pub fn unlock_chest(&mut self, dx: i32, dy: i32) -> bool {
+        let Some(index) = self.chest_at(self.player.x + dx, self.player.y + dy) else {
+            self.log("There is no chest there.");
+            return false;
+        };
+        let Some(key_id) = self.chests[index].lock else {
+            self.log("The chest is not locked.");
+            return false;
+        };
+
+        match self.use_key(key_id) {
+            Some(key) => {
+                let _ = self.chests[index].unlock(key_id);
+                self.log_unlock("chest", &key);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Searches the surroundings for hidden traps.
+    ///
+    /// Each hidden trap within the search radius is found with a chance
+    /// based on the player's speed.
+    ///
+    /// # Returns
+    /// `true` if the action used up the player's turn
+    pub fn search(&mut self) -> bool {
+        let chance = 50 + self.player.speed * 2;
+        let found = self.detect_traps(SEARCH_RADIUS, chance);
+        if found == 0 {
+            self.log("You search but find nothing.");
+        }
+        true
+    }
+
+    /// Rolls to discover hidden traps near the player.
+    ///
+    /// # Arguments
+    /// * `radius` - Distance in tiles to check
+    /// * `chance` - Chance in percent to discover each trap
+    ///
+    /// # Returns
+    /// Number of traps discovered
+    fn detect_traps(&mut self, radius: i32, chance: i32) -> usize {
+        let (px, py) = (self.player.x, self.player.y);
+        let mut found = Vec::new();
+
+        for trap in self.traps.iter_mut().filter(|trap| trap.hidden) {
+            let distance = (trap.x - px).abs().max((trap.y - py).abs());
+            if distance <= radius && self.rng.chance(chance) {
+                trap.hidden = false;
+                found.push(trap.kind.name());
+            }
+        }
+
+        for name in &found {
+            let article = if name.starts_with(['a', 'e', 'i', 'o', 'u']) {
+                "an"
+            } else {
+                "a"
+            };
+            self.log(format!("You discover {} {}!", article, name));
+        }
+        found.len()
+    }
+
+    /// Lists the tiles a teleport trap can move the player to.
+    ///
+    /// # Returns
+    /// Every empty floor tile the player can walk to from where it stands,
+    /// without monsters, chests, items or traps
+    fn teleport_targets(&self) -> Vec<(i32, i32)> {
+        mapgen::distances(&self.map, (self.player.x, self.player.y))
+            .iter_points()
+            .filter(|(_, steps)| matches!(steps, Some(steps) if *steps > 0))
+            .map(|(point, _)| (point.x, point.y))
+            .filter(|&(x, y)| {
+                self.map.get_tile(x, y) == Some(&Tile::Floor)
+                    && self.monster_at(x, y).is_none()
+                    && self.chest_at(x, y).is_none()
+                    && self.trap_at(x, y).is_none()
+                    && !self.items.iter().any(|item| (item.x, item.y) == (x, y))
+            })
+            .collect()
+    }
+
+    /// Applies the effect of a trap the player stepped on and reveals it.
+    fn trigger_trap(&mut self, index: usize) {
+        self.traps[index].hidden = false;
+        let kind = self.traps[index].kind;
+
+        match kind {
+            TrapKind::Spike => {
+                self.log("Spikes shoot out of the floor!");
+                self.damage_player(15 + self.depth * 3, "impaled by a spike trap");
+            }
+            TrapKind::PoisonGas => {
+                self.log("A cloud of poison gas bursts from the floor!");
+                self.player.add_status(StatusEffect::new(
+                    StatusKind::Poisoned,
+                    5,
+                    2 + self.depth / 2,
+                ));
+            }
+            TrapKind::Teleport => {
+                self.log("The world blurs around you.");
+                let targets = self.teleport_targets();
+                match targets.get(self.rng.range(0, targets.len() as i32) as usize) {
+                    Some(&(x, y)) => {
+                        self.player.x = x;
+                        self.player.y = y;
+                        self.emit(Event::Moved { x, y });
+                    }
+                    None => self.log("The trap fizzles."),
+                }
+            }
+            TrapKind::Alarm => {
+                self.log("A loud bell rings through the dungeon!");
+                for monster in &mut self.monsters {
+                    monster.alerted = true;
+                }
+            }
+        }
+    }
+
+    /// Picks up all items on the player's tile.
+    ///
+    /// # Returns
+    /// `true` if the action used up the player's turn
+    pub fn pick_up(&mut self) -> bool {
+        let (x, y) = (self.player.x, self.player.y);
+        let (here, rest): (Vec<MapItem>, Vec<MapItem>) = self
+            .items
+            .drain(..)
+            .partition(|map_item| map_item.x == x && map_item.y == y);
+        self.items = rest;
+
+        if here.is_empty() {
+            self.log("There is nothing here.");
+            return false;
+        }
+
+        for map_item in here {
+            let name = self.identification.name(&map_item.item);
+            self.log(format!("You pick up the {}.", name));
+            self.player.store_item(map_item.item);
+        }
+        true
+    }
+
+    /// Opens an adjacent door.
+    ///
+    /// # Returns
+    /// `true` if the action used up the player's turn
+    pub fn open_door(&mut self, dx: i32, dy: i32) -> bool {
+        let (x, y) = (self.player.x + dx, self.player.y + dy);
+        match self.map.open_door(x, y) {
+            Ok(()) => {
+                self.log("You open the door.");
+                true
+            }
+            Err(e) => {
+                self.log(e);
+                false
+            }
+        }
+    }
+
+    /// Closes an adjacent door.
+    ///
+    /// # Returns
+    /// `true` if the action used up the player's turn
+    pub fn close_door(&mut self, dx: i32, dy: i32) -> bool {
+        let (x, y) = (self.player.x + dx, self.player.y + dy);
+        if let Some(index) = self.monster_at(x, y) {
+            let message = format!("The {} is in the way.", self.monsters[index].name);
+            self.log(message);
+            return false;
+        }
+
+        match self.map.close_door(x, y) {
+            Ok(()) => {
+                self.log("You close the door.");
+                true
+            }
+            Err(e) => {
+                self.log(e);
+                false
+            }
+        }
+    }
+
+    /// Unlocks an adjacent door with a matching key from the inventory.
+    ///
+    /// # Returns
+    /// `true` if the action used up the player's turn
+    pub fn unlock_door(&mut self, dx: i32, dy: i32) -> bool {
+        let (x, y) = (self.player.x + dx, self.player.y + dy);
+        let key_id = match self.map.get_tile(x, y) {
+            Some(Tile::Door(DoorState::Locked(id))) => *id,
+            Some(Tile::Door(_)) => {
+                self.log("The door is not locked.");
+                return false;
+            }
+            _ => {
+                self.log("There is no door there.");
+                return false;
+            }
+        };
+
+        match self.use_key(key_id) {
+            Some(key) => {
+                let _ = self.map.unlock_door(x, y, key_id);
+                self.log_unlock("door", &key);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Takes a key for a lock from the player, logging if none is carried.
+    fn use_key(&mut self, key_id: u32) -> Option<Item> {
+        match self.player.use_key(key_id) {
+            Ok(key) => Some(key),
+            Err(e) => {
+                self.log(e);
+                None
+            }
+        }
+    }
+
+    /// Logs the unlocking of a lock, noting whether the key broke.
+    fn log_unlock(&mut self, target: &str, key: &Item) {
+        if let ItemType::Key {
+            consumed_on_use: true,
+            ..
+        } = key.item_type
+        {
+            self.log(format!(
+                "You unlock the {}. The {} breaks.",
+                target, key.name
+            ));
+        } else {
+            self.log(format!("You unlock the {} with the {}.", target, key.name));
+        }
+    }
+
+    /// Lists the monsters the player can see within a range.
+    ///
+    /// # Arguments
+    /// * `range` - Maximum distance in tiles
+    ///
+    /// # Returns
+    /// Positions of all visible monsters, closest first
+    pub fn visible_targets(&self, range: i32) -> Vec<(i32, i32)> {
+        let origin = (self.player.x, self.player.y);
+        let mut targets: Vec<(i32, i32)> = self
+            .monsters
+            .iter()
+            .filter(|monster| self.can_see_monster(monster))
+            .map(|monster| (monster.x, monster.y))
+            .filter(|&target| distance(origin, target) <= range)
+            .collect();
+        targets.sort_by_key(|&target| distance(origin, target));
+        targets
+    }
+
+    /// Traces the flight of a projectile from the player towards a target.
+    ///
+    /// The projectile stops in front of walls, closed doors and chests, on
+    /// the first monster in its way, or when it runs out of range.
+    ///
+    /// # Arguments
+    /// * `target` - Position the projectile is aimed at
+    /// * `range` - Maximum distance in tiles
+    ///
+    /// # Returns
+    /// All positions the projectile passes, excluding the player's position
+    pub fn projectile_path(&self, target: (i32, i32), range: i32) -> Vec<(i32, i32)> {
+        let origin = (self.player.x, self.player.y);
+        let mut path = Vec::new();
+
+        for (x, y) in line(origin, target).into_iter().skip(1) {
+            if distance(origin, (x, y)) > range
+                || !self.map.is_walkable(x, y)
+                || self.chest_at(x, y).is_some()
+            {
+                break;
+            }
+            path.push((x, y));
+            if self.monster_at(x, y).is_some() {
+                break;
+            }
+        }
+        path
+    }
+
+    /// Shoots the equipped ranged weapon at a target.
+    ///
+    /// # Arguments
+    /// * `target` - Position the shot is aimed at
+    ///
+    /// # Returns
+    /// * `Some(path)` with the flight path if a shot was fired
+    /// * `None` if no ranged weapon or ammunition is available, or the
+    ///   target is the player or right behind a wall, which uses no ammunition
+    pub fn fire_at(&mut self, target: (i32, i32)) -> Option<Vec<(i32, i32)>> {
+        let Some((weapon, range)) = self.player.ranged_weapon() else {
+            self.log("You have no ranged weapon equipped.");
+            return None;
+        };
+        let damage = self.player.attack + weapon.value;
+
+        let path = self.projectile_path(target, range);
+        let Some(&(x, y)) = path.last() else {
+            self.log("There is nothing to shoot at there.");
+            return None;
+        };
+
+        let ammo = match self.player.take_ammo() {
+            Ok(ammo) => ammo,
+            Err(e) => {
+                self.log(e);
+                return None;
+            }
+        };
+
+        let recovered = match self.monster_at(x, y) {
+            Some(index) => {
+                self.hit_monster(index, damage);
+                self.rng.chance(50)
+            }
+            None => {
+                self.log(format!("The {} misses.", ammo.name));
+                self.rng.chance(75)
+            }
+        };
+        if recovered {
+            self.items.push(MapItem { x, y, item: ammo });
+        }
+
+        Some(path)
+    }
+
+    /// Throws an item from the inventory at a target.
+    ///
+    /// Potions shatter where they land and affect the monster hit, fire and
+    /// poison also splash onto monsters next to it.
+    ///
+    /// # Arguments
+    /// * `inventory_index` - Index of the item in the inventory to throw
+    /// * `target` - Position the item is aimed at
+    ///
+    /// # Returns
+    /// * `Some(path)` with the flight path if the item was thrown
+    /// * `None` if the item cannot be thrown, or the target is the player or
+    ///   right behind a wall, which keeps the item
+    pub fn throw_at(
+        &mut self,
+        inventory_index: usize,
+        target: (i32, i32),
+    ) -> Option<Vec<(i32, i32)>> {
+        match self.player.inventory.get(inventory_index) {
+            Some(item) if item.is_throwable() => {}
+            Some(_) => {
+                self.log("You cannot throw that.");
+                return None;
+            }
+            None => {
+                self.log("Invalid Inventory-Index");
+                return None;
+            }
+        }
+
+        let path = self.projectile_path(target, THROW_RANGE);
+        let Some(&landing) = path.last() else {
+            self.log("There is nothing to throw at there.");
+            return None;
+        };
+
+        let item = self.player.inventory.remove(inventory_index);
+        self.log(format!("The {} shatters!", self.identification.name(&item)));
+        self.reveal_kind(&item);
+
+        let ItemType::Potion(kind) = item.item_type else {
+            return Some(path);
+        };
+        let splash: Vec<usize> = (0..self.monsters.len())
+            .filter(|&i| distance(landing, (self.monsters[i].x, self.monsters[i].y)) <= 1)
+            .collect();
+
+        match kind {
+            PotionKind::Healing => {
+                if let Some(index) = self.monster_at(landing.0, landing.1) {
+                    self.monsters[index].heal(item.value);
+                    let message = format!("The {} looks healthier.", self.monsters[index].name);
+                    self.log(message);
+                }
+            }
+            PotionKind::Fire => {
+                for index in splash.into_iter().rev() {
+                    self.hit_monster(index, item.value + self.monsters[index].defense);
+                }
+            }
+            PotionKind::Poison => {
+                for index in splash {
+                    self.monsters[index].add_status(StatusEffect::new(
+                        StatusKind::Poisoned,
+                        5,
+                        item.value,
+                    ));
+                    let message = format!("The {} is poisoned.", self.monsters[index].name);
+                    self.log(message);
+                }
+            }
+        }
+
+        Some(path)
+    }
+
+    /// Casts a spell from the player's spellbook.
+    ///
+    /// # Arguments
+    /// * `index` - Index of the spell in the spellbook
+    /// * `target` - Target position for spells that need one
+    ///
+    /// # Returns
+    /// * `Some(path)` with the flight path to animate if the spell was cast
+    /// * `None` if the spell is unknown, not ready or cannot be paid for
+    pub fn cast_spell(
+        &mut self,
+        index: usize,
+        target: Option<(i32, i32)>,
+    ) -> Option<Vec<(i32, i32)>> {
+        let Some(known) = self.player.spellbook.spells.get(index) else {
+            self.log("You do not know that spell.");
+            return None;
+        };
+        let spell = known.spell.clone();
+
+        if known.cooldown_left > 0 {
+            let message = format!(
+                "{} is not ready yet ({} turns).",
+                spell.name, known.cooldown_left
+            );
+            self.log(message);
+            return None;
+        }
+        if self.player.mana < spell.cost {
+            self.log("You do not have enough mana.");
+            return None;
+        }
+
+        match self.apply_spell(&spell, target) {
+            Ok(path) => {
+                self.player.mana -= spell.cost;
+                self.player.spellbook.spells[index].cooldown_left = spell.cooldown;
+                Some(path)
+            }
+            Err(e) => {
+                self.log(e);
+                None
+            }
+        }
+    }
+
+    /// Reads a scroll from the inventory.
+    ///
+    /// # Arguments
+    /// * `inventory_index` - Index of the scroll in the inventory
+    /// * `target` - Target position for spells that need one
+    ///
+    /// # Returns
+    /// * `Some(path)` with the flight path to animate if the scroll was read
+    /// * `None` if the item is no scroll or its spell failed
+    pub fn read_scroll(
+        &mut self,
+        inventory_index: usize,
+        target: Option<(i32, i32)>,
+    ) -> Option<Vec<(i32, i32)>> {
+        let (kind, scroll) = match self.player.inventory.get(inventory_index) {
+            Some(item) => match item.item_type {
+                ItemType::Scroll(kind) => (kind, item.clone()),
+                _ => {
+                    self.log("You cannot read that.");
+                    return None;
+                }
+            },
+            None => {
+                self.log("Invalid Inventory-Index");
+                return None;
+            }
+        };
+
+        match kind {
+            ScrollKind::Teach(spell) => {
+                let name = spell.spell().name;
+                if !self.player.spellbook.learn(spell) {
+                    self.log(format!("You already know {}.", name));
+                    return None;
+                }
+                self.player.inventory.remove(inventory_index);
+                self.log(format!("You learn the {} spell.", name));
+                Some(Vec::new())
+            }
+            ScrollKind::Cast(spell) => match self.apply_spell(&spell.spell(), target) {
+                Ok(path) => {
+                    self.player.inventory.remove(inventory_index);
+                    self.reveal_kind(&scroll);
+                    Some(path)
+                }
+                Err(e) => {
+                    self.log(e);
+                    None
+                }
+            },
+            ScrollKind::Identify => {
+                self.player.inventory.remove(inventory_index);
+                self.reveal_kind(&scroll);
+                self.identify_random_item();
+                Some(Vec::new())
+            }
+        }
+    }
+
+    /// Makes the kind of a used item known, telling the player what it was.
+    fn reveal_kind(&mut self, item: &Item) {
+        if self.identification.identify(item) {
+            self.log(format!("It was a {}.", item.display_name()));
+        }
+    }
+
+    /// Identifies a random unknown item in the player's inventory.
+    fn identify_random_item(&mut self) {
+        let unknown: Vec<&Item> = self
+            .player
+            .inventory
+            .iter()
+            .filter(|item| !self.identification.is_known(item))
+            .collect();
+        if unknown.is_empty() {
+            self.log("You already know everything you carry.");
+            return;
+        }
+
+        let item = unknown[self.rng.range(0, unknown.len() as i32) as usize].clone();
+        let appearance = self.identification.name(&item);
+        self.identification.identify(&item);
+        self.log(format!("The {} is a {}.", appearance, item.display_name()));
+    }
+
+    /// Applies the effect of a spell.
+    ///
+    /// # Returns
+    /// * `Ok(path)` with the flight path to animate, empty for spells without
+    /// * `Err(String)` if the target is the player, out of reach or leaves
+    ///   the spell no room to fly, nothing has happened then
+    fn apply_spell(
+        &mut self,
+        spell: &Spell,
+        target: Option<(i32, i32)>,
+    ) -> Result<Vec<(i32, i32)>, String> {
+        let origin = (self.player.x, self.player.y);
+        let power = spell.power + self.player.level * 2;
+        let target = match target {
+            Some(target) if spell.needs_target() => {
+                if target == origin {
+                    return Err("You cannot target yourself.".to_string());
+                }
+                if distance(origin, target) > spell.range {
+                    return Err("That is out of range.".to_string());
+                }
+                target
+            }
+            None if spell.needs_target() => return Err("The spell needs a target.".to_string()),
+            _ => origin,
+        };
+
+        match spell.kind {
+            SpellKind::Bolt => {
+                let path = self.projectile_path(target, spell.range);
+                let Some(&(x, y)) = path.last() else {
+                    return Err("There is no room to cast that there.".to_string());
+                };
+                self.log("A bolt of energy shoots from your hands.");
+                if let Some(index) = self.monster_at(x, y) {
+                    self.hit_monster(index, power + self.monsters[index].defense);
+                }
+                Ok(path)
+            }
+            SpellKind::Blast => {
+                if !self.map.is_visible(target.0, target.1) {
+                    return Err("You cannot see that spot.".to_string());
+                }
+                let path = self.projectile_path(target, spell.range);
+                let Some(&center) = path.last() else {
+                    return Err("There is no room to cast that there.".to_string());
+                };
+                self.log("The air explodes in flames!");
+                for index in (0..self.monsters.len()).rev() {
+                    let monster = &self.monsters[index];
+                    if distance(center, (monster.x, monster.y)) <= spell.radius {
+                        self.hit_monster(index, power + monster.defense);
+                    }
+                }
+                Ok(path)
+            }
+            SpellKind::Heal => {
+                self.player.heal(power);
+                self.log("Warmth flows through your body.");
+                Ok(Vec::new())
+            }
+            SpellKind::Blink => {
+                if !self.map.is_visible(target.0, target.1)
+                    || !self.map.is_walkable(target.0, target.1)
+                    || self.monster_at(target.0, target.1).is_some()
+                    || self.chest_at(target.0, target.1).is_some()
+                {
+                    return Err("You cannot blink there.".to_string());
+                }
+                self.player.x = target.0;
+                self.player.y = target.1;
+                self.emit(Event::Moved {
+                    x: target.0,
+                    y: target.1,
+                });
+                self.log("You blink through space.");
+                Ok(Vec::new())
+            }
+            SpellKind::Detect => {
+                for y in origin.1 - spell.radius..=origin.1 + spell.radius {
+                    for x in origin.0 - spell.radius..=origin.0 + spell.radius {
+                        self.map.reveal(x, y);
+                    }
+                }
+                for trap in &mut self.traps {
+                    if distance(origin, (trap.x, trap.y)) <= spell.radius {
+                        trap.hidden = false;
+                    }
+                }
+                self.log("The layout of the dungeon forms in your mind.");
+                Ok(Vec::new())
+            }
+        }
+    }
+
+    /// Uses a consumable item from the player's inventory.
+    ///
+    /// # Arguments
+    /// * `index` - Index of the item in the inventory
+    ///
+    /// # Returns
+    /// `true` if the item was used and the turn is over
+    pub fn use_item(&mut self, index: usize) -> bool {
+        let Some(item) = self.player.inventory.get(index).cloned() else {
+            self.log("Invalid Inventory-Index");
+            return false;
+        };
+        let name = self.identification.name(&item);
+        let is_food = matches!(item.item_type, ItemType::Food);
+
+        match self.player.use_item(index) {
+            Ok(()) if is_food => {
+                self.log(format!("You eat the {}.", name));
+                if self.player.hunger() == HungerState::Satiated {
+                    self.log(HungerState::Satiated.message());
+                }
+                true
+            }
+            Ok(()) => {
+                self.log(format!("You use the {}.", name));
+                self.reveal_kind(&item);
+                if !self.player.is_alive() {
+                    self.die(format!("drank a {}", item.name.to_lowercase()));
+                }
+                true
+            }
+            Err(e) => {
+                self.log(e);
+                false
+            }
+        }
+    }
+
+    /// Equips an item from the player's inventory.
+    ///
+    /// # Arguments
+    /// * `index` - Index of the item in the inventory
+    ///
+    /// # Returns
+    /// `true` if the item was equipped and the turn is over
+    pub fn equip(&mut self, index: usize) -> bool {
+        let Some(name) = self.player.inventory.get(index).map(Item::display_name) else {
+            self.log("Invalid Inventory-Index");
+            return false;
+        };
+
+        match self.player.add_item(index) {
+            Ok(()) => {
+                self.log(format!("You equip the {}.", name));
+                true
+            }
+            Err(e) => {
+                self.log(e);
+                false
+            }
+        }
+    }
+
+    /// Lists the known spells in the message log.
+    pub fn list_spells(&mut self) {
+        if self.player.spellbook.spells.is_empty() {
+            self.log("You know no spells.");
+            return;
+        }
+        let lines: Vec<String> = self
+            .player
+            .spellbook
+            .spells
+            .iter()
+            .enumerate()
+            .map(|(i, known)| {
+                let mut line = format!("{}. {} ({} MP)", i + 1, known.spell.name, known.spell.cost);
+                if known.cooldown_left > 0 {
+                    line.push_str(&format!(", ready in {} turns", known.cooldown_left));
+                }
+                line
+            })
+            .collect();
+        for line in lines {
+            self.log(line);
+        }
+    }
+
+    /// Deals damage to the player and ends the game if it is fatal.
+    ///
+    /// # Arguments
+    /// * `amount` - Raw damage amount before defense calculation
+    /// * `cause` - Cause of death recorded if the damage kills the player
+    fn damage_player(&mut self, amount: i32, cause: &str) {
+        let health = self.player.health;
+        let dead = self.player.take_damage(amount);
+        if self.player.health < health {
+            self.emit(Event::PlayerHurt {
+                damage: health - self.player.health,
+                health: self.player.health,
+            });
+        }
+        if dead {
+            self.die(cause);
+        }
+    }
+
+    /// Ends the game with the player's death.
+    fn die(&mut self, cause: impl Into<String>) {
+        let cause = cause.into();
+        self.state = GameState::GameOver;
+        self.cause_of_death = Some(cause.clone());
+        self.log("You die...");
+        self.emit(Event::GameOver { cause });
+    }
+
+    /// Summarises the run for the Hall of Fame.
+    ///
+    /// # Returns
+    /// A HighScore entry with the player's progress and the cause of death
+    pub fn high_score(&self) -> HighScore {
+        HighScore {
+            name: self.player.name.clone(),
+            class: self.player.class.name().to_string(),
+            depth: self.depth,
+            level: self.player.level,
+            turns: self.turns,
+            kills: self.kills,
+            cause: self
+                .cause_of_death
+                .clone()
+                .unwrap_or_else(|| "quit the game".to_string()),
+        }
+    }
+
+    /// Resolves a player attack against a monster.
+    fn attack_monster(&mut self, index: usize) {
+        let attack = self.player.attack;
+        self.hit_monster(index, attack);
+    }
+
+    /// Deals damage to a monster, killing it if its health runs out.
+    fn hit_monster(&mut self, index: usize, damage: i32) {
+        let name = self.monsters[index].name.clone();
+        if self.monsters[index].take_damage(damage) {
+            self.log(format!("You slay the {}.", name));
+            self.kill_monster(index);
+        } else {
+            self.log(format!("You hit the {}.", name));
+        }
+    }
+
+    /// Removes a dead monster, awarding experience and dropping its loot.
+    fn kill_monster(&mut self, index: usize) {
+        let monster = self.monsters.remove(index);
+        self.emit(Event::MonsterKilled {
+            name: monster.name.clone(),
+            x: monster.x,
+            y: monster.y,
+        });
+        self.kills += 1;
+        self.player.gain_experience(monster.experience);
+        self.drop_loot(&monster);
+    }
+
+    /// Rolls a dead monster's loot table and drops the items where it died.
+    fn drop_loot(&mut self, monster: &Monster) {
+        let Some(table) = monster.loot_table.as_deref().and_then(find_table) else {
+            return;
+        };
+
+        for item in table.roll(self.depth, &mut self.rng) {
+            let name = self.identification.name(&item);
+            self.log(format!("The {} drops a {}.", monster.name, name));
+            self.items.push(MapItem {
+                x: monster.x,
+                y: monster.y,
+                item,
+            });
         }
     }
 
     /// Updates the game state.
     ///
-    /// Currently empty, will be implemented with game logic.
-    pub fn update(&mut self) {}
+    /// Runs one turn for every monster, then applies the player's status
+    /// effects, burns the fuel of the player's light, uses up food and
+    /// makes a passive check for nearby traps. A fainting player may pass
+    /// out, giving every monster another turn. Once the player has died,
+    /// the state switches to `GameState::GameOver` and no further turns are
+    /// played. No turns are played while the game is paused either.
+    pub fn update(&mut self) {
+        if self.state != GameState::Running {
+            return;
+        }
+        self.turns += 1;
+
+        self.update_monsters();
+        if self.state == GameState::GameOver {
+            return;
+        }
+
+        for message in self.player.tick_status_effects() {
+            self.log(message);
+        }
+        if !self.player.is_alive() {
+            self.die("succumbed to poison");
+            return;
+        }
+
+        self.player.spellbook.tick();
+        self.player.restore_mana(1);
+        if let Some(message) = self.player.burn_light() {
+            self.log(message);
+        }
+        if let Some(message) = self.player.tick_hunger() {
+            self.log(message);
+        }
+        if !self.player.is_alive() {
+            self.die("starved to death");
+            return;
+        }
+        if self.player.hunger() == HungerState::Fainting && self.rng.chance(FAINT_CHANCE) {
+            self.log("You faint from hunger.");
+            self.update_monsters();
+            if self.state == GameState::GameOver {
+                return;
+            }
+        }
+
+        let chance = self.player.speed;
+        self.detect_traps(1, chance);
+        self.refresh_fov();
+    }
+
+    /// Runs one turn for every monster. Monsters that see the player or were
+    /// alerted chase and attack it, opening closed doors if they are able
+    /// to. A player standing in the dark is only seen from half as far.
+    fn update_monsters(&mut self) {
+        let player_pos = (self.player.x, self.player.y);
+        let sight_range = if self.map.light[player_pos] <= LightLevel::Dim {
+            MONSTER_SIGHT_RANGE / 2
+        } else {
+            MONSTER_SIGHT_RANGE
+        };
+
+        for i in (0..self.monsters.len()).rev() {
+            if self.monsters[i].tick_status_effects() {
+                let message = format!("The {} succumbs to its wounds.", self.monsters[i].name);
+                self.log(message);
+                self.kill_monster(i);
+            }
+        }
+
+        for i in 0..self.monsters.len() {
+            let (mx, my) = (self.monsters[i].x, self.monsters[i].y);
+            let distance = (player_pos.0 - mx).abs() + (player_pos.1 - my).abs();
+
+            if !self.monsters[i].alerted
+                && (distance > sight_range || !has_line_of_sight(&self.map, (mx, my), player_pos))
+            {
+                continue;
+            }
+
+            if distance == 1 {
+                let monster = &self.monsters[i];
+                let message = format!("The {} hits you.", monster.name);
+                let cause = format!("killed by a {}", monster.name.to_lowercase());
+                let attack = monster.attack;
+                self.log(message);
+                self.damage_player(attack, &cause);
+                if self.state == GameState::GameOver {
+                    return;
+                }
+                continue;
+            }
+
+            let step = [(0, -1), (0, 1), (-1, 0), (1, 0)]
+                .into_iter()
+                .map(|(dx, dy)| (mx + dx, my + dy))
+                .filter(|&(x, y)| {
+                    (player_pos.0 - x).abs() + (player_pos.1 - y).abs() < distance
+                        && self.monsters[i].can_pass(&self.map, x, y)
+                        && self.monster_at(x, y).is_none()
+                        && self.chest_at(x, y).is_none()
+                        && (x, y) != player_pos
+                })
+                .min_by_key(|&(x, y)| (player_pos.0 - x).abs() + (player_pos.1 - y).abs());
+
+            if let Some((x, y)) = step {
+                if self.map.get_tile(x, y) == Some(&Tile::Door(DoorState::Closed)) {
+                    let _ = self.map.open_door(x, y);
+                } else {
+                    self.monsters[i].x = x;
+                    self.monsters[i].y = y;
+                }
+            }
+        }
+    }
+}
+
+/// Calculates the distance between two points, counting diagonal steps as one.
+fn distance(a: (i32, i32), b: (i32, i32)) -> i32 {
+    (a.0 - b.0).abs().max((a.1 - b.1).abs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{equipment::Slot, spell::Spellbook};
+
+    /// Creates a game on a map drawn as text with the player at `(x, y)`.
+    fn ascii_game(ascii: &str, x: i32, y: i32) -> Game {
+        let mut game = Game::new("Ann".to_string(), Map::from_ascii(ascii).unwrap());
+        game.rng = Rng::new(1);
+        game.player.x = x;
+        game.player.y = y;
+        game.refresh_fov();
+        game
+    }
+
+    /// Counts the arrows the player carries.
+    fn arrows(game: &Game) -> i32 {
+        game.player
+            .inventory
+            .iter()
+            .map(|item| match item.item_type {
+                ItemType::Ammo { quantity } => quantity,
+                _ => 0,
+            })
+            .sum()
+    }
+
+    /// Creates a game with an archer west of a tough rat.
+    fn archery_game() -> Game {
+        let mut game = ascii_game("##########\n#..#.....#\n#........#\n##########", 1, 2);
+        game.player
+            .equipment
+            .set(Slot::MainHand, Some(Item::create_short_bow()));
+        game.player.inventory.push(Item::create_arrows(3));
+        let mut rat = Monster::create_rat(4, 2);
+        rat.health = 500;
+        game.monsters.push(rat);
+        game
+    }
+
+    #[test]
+    fn test_projectile_path_stops_at_walls_monsters_and_range() {
+        let mut game = archery_game();
+        assert_eq!(game.projectile_path((8, 2), 10), [(2, 2), (3, 2), (4, 2)]);
+
+        game.monsters.clear();
+        assert_eq!(game.projectile_path((8, 2), 3), [(2, 2), (3, 2), (4, 2)]);
+        game.player.y = 1;
+        assert_eq!(game.projectile_path((6, 1), 10), [(2, 1)]);
+        assert!(game.projectile_path((1, 1), 10).is_empty());
+    }
+
+    #[test]
+    fn test_fire_at_uses_ammo_only_when_shooting() {
+        let mut game = archery_game();
+        assert_eq!(game.fire_at((1, 2)), None);
+        assert_eq!(arrows(&game), 3);
+        game.player.x = 2;
+        game.player.y = 1;
+        assert_eq!(game.fire_at((3, 1)), None);
+        assert_eq!(arrows(&game), 3);
+
+        game.player.x = 1;
+        game.player.y = 2;
+        let path = game.fire_at((8, 2)).unwrap();
+        assert_eq!(path.last(), Some(&(4, 2)));
+        assert_eq!(arrows(&game), 2);
+        assert!(game.monsters[0].health < 500);
+        assert!(game.items.iter().all(|i| (i.x, i.y) == (4, 2)));
+
+        game.player.inventory.clear();
+        assert_eq!(game.fire_at((8, 2)), None);
+        assert_eq!(game.messages.last().unwrap(), "You have no ammunition.");
+    }
+
+    #[test]
+    fn test_throw_at_keeps_the_item_without_a_path() {
+        let mut game = archery_game();
+        game.player.inventory = vec![Item::create_fire_potion()];
+        game.player.x = 2;
+        game.player.y = 1;
+        game.monsters[0].x = 2;
+        game.monsters[0].y = 2;
+
+        assert_eq!(game.throw_at(0, (3, 1)), None);
+        assert_eq!(
+            game.messages.last().unwrap(),
+            "There is nothing to throw at there."
+        );
+        assert_eq!(game.throw_at(0, (2, 1)), None);
+        assert_eq!(game.player.inventory.len(), 1);
+        assert_eq!(game.monsters[0].health, 500);
+    }
+
+    #[test]
+    fn test_thrown_potions_shatter_on_the_target() {
+        let mut game = archery_game();
+        game.player.inventory = vec![Item::create_sword(), Item::create_poison_potion()];
+
+        assert_eq!(game.throw_at(0, (4, 2)), None);
+        assert_eq!(game.player.inventory.len(), 2);
+        let path = game.throw_at(1, (4, 2)).unwrap();
+        assert_eq!(path.last(), Some(&(4, 2)));
+        assert_eq!(game.player.inventory.len(), 1);
+        assert!(game.monsters[0]
+            .status_effects
+            .iter()
+            .any(|effect| effect.kind == StatusKind::Poisoned));
+    }
+
+    #[test]
+    fn test_spells_cost_mana_and_cool_down_only_when_cast() {
+        let mut game = archery_game();
+        game.player.spellbook = Spellbook::new(&[SpellKind::Bolt, SpellKind::Heal]);
+        game.player.mana = 20;
+
+        assert_eq!(game.cast_spell(0, Some((1, 2))), None);
+        assert_eq!(game.messages.last().unwrap(), "You cannot target yourself.");
+        assert_eq!(game.cast_spell(0, None), None);
+        game.player.x = 2;
+        game.player.y = 1;
+        assert_eq!(game.cast_spell(0, Some((3, 1))), None);
+        assert_eq!(game.player.mana, 20);
+
+        game.player.x = 1;
+        game.player.y = 2;
+        let path = game.cast_spell(0, Some((8, 2))).unwrap();
+        assert_eq!(path.last(), Some(&(4, 2)));
+        assert_eq!(game.player.mana, 15);
+        assert!(game.monsters[0].health < 500);
+
+        assert_eq!(game.cast_spell(1, None), Some(Vec::new()));
+        assert_eq!(game.player.mana, 5);
+        assert_eq!(game.player.spellbook.spells[1].cooldown_left, 8);
+        assert_eq!(game.cast_spell(1, None), None);
+        assert_eq!(
+            game.messages.last().unwrap(),
+            "Heal is not ready yet (8 turns)."
+        );
+        game.player.spellbook.tick();
+        assert_eq!(game.player.spellbook.spells[1].cooldown_left, 7);
+
+        game.player.mana = 4;
+        assert_eq!(game.cast_spell(0, Some((8, 2))), None);
+        assert_eq!(
+            game.messages.last().unwrap(),
+            "You do not have enough mana."
+        );
+    }
+
+    #[test]
+    fn test_stealthy_monsters_hide_in_dim_light() {
+        let mut map = Map::from_ascii("##########\n#........#\n##########").unwrap();
+        map.ambient_light = LightLevel::Dim;
+        let mut game = Game::new("Ann".to_string(), map);
+        game.player.x = 1;
+        game.player.y = 1;
+        game.refresh_fov();
+
+        let goblin = Monster::create_goblin(5, 1);
+        assert!(game.map.is_visible(5, 1));
+        assert!(!game.can_see_monster(&goblin));
+        assert!(game.can_see_monster(&Monster::create_rat(5, 1)));
+        assert!(game.can_see_monster(&Monster::create_goblin(2, 1)));
+
+        game.player.inventory.push(Item::create_torch());
+        game.player.add_item(0).unwrap();
+        game.refresh_fov();
+        assert!(game.can_see_monster(&goblin));
+    }
+
+    #[test]
+    fn test_unlock_door_uses_up_the_key() {
+        let mut game = ascii_game("#####\n#.+.#\n#####", 1, 1);
+        game.map.set_tile(2, 1, Tile::Door(DoorState::Locked(4)));
+
+        assert!(!game.unlock_door(1, 0));
+        assert_eq!(
+            game.messages.last().unwrap(),
+            "You have no key for this lock."
+        );
+
+        game.player.inventory.push(Item::create_key(4, true));
+        assert!(game.unlock_door(1, 0));
+        assert_eq!(
+            game.map.get_tile(2, 1),
+            Some(&Tile::Door(DoorState::Closed))
+        );
+        assert!(game.player.inventory.is_empty());
+        assert!(game.open_door(1, 0));
+        assert_eq!(game.map.get_tile(2, 1), Some(&Tile::Door(DoorState::Open)));
+    }
 }
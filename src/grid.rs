@@ -0,0 +1,304 @@
+//! Grid module with a flat two-dimensional storage for map layers.
+//!
+//! A [`Grid`] keeps its cells row by row in a single vector. Positions are
+//! given as [`Point`]s or `(x, y)` tuples of `i32`, so callers never cast
+//! to `usize`. [`Grid::get`] returns `None` outside the grid, indexing
+//! with `grid[(x, y)]` panics there instead, and [`Grid::get_wrapping`]
+//! leaves the check to debug builds.
+
+use std::ops::{Add, Index, IndexMut};
+
+/// Represents a position on a grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Point {
+    /// The x-coordinate, growing to the right
+    pub x: i32,
+    /// The y-coordinate, growing downwards
+    pub y: i32,
+}
+
+impl Point {
+    /// Offsets of the four straight neighbours: up, down, left, right.
+    pub const STRAIGHT: [Point; 4] = [
+        Point::new(0, -1),
+        Point::new(0, 1),
+        Point::new(-1, 0),
+        Point::new(1, 0),
+    ];
+    /// Offsets of all eight neighbours, row by row.
+    pub const AROUND: [Point; 8] = [
+        Point::new(-1, -1),
+        Point::new(0, -1),
+        Point::new(1, -1),
+        Point::new(-1, 0),
+        Point::new(1, 0),
+        Point::new(-1, 1),
+        Point::new(0, 1),
+        Point::new(1, 1),
+    ];
+
+    /// Creates a new point.
+    pub const fn new(x: i32, y: i32) -> Self {
+        Point { x, y }
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, other: Point) -> Point {
+        Point::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl From<(i32, i32)> for Point {
+    fn from((x, y): (i32, i32)) -> Self {
+        Point::new(x, y)
+    }
+}
+
+impl From<Point> for (i32, i32) {
+    fn from(point: Point) -> Self {
+        (point.x, point.y)
+    }
+}
+
+/// Represents a rectangle of cells stored row by row.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Grid<T> {
+    /// Width in cells
+    width: i32,
+    /// Height in cells
+    height: i32,
+    /// The cells, row by row
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    /// Creates a new grid with every cell set to one value.
+    ///
+    /// # Arguments
+    /// * `width` - Width in cells, negative values count as 0
+    /// * `height` - Height in cells, negative values count as 0
+    /// * `value` - Initial value of every cell
+    pub fn new(width: i32, height: i32, value: T) -> Self {
+        let (width, height) = (width.max(0), height.max(0));
+        Grid {
+            width,
+            height,
+            cells: vec![value; (width * height) as usize],
+        }
+    }
+
+    /// Sets every cell to one value.
+    pub fn fill(&mut self, value: T) {
+        self.cells.fill(value);
+    }
+}
+
+impl<T> Grid<T> {
+    /// Creates a new grid with each cell computed from its position.
+    ///
+    /// # Arguments
+    /// * `width` - Width in cells
+    /// * `height` - Height in cells
+    /// * `cell` - Computes the value of a cell, called row by row
+    pub fn from_fn(width: i32, height: i32, mut cell: impl FnMut(Point) -> T) -> Self {
+        let (width, height) = (width.max(0), height.max(0));
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| Point::new(x, y)))
+            .map(&mut cell)
+            .collect();
+        Grid {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Gets the width in cells.
+    pub fn width(&self) -> i32 {
+        self.width
+    }
+
+    /// Gets the height in cells.
+    pub fn height(&self) -> i32 {
+        self.height
+    }
+
+    /// Checks whether a position lies on the grid.
+    pub fn contains(&self, point: impl Into<Point>) -> bool {
+        let point = point.into();
+        point.x >= 0 && point.y >= 0 && point.x < self.width && point.y < self.height
+    }
+
+    /// Finds the index of a position in the cell vector.
+    fn index_of(&self, point: Point) -> Option<usize> {
+        self.contains(point)
+            .then(|| (point.y * self.width + point.x) as usize)
+    }
+
+    /// Gets a cell.
+    ///
+    /// # Returns
+    /// * `Some(&T)` if the position lies on the grid
+    /// * `None` otherwise
+    pub fn get(&self, point: impl Into<Point>) -> Option<&T> {
+        self.index_of(point.into()).map(|index| &self.cells[index])
+    }
+
+    /// Gets a cell for changing it.
+    ///
+    /// # Returns
+    /// * `Some(&mut T)` if the position lies on the grid
+    /// * `None` otherwise
+    pub fn get_mut(&mut self, point: impl Into<Point>) -> Option<&mut T> {
+        self.index_of(point.into())
+            .map(|index| &mut self.cells[index])
+    }
+
+    /// Gets a cell by its place in the row-by-row storage, without checking
+    /// the coordinates separately.
+    ///
+    /// Meant for hot loops over positions known to be on the grid. Release
+    /// builds do not fail for an x outside the grid but return the wrong
+    /// cell, one of a neighbouring row. Only a position before the first or
+    /// past the last cell panics. Debug builds check the position.
+    pub fn get_wrapping(&self, point: impl Into<Point>) -> &T {
+        let point = point.into();
+        debug_assert!(self.contains(point), "{:?} is outside the grid", point);
+        &self.cells[(point.y * self.width + point.x) as usize]
+    }
+
+    /// Sets a cell.
+    ///
+    /// # Returns
+    /// `true` if the position lies on the grid, `false` if nothing was set
+    pub fn set(&mut self, point: impl Into<Point>, value: T) -> bool {
+        match self.get_mut(point) {
+            Some(cell) => {
+                *cell = value;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Iterates over all cells, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    /// Iterates over all cells with their positions, row by row.
+    pub fn iter_points(&self) -> impl Iterator<Item = (Point, &T)> {
+        let width = self.width.max(1);
+        self.cells.iter().enumerate().map(move |(index, cell)| {
+            (Point::new(index as i32 % width, index as i32 / width), cell)
+        })
+    }
+
+    /// Iterates over the rows from top to bottom.
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1) as usize)
+    }
+
+    /// Iterates over the straight neighbours of a position on the grid.
+    pub fn neighbours(&self, point: impl Into<Point>) -> impl Iterator<Item = Point> + '_ {
+        self.offsets(point.into(), &Point::STRAIGHT)
+    }
+
+    /// Iterates over all eight neighbours of a position on the grid,
+    /// diagonals included.
+    pub fn neighbours_around(&self, point: impl Into<Point>) -> impl Iterator<Item = Point> + '_ {
+        self.offsets(point.into(), &Point::AROUND)
+    }
+
+    /// Iterates over the positions at some offsets that lie on the grid.
+    fn offsets<'a>(
+        &'a self,
+        point: Point,
+        offsets: &'static [Point],
+    ) -> impl Iterator<Item = Point> + 'a {
+        offsets
+            .iter()
+            .map(move |offset| point + *offset)
+            .filter(|neighbour| self.contains(*neighbour))
+    }
+
+    /// Creates a grid of the same size from the cells of this one.
+    pub fn map<U>(&self, mut convert: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(&mut convert).collect(),
+        }
+    }
+}
+
+impl<T, P: Into<Point>> Index<P> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: P) -> &T {
+        let point = point.into();
+        let index = self.index_of(point).unwrap_or_else(|| {
+            panic!(
+                "{:?} is outside the {}x{} grid",
+                point, self.width, self.height
+            )
+        });
+        &self.cells[index]
+    }
+}
+
+impl<T, P: Into<Point>> IndexMut<P> for Grid<T> {
+    fn index_mut(&mut self, point: P) -> &mut T {
+        let point = point.into();
+        let index = self.index_of(point).unwrap_or_else(|| {
+            panic!(
+                "{:?} is outside the {}x{} grid",
+                point, self.width, self.height
+            )
+        });
+        &mut self.cells[index]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_access_and_iterators() {
+        let mut grid = Grid::from_fn(3, 2, |p| p.x + 10 * p.y);
+        assert_eq!(grid.get((2, 1)), Some(&12));
+        assert_eq!(grid.get((3, 0)), None);
+        assert_eq!(grid.get((-1, 0)), None);
+        assert_eq!(grid.get_wrapping(Point::new(1, 1)), &11);
+        assert!(grid.set(Point::new(0, 1), 7));
+        assert!(!grid.set((0, 2), 7));
+        grid[(1, 0)] = 5;
+
+        let rows: Vec<&[i32]> = grid.rows().collect();
+        assert_eq!(rows, vec![&[0, 5, 2][..], &[7, 11, 12][..]]);
+        assert_eq!(grid.iter_points().last(), Some((Point::new(2, 1), &12)));
+        assert_eq!(
+            grid.neighbours((0, 0)).collect::<Vec<_>>(),
+            vec![Point::new(0, 1), Point::new(1, 0)]
+        );
+        assert_eq!(grid.neighbours_around((1, 1)).count(), 5);
+        assert_eq!(
+            grid.map(|cell| cell % 2 == 0)
+                .iter()
+                .filter(|c| **c)
+                .count(),
+            3
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "outside the 3x2 grid")]
+    fn test_index_outside_panics() {
+        let grid = Grid::new(3, 2, 0);
+        let _ = grid[(0, 2)];
+    }
+}
@@ -0,0 +1,208 @@
+//! High score module recording finished runs in a persistent Hall of Fame.
+
+use std::{fs, str::FromStr};
+
+/// Default file the Hall of Fame is stored in.
+pub const HIGH_SCORE_FILE: &str = "highscores.txt";
+/// Maximum number of runs kept in the Hall of Fame.
+const MAX_ENTRIES: usize = 10;
+
+/// Represents a single finished run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HighScore {
+    /// Name of the player character
+    pub name: String,
+    /// Class name of the player character
+    pub class: String,
+    /// Deepest dungeon level reached
+    pub depth: i32,
+    /// Character level at the end of the run
+    pub level: i32,
+    /// Number of turns played
+    pub turns: u32,
+    /// Number of monsters killed
+    pub kills: u32,
+    /// What ended the run
+    pub cause: String,
+}
+
+impl HighScore {
+    /// Calculates the score used to rank runs.
+    ///
+    /// Depth counts the most, followed by character level and kills.
+    pub fn score(&self) -> i64 {
+        self.depth as i64 * 1000 + self.level as i64 * 100 + self.kills as i64 * 10
+    }
+
+    /// Formats the run as one tab-separated line of the high score file.
+    fn to_line(&self) -> String {
+        [
+            self.name.replace('\t', " "),
+            self.class.clone(),
+            self.depth.to_string(),
+            self.level.to_string(),
+            self.turns.to_string(),
+            self.kills.to_string(),
+            self.cause.replace('\t', " "),
+        ]
+        .join("\t")
+    }
+
+    /// Parses one line of the high score file.
+    ///
+    /// # Returns
+    /// * `Ok(HighScore)` if the line is valid
+    /// * `Err(String)` describing what is wrong with the line
+    fn from_line(line: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = line.split('\t').collect();
+        if fields.len() != 7 {
+            return Err(format!("expected 7 fields, found {}", fields.len()));
+        }
+
+        Ok(HighScore {
+            name: fields[0].to_string(),
+            class: fields[1].to_string(),
+            depth: parse_number(fields[2])?,
+            level: parse_number(fields[3])?,
+            turns: parse_number(fields[4])?,
+            kills: parse_number(fields[5])?,
+            cause: fields[6].to_string(),
+        })
+    }
+}
+
+/// Parses a number field of the high score file.
+///
+/// # Returns
+/// * `Ok(T)` with the number
+/// * `Err(String)` if the field is not a number of the type, e.g. negative
+///   for a count
+fn parse_number<T: FromStr>(field: &str) -> Result<T, String> {
+    field
+        .parse::<T>()
+        .map_err(|_| format!("invalid number '{}'", field))
+}
+
+/// Represents the list of best runs, best first.
+#[derive(Debug, Clone, Default)]
+pub struct HallOfFame {
+    /// Recorded runs sorted by score
+    pub entries: Vec<HighScore>,
+}
+
+impl HallOfFame {
+    /// Loads the Hall of Fame from a file.
+    ///
+    /// A missing file yields an empty Hall of Fame.
+    ///
+    /// # Arguments
+    /// * `path` - Path of the high score file
+    ///
+    /// # Returns
+    /// * `Ok(HallOfFame)` with the stored runs
+    /// * `Err(String)` if the file cannot be read or is malformed
+    pub fn load(path: &str) -> Result<Self, String> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(format!("Cannot read {}: {}", path, e)),
+        };
+
+        let mut hall = HallOfFame::default();
+        for (number, line) in content.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry = HighScore::from_line(line)
+                .map_err(|e| format!("{} line {}: {}", path, number + 1, e))?;
+            hall.entries.push(entry);
+        }
+        hall.entries.sort_by_key(|entry| -entry.score());
+        Ok(hall)
+    }
+
+    /// Writes the Hall of Fame to a file.
+    ///
+    /// # Arguments
+    /// * `path` - Path of the high score file
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(String)` if the file cannot be written
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        let content: String = self
+            .entries
+            .iter()
+            .map(|entry| entry.to_line() + "\n")
+            .collect();
+        fs::write(path, content).map_err(|e| format!("Cannot write {}: {}", path, e))
+    }
+
+    /// Adds a run, keeping only the best runs.
+    ///
+    /// # Arguments
+    /// * `entry` - The finished run
+    ///
+    /// # Returns
+    /// * `Some(usize)` with the 0-based rank of the run
+    /// * `None` if the run did not make it into the Hall of Fame
+    pub fn add(&mut self, entry: HighScore) -> Option<usize> {
+        let rank = self
+            .entries
+            .iter()
+            .position(|other| other.score() < entry.score())
+            .unwrap_or(self.entries.len());
+        if rank >= MAX_ENTRIES {
+            return None;
+        }
+
+        self.entries.insert(rank, entry);
+        self.entries.truncate(MAX_ENTRIES);
+        Some(rank)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(name: &str, depth: i32) -> HighScore {
+        HighScore {
+            name: name.to_string(),
+            class: "Rogue".to_string(),
+            depth,
+            level: 3,
+            turns: 420,
+            kills: 7,
+            cause: "killed by a goblin".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_add_keeps_best_runs_in_order() {
+        let mut hall = HallOfFame::default();
+        for depth in 1..=MAX_ENTRIES as i32 {
+            hall.add(run("Filler", depth));
+        }
+
+        assert_eq!(hall.add(run("Low", 0)), None);
+        assert_eq!(hall.add(run("Best", 20)), Some(0));
+        assert_eq!(hall.entries.len(), MAX_ENTRIES);
+        assert_eq!(hall.entries[1].depth, MAX_ENTRIES as i32);
+    }
+
+    #[test]
+    fn test_line_round_trip() {
+        let entry = run("Tab\tName", 4);
+        let parsed = HighScore::from_line(&entry.to_line()).unwrap();
+
+        assert_eq!(parsed.name, "Tab Name");
+        assert_eq!(parsed.depth, 4);
+        assert!(HighScore::from_line("broken").is_err());
+        let negative = entry.to_line().replace("\t420\t", "\t-420\t");
+        assert_eq!(
+            HighScore::from_line(&negative).unwrap_err(),
+            "invalid number '-420'"
+        );
+    }
+}
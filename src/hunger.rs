@@ -0,0 +1,126 @@
+//! Hunger module with the food clock of the player.
+//!
+//! The player's food goes down by one every turn and is restored by eating.
+//! The less food is left, the worse the [`HungerState`] and its stat
+//! penalty. At 0 the player starves and loses health every turn.
+
+use crate::character::Stats;
+
+/// Most food the player can have.
+pub const MAX_FOOD: i32 = 2000;
+/// Food of a new player.
+pub const STARTING_FOOD: i32 = 1200;
+
+/// Represents how hungry the player is, best fed first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HungerState {
+    /// Ate too much, slightly slowed down
+    Satiated,
+    /// No effects
+    NotHungry,
+    /// Slightly weakened
+    Hungry,
+    /// Clearly weakened
+    Weak,
+    /// Badly weakened and may pass out
+    Fainting,
+}
+
+impl HungerState {
+    /// All hunger states, best fed first.
+    pub const ALL: [HungerState; 5] = [
+        HungerState::Satiated,
+        HungerState::NotHungry,
+        HungerState::Hungry,
+        HungerState::Weak,
+        HungerState::Fainting,
+    ];
+
+    /// Gets the display name of the hunger state.
+    ///
+    /// # Returns
+    /// A short name as shown in the stats panel
+    pub fn name(&self) -> &'static str {
+        match self {
+            HungerState::Satiated => "Satiated",
+            HungerState::NotHungry => "Not hungry",
+            HungerState::Hungry => "Hungry",
+            HungerState::Weak => "Weak",
+            HungerState::Fainting => "Fainting",
+        }
+    }
+
+    /// Gets the hunger state for an amount of food.
+    ///
+    /// # Arguments
+    /// * `food` - Food the player has left
+    ///
+    /// # Returns
+    /// The matching hunger state
+    pub fn from_food(food: i32) -> HungerState {
+        match food {
+            f if f > 1500 => HungerState::Satiated,
+            f if f > 300 => HungerState::NotHungry,
+            f if f > 150 => HungerState::Hungry,
+            f if f > 50 => HungerState::Weak,
+            _ => HungerState::Fainting,
+        }
+    }
+
+    /// Gets the stat penalty of the hunger state.
+    ///
+    /// # Returns
+    /// Stats to add to the player's stats, zero or negative
+    pub fn penalty(&self) -> Stats {
+        match self {
+            HungerState::Satiated => Stats::new(0, 0, 0, -1),
+            HungerState::NotHungry => Stats::new(0, 0, 0, 0),
+            HungerState::Hungry => Stats::new(0, -1, 0, 0),
+            HungerState::Weak => Stats::new(0, -3, -1, -2),
+            HungerState::Fainting => Stats::new(0, -5, -3, -4),
+        }
+    }
+
+    /// Gets the message shown when the player gets into this state.
+    ///
+    /// # Returns
+    /// A full sentence describing how the player feels
+    pub fn message(&self) -> &'static str {
+        match self {
+            HungerState::Satiated => "You are stuffed.",
+            HungerState::NotHungry => "You are no longer hungry.",
+            HungerState::Hungry => "You are getting hungry.",
+            HungerState::Weak => "You feel weak from hunger.",
+            HungerState::Fainting => "You are fainting from hunger!",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Item, Player};
+
+    #[test]
+    fn test_hunger_weakens_and_food_restores() {
+        let mut player = Player::new("Ann".to_string());
+        let attack = player.attack;
+        player.food = 301;
+        assert_eq!(
+            player.tick_hunger(),
+            Some("You are getting hungry.".to_string())
+        );
+        assert_eq!(player.hunger(), HungerState::Hungry);
+        assert_eq!(player.attack, attack - 1);
+
+        player.inventory.push(Item::create_food_ration());
+        player.use_item(0).unwrap();
+        assert_eq!(player.food, 1100);
+        assert_eq!(player.attack, attack);
+
+        player.food = 0;
+        let health = player.health;
+        assert_eq!(player.tick_hunger(), None);
+        assert_eq!(player.health, health - 1);
+    }
+}
@@ -0,0 +1,219 @@
+//! Identification module hiding what potions and scrolls do until they are
+//! used.
+//!
+//! Every run gives each potion kind a random colour and each scroll kind a
+//! random label. Items of an unknown kind are shown by their appearance,
+//! with a price hint as the only clue. Drinking, throwing or reading one,
+//! or a scroll of identify, makes the kind known for the rest of the run.
+//! Tomes are always known.
+
+use crate::{
+    item::{PotionKind, ScrollKind},
+    rng::Rng,
+    spell::SpellKind,
+    Item, ItemType,
+};
+
+/// Colours potions can have, at least one per potion kind.
+const POTION_COLOURS: [&str; 8] = [
+    "murky green",
+    "bubbling red",
+    "cloudy white",
+    "smoky black",
+    "glowing blue",
+    "fizzy orange",
+    "oily yellow",
+    "sparkling pink",
+];
+/// Labels scrolls can have, at least one per scroll kind.
+const SCROLL_LABELS: [&str; 8] = [
+    "ZELGO MER",
+    "FOOBIE BLETCH",
+    "XIXAXA XOXAXA",
+    "ELBIB YLOH",
+    "VERR YED HORRE",
+    "KIRJE",
+    "ANDOVA BEGARIN",
+    "THARR",
+];
+
+/// Represents a kind of item that has to be identified.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MagicKind {
+    /// A kind of potion
+    Potion(PotionKind),
+    /// A kind of scroll, tomes excluded
+    Scroll(ScrollKind),
+}
+
+impl MagicKind {
+    /// Lists every kind that has to be identified.
+    ///
+    /// # Returns
+    /// All potion kinds, then a scroll for every spell and the scroll of
+    /// identify
+    pub fn all() -> Vec<MagicKind> {
+        let potions = PotionKind::ALL.into_iter().map(MagicKind::Potion);
+        let scrolls = SpellKind::ALL
+            .into_iter()
+            .map(|spell| MagicKind::Scroll(ScrollKind::Cast(spell)))
+            .chain([MagicKind::Scroll(ScrollKind::Identify)]);
+        potions.chain(scrolls).collect()
+    }
+
+    /// Gets the item type of the kind.
+    pub fn item_type(&self) -> ItemType {
+        match *self {
+            MagicKind::Potion(kind) => ItemType::Potion(kind),
+            MagicKind::Scroll(kind) => ItemType::Scroll(kind),
+        }
+    }
+
+    /// Gets the kind of an item.
+    ///
+    /// # Returns
+    /// * `Some(MagicKind)` for potions and scrolls
+    /// * `None` for items that are always known
+    pub fn of(item: &Item) -> Option<MagicKind> {
+        match item.item_type {
+            ItemType::Potion(kind) => Some(MagicKind::Potion(kind)),
+            ItemType::Scroll(ScrollKind::Teach(_)) => None,
+            ItemType::Scroll(kind) => Some(MagicKind::Scroll(kind)),
+            _ => None,
+        }
+    }
+}
+
+/// Represents how a kind of item looks in this run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Appearance {
+    /// The kind of item
+    pub kind: MagicKind,
+    /// Name shown while the kind is unknown, e.g. "murky green potion"
+    pub name: String,
+    /// Whether the player has identified the kind
+    pub known: bool,
+}
+
+/// Represents the appearances of all unknown kinds in a run.
+///
+/// Kinds without an appearance count as known, so the default value knows
+/// every item.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Identification {
+    /// Appearance of every kind that has to be identified
+    pub appearances: Vec<Appearance>,
+}
+
+impl Identification {
+    /// Gives every kind a random appearance, all of them unknown.
+    ///
+    /// # Arguments
+    /// * `rng` - Random number generator of the run
+    ///
+    /// # Returns
+    /// A new Identification knowing no potion or scroll
+    pub fn new(rng: &mut Rng) -> Self {
+        let mut colours = POTION_COLOURS.to_vec();
+        let mut labels = SCROLL_LABELS.to_vec();
+        let appearances = MagicKind::all()
+            .into_iter()
+            .map(|kind| {
+                let name = match kind {
+                    MagicKind::Potion(_) => format!("{} potion", take_random(&mut colours, rng)),
+                    MagicKind::Scroll(_) => {
+                        format!("scroll labeled {}", take_random(&mut labels, rng))
+                    }
+                };
+                Appearance {
+                    kind,
+                    name,
+                    known: false,
+                }
+            })
+            .collect();
+        Identification { appearances }
+    }
+
+    /// Finds the appearance of an item's kind.
+    fn appearance(&self, item: &Item) -> Option<&Appearance> {
+        let kind = MagicKind::of(item)?;
+        self.appearances.iter().find(|a| a.kind == kind)
+    }
+
+    /// Checks whether the player knows what an item does.
+    pub fn is_known(&self, item: &Item) -> bool {
+        self.appearance(item).is_none_or(|a| a.known)
+    }
+
+    /// Makes the kind of an item known.
+    ///
+    /// # Returns
+    /// `true` if the kind was unknown before
+    pub fn identify(&mut self, item: &Item) -> bool {
+        let Some(kind) = MagicKind::of(item) else {
+            return false;
+        };
+        match self.appearances.iter_mut().find(|a| a.kind == kind) {
+            Some(appearance) if !appearance.known => {
+                appearance.known = true;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Gets the name an item is shown by.
+    ///
+    /// # Returns
+    /// The display name of a known item, the appearance otherwise
+    pub fn name(&self, item: &Item) -> String {
+        match self.appearance(item) {
+            Some(appearance) if !appearance.known => appearance.name.clone(),
+            _ => item.display_name(),
+        }
+    }
+
+    /// Describes an item to the player.
+    ///
+    /// # Returns
+    /// The description of a known item, a price hint otherwise
+    pub fn describe(&self, item: &Item) -> String {
+        if self.is_known(item) {
+            item.description.clone()
+        } else {
+            format!(
+                "You do not know what it does. It might fetch {} gold.",
+                item.price()
+            )
+        }
+    }
+}
+
+/// Removes a random entry from a pool of names.
+fn take_random(pool: &mut Vec<&'static str>, rng: &mut Rng) -> &'static str {
+    pool.swap_remove(rng.range(0, pool.len() as i32) as usize)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unknown_items_show_appearances_until_identified() {
+        let mut identification = Identification::new(&mut Rng::new(7));
+        assert_eq!(identification.appearances.len(), MagicKind::all().len());
+
+        let potion = Item::create_health_potion();
+        let name = identification.name(&potion);
+        assert!(name.ends_with(" potion"), "{}", name);
+        assert!(identification.describe(&potion).contains("40 gold"));
+        assert!(identification.is_known(&Item::create_sword()));
+        assert!(identification.is_known(&Item::create_tome(SpellKind::Bolt)));
+
+        assert!(identification.identify(&Item::create_greater_health_potion()));
+        assert!(!identification.identify(&potion));
+        assert_eq!(identification.name(&potion), "Health Potion");
+        assert!(!identification.is_known(&Item::create_fire_potion()));
+    }
+}
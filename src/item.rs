@@ -1,16 +1,91 @@
 //! Item system module for managing game items and equipment.
 
+use crate::{
+    affix::{Affix, Rarity},
+    character::Stats,
+    equipment::Slot,
+    spell::SpellKind,
+};
+
+/// Represents the different effects a potion can have.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PotionKind {
+    /// Restores health
+    Healing,
+    /// Burns whoever it touches
+    Fire,
+    /// Poisons whoever it touches
+    Poison,
+}
+
+impl PotionKind {
+    /// All potion kinds.
+    pub const ALL: [PotionKind; 3] = [PotionKind::Healing, PotionKind::Fire, PotionKind::Poison];
+}
+
+/// Represents what happens when a scroll is read.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ScrollKind {
+    /// Casts the spell once without using mana
+    Cast(SpellKind),
+    /// Teaches the spell permanently
+    Teach(SpellKind),
+    /// Identifies an unknown item in the inventory
+    Identify,
+}
+
 /// Represents different types of items in the game.
 #[derive(Debug, Clone)]
 pub enum ItemType {
     /// Weapons used for combat
-    Weapon,
-    /// Armor for defense
-    Armor,
-    /// Consumable potions
-    Potion,
+    Weapon {
+        /// Whether the weapon needs the off hand as well
+        two_handed: bool,
+    },
+    /// Weapons that shoot ammunition at a distance
+    RangedWeapon {
+        /// Maximum distance in tiles a shot can travel
+        range: i32,
+    },
+    /// Ammunition for ranged weapons
+    Ammo {
+        /// Number of shots left in the stack
+        quantity: i32,
+    },
+    /// Armor for defense, worn in the given slot
+    Armor(Slot),
+    /// Consumable potions that can be drunk or thrown
+    Potion(PotionKind),
+    /// Consumable scrolls that cast or teach spells
+    Scroll(ScrollKind),
     /// Keys for unlocking doors or chests
-    Key,
+    Key {
+        /// Id matching the lock this key opens
+        key_id: u32,
+        /// Whether the key is used up when unlocking
+        consumed_on_use: bool,
+    },
+    /// Consumable food that stills hunger
+    Food,
+    /// Light sources carried by the player
+    Light {
+        /// Turns of light left before the item burns out
+        fuel: i32,
+    },
+    /// Rings and amulets, worn in the given slot, whose only bonus comes
+    /// from their affixes
+    Jewelry(Slot),
+}
+
+/// Represents an item lying on the map.
+#[derive(Debug, Clone)]
+pub struct MapItem {
+    /// X-coordinate on the map
+    pub x: i32,
+    /// Y-coordinate on the map
+    pub y: i32,
+    /// The item itself
+    pub item: Item,
 }
 
 /// Represents an item in the game.
@@ -20,10 +95,15 @@ pub struct Item {
     pub name: String,
     /// Type category of the item
     pub item_type: ItemType,
-    /// Numerical value (damage for weapons, defense for armor, healing for potions)
+    /// Numerical value (damage for weapons, defense for armor, strength for
+    /// potions, nutrition for food, radius for lights, 0 for jewelry)
     pub value: i32,
     /// Descriptive text about the item
     pub description: String,
+    /// How rare the item is
+    pub rarity: Rarity,
+    /// Prefixes and suffixes adding to the stats of the wearer
+    pub affixes: Vec<Affix>,
 }
 
 impl Item {
@@ -36,16 +116,147 @@ impl Item {
     /// * `description` - A description of the item
     ///
     /// # Returns
-    /// A new Item instance with the specified properties
+    /// A new common Item instance with the specified properties and no
+    /// affixes
     pub fn new(name: String, item_type: ItemType, value: i32, description: String) -> Self {
         Item {
             name,
             item_type,
             value,
             description,
+            rarity: Rarity::Common,
+            affixes: Vec::new(),
+        }
+    }
+
+    /// Gets the map symbol used to draw the item on the floor.
+    ///
+    /// # Returns
+    /// A character depending on the item type
+    pub fn glyph(&self) -> char {
+        match self.item_type {
+            ItemType::Weapon { .. } => ')',
+            ItemType::RangedWeapon { .. } => '}',
+            ItemType::Ammo { .. } => '/',
+            ItemType::Armor(_) => '[',
+            ItemType::Potion(_) => '!',
+            ItemType::Scroll(_) => '?',
+            ItemType::Key { .. } => '-',
+            ItemType::Food => '%',
+            ItemType::Light { .. } => '(',
+            ItemType::Jewelry(_) => '"',
+        }
+    }
+
+    /// Gets the name shown in the inventory and messages.
+    ///
+    /// # Returns
+    /// The item name between its prefixes and suffixes, with the remaining
+    /// quantity for ammunition and the remaining fuel for lights
+    pub fn display_name(&self) -> String {
+        let mut words: Vec<&str> = self
+            .affixes
+            .iter()
+            .filter(|affix| affix.kind.is_prefix())
+            .map(|affix| affix.kind.name())
+            .collect();
+        words.push(&self.name);
+        words.extend(
+            self.affixes
+                .iter()
+                .filter(|affix| !affix.kind.is_prefix())
+                .map(|affix| affix.kind.name()),
+        );
+        let name = words.join(" ");
+
+        match self.item_type {
+            ItemType::Ammo { quantity } => format!("{} ({})", name, quantity),
+            ItemType::Light { fuel } => format!("{} ({} turns)", name, fuel),
+            _ => name,
+        }
+    }
+
+    /// Gets the stats the affixes of the item add together.
+    pub fn bonus(&self) -> Stats {
+        self.affixes
+            .iter()
+            .fold(Stats::new(0, 0, 0, 0), |sum, affix| sum.add(affix.bonus()))
+    }
+
+    /// Gets what a merchant would pay for the item, the only hint to what
+    /// an unidentified item does.
+    ///
+    /// # Returns
+    /// The price in gold
+    pub fn price(&self) -> i32 {
+        match self.item_type {
+            ItemType::Weapon { .. } | ItemType::RangedWeapon { .. } => {
+                self.value * 10 * self.worth()
+            }
+            ItemType::Ammo { quantity } => quantity * 2,
+            ItemType::Armor(_) => self.value * 15 * self.worth(),
+            ItemType::Potion(PotionKind::Healing) => self.value * 2,
+            ItemType::Potion(PotionKind::Fire) => 60,
+            ItemType::Potion(PotionKind::Poison) => 30,
+            ItemType::Scroll(ScrollKind::Cast(spell)) => 20 + spell.spell().cost * 5,
+            ItemType::Scroll(ScrollKind::Teach(spell)) => 100 + spell.spell().cost * 20,
+            ItemType::Scroll(ScrollKind::Identify) => 20,
+            ItemType::Key { .. } => 0,
+            ItemType::Food => self.value / 20,
+            ItemType::Light { fuel } => fuel / 20,
+            ItemType::Jewelry(_) => 25 * self.worth(),
+        }
+    }
+
+    /// Gets how many times the price of a plain item the item is worth.
+    fn worth(&self) -> i32 {
+        1 + self.affixes.iter().map(|affix| affix.power).sum::<i32>()
+    }
+
+    /// Gets the slot the item is worn in.
+    ///
+    /// # Returns
+    /// * `Some(Slot)` for weapons, armor and jewelry, the left ring slot for
+    ///   all rings
+    /// * `None` for items that cannot be worn
+    pub fn slot(&self) -> Option<Slot> {
+        match self.item_type {
+            ItemType::Weapon { .. } | ItemType::RangedWeapon { .. } => Some(Slot::MainHand),
+            ItemType::Armor(slot) | ItemType::Jewelry(slot) => Some(slot),
+            _ => None,
         }
     }
 
+    /// Checks whether the item needs both hands, like heavy weapons and all
+    /// ranged weapons.
+    pub fn is_two_handed(&self) -> bool {
+        matches!(
+            self.item_type,
+            ItemType::Weapon { two_handed: true } | ItemType::RangedWeapon { .. }
+        )
+    }
+
+    /// Checks whether the item can be thrown at a target.
+    ///
+    /// # Returns
+    /// `true` for potions, which shatter on impact
+    pub fn is_throwable(&self) -> bool {
+        matches!(self.item_type, ItemType::Potion(_))
+    }
+
+    /// Creates a dagger weapon.
+    ///
+    /// # Returns
+    /// A pre-configured dagger item with 6 damage value
+    pub fn create_dagger() -> Self {
+        Item::new(
+            "Dagger".to_string(),
+            ItemType::Weapon { two_handed: false },
+            6,
+            "A short, light blade.".to_string(),
+        )
+    }
+
     /// Creates a basic sword weapon.
     ///
     /// # Returns
@@ -55,12 +266,160 @@ impl Item {
     pub fn create_sword() -> Self {
         Item::new(
             "Sword".to_string(),
-            ItemType::Weapon,
+            ItemType::Weapon { two_handed: false },
             10,
             "A simple sword.".to_string(),
         )
     }
 
+    /// Creates a battle axe weapon.
+    ///
+    /// # Returns
+    /// A pre-configured axe item with 15 damage value
+    pub fn create_battle_axe() -> Self {
+        Item::new(
+            "Battle Axe".to_string(),
+            ItemType::Weapon { two_handed: true },
+            15,
+            "A heavy axe with a wide blade. Needs both hands.".to_string(),
+        )
+    }
+
+    /// Creates a short bow ranged weapon.
+    ///
+    /// # Returns
+    /// A pre-configured bow with 8 damage value and a range of 8 tiles
+    pub fn create_short_bow() -> Self {
+        Item::new(
+            "Short Bow".to_string(),
+            ItemType::RangedWeapon { range: 8 },
+            8,
+            "A bow of yew. Needs arrows and both hands.".to_string(),
+        )
+    }
+
+    /// Creates a stack of arrows.
+    ///
+    /// # Arguments
+    /// * `quantity` - Number of arrows in the stack
+    ///
+    /// # Returns
+    /// A pre-configured stack of arrows
+    pub fn create_arrows(quantity: i32) -> Self {
+        Item::new(
+            "Arrows".to_string(),
+            ItemType::Ammo { quantity },
+            0,
+            "Ammunition for bows.".to_string(),
+        )
+    }
+
+    /// Creates a leather armor.
+    ///
+    /// # Returns
+    /// A pre-configured armor item with 3 defense value
+    pub fn create_leather_armor() -> Self {
+        Item::new(
+            "Leather Armor".to_string(),
+            ItemType::Armor(Slot::Body),
+            3,
+            "Boiled leather, light and quiet.".to_string(),
+        )
+    }
+
+    /// Creates a chain mail armor.
+    ///
+    /// # Returns
+    /// A pre-configured armor item with 6 defense value
+    pub fn create_chain_mail() -> Self {
+        Item::new(
+            "Chain Mail".to_string(),
+            ItemType::Armor(Slot::Body),
+            6,
+            "Interlocking iron rings.".to_string(),
+        )
+    }
+
+    /// Creates an iron helmet.
+    ///
+    /// # Returns
+    /// A pre-configured head armor with 2 defense value
+    pub fn create_helmet() -> Self {
+        Item::new(
+            "Iron Helmet".to_string(),
+            ItemType::Armor(Slot::Head),
+            2,
+            "A dented iron cap.".to_string(),
+        )
+    }
+
+    /// Creates a pair of leather gloves.
+    ///
+    /// # Returns
+    /// A pre-configured hand armor with 1 defense value
+    pub fn create_gloves() -> Self {
+        Item::new(
+            "Leather Gloves".to_string(),
+            ItemType::Armor(Slot::Hands),
+            1,
+            "Worn gloves with a firm grip.".to_string(),
+        )
+    }
+
+    /// Creates a pair of boots.
+    ///
+    /// # Returns
+    /// A pre-configured foot armor with 1 defense value
+    pub fn create_boots() -> Self {
+        Item::new(
+            "Boots".to_string(),
+            ItemType::Armor(Slot::Feet),
+            1,
+            "Sturdy boots for long walks.".to_string(),
+        )
+    }
+
+    /// Creates a wooden shield.
+    ///
+    /// # Returns
+    /// A pre-configured off hand armor with 2 defense value
+    pub fn create_wooden_shield() -> Self {
+        Item::new(
+            "Wooden Shield".to_string(),
+            ItemType::Armor(Slot::OffHand),
+            2,
+            "A round shield. Cannot be used with a two-handed weapon.".to_string(),
+        )
+    }
+
+    /// Creates a plain ring. Rings only help once they have affixes, see
+    /// [`enchant`](crate::affix::enchant).
+    ///
+    /// # Returns
+    /// A pre-configured ring worn on either hand
+    pub fn create_ring() -> Self {
+        Item::new(
+            "Ring".to_string(),
+            ItemType::Jewelry(Slot::LeftRing),
+            0,
+            "A small band of silver.".to_string(),
+        )
+    }
+
+    /// Creates a plain amulet. Amulets only help once they have affixes, see
+    /// [`enchant`](crate::affix::enchant).
+    ///
+    /// # Returns
+    /// A pre-configured amulet worn around the neck
+    pub fn create_amulet() -> Self {
+        Item::new(
+            "Amulet".to_string(),
+            ItemType::Jewelry(Slot::Amulet),
+            0,
+            "A pendant on a thin chain.".to_string(),
+        )
+    }
+
     /// Creates a health potion.
     ///
     /// # Returns
@@ -70,9 +429,163 @@ impl Item {
     pub fn create_health_potion() -> Self {
         Item::new(
             "Health Potion".to_string(),
-            ItemType::Potion,
+            ItemType::Potion(PotionKind::Healing),
             20,
             "Restores 20 health.".to_string(),
         )
     }
+
+    /// Creates a key for a lock.
+    ///
+    /// # Arguments
+    /// * `key_id` - Id of the lock the key opens
+    /// * `consumed_on_use` - Whether the key breaks after unlocking
+    ///
+    /// # Returns
+    /// A pre-configured key item, either a single-use iron key or a
+    /// reusable brass key
+    pub fn create_key(key_id: u32, consumed_on_use: bool) -> Self {
+        let (name, description) = if consumed_on_use {
+            ("Iron Key", "A crude key. It will break after one use.")
+        } else {
+            ("Brass Key", "A sturdy key that can be used again.")
+        };
+
+        Item::new(
+            name.to_string(),
+            ItemType::Key {
+                key_id,
+                consumed_on_use,
+            },
+            0,
+            description.to_string(),
+        )
+    }
+
+    /// Creates a food ration.
+    ///
+    /// # Returns
+    /// A pre-configured ration worth 800 turns of food
+    pub fn create_food_ration() -> Self {
+        Item::new(
+            "Food Ration".to_string(),
+            ItemType::Food,
+            800,
+            "Hard bread and dried meat.".to_string(),
+        )
+    }
+
+    /// Creates an apple.
+    ///
+    /// # Returns
+    /// A pre-configured apple worth 200 turns of food
+    pub fn create_apple() -> Self {
+        Item::new(
+            "Apple".to_string(),
+            ItemType::Food,
+            200,
+            "A bit wrinkled, but still crisp.".to_string(),
+        )
+    }
+
+    /// Creates a torch.
+    ///
+    /// # Returns
+    /// A pre-configured torch lighting 5 tiles around the player for 400
+    /// turns
+    pub fn create_torch() -> Self {
+        Item::new(
+            "Torch".to_string(),
+            ItemType::Light { fuel: 400 },
+            5,
+            "A stick of pitch-soaked wood.".to_string(),
+        )
+    }
+
+    /// Creates a greater health potion.
+    ///
+    /// # Returns
+    /// A pre-configured health potion with 50 healing value
+    pub fn create_greater_health_potion() -> Self {
+        Item::new(
+            "Greater Health Potion".to_string(),
+            ItemType::Potion(PotionKind::Healing),
+            50,
+            "Restores 50 health.".to_string(),
+        )
+    }
+
+    /// Creates a scroll that casts a spell once.
+    ///
+    /// # Arguments
+    /// * `kind` - The spell the scroll casts
+    ///
+    /// # Returns
+    /// A pre-configured scroll named after the spell
+    pub fn create_scroll(kind: SpellKind) -> Self {
+        let spell = kind.spell();
+        Item::new(
+            format!("Scroll of {}", spell.name),
+            ItemType::Scroll(ScrollKind::Cast(kind)),
+            spell.power,
+            format!("Casts {} once when read.", spell.name),
+        )
+    }
+
+    /// Creates a tome that teaches a spell.
+    ///
+    /// # Arguments
+    /// * `kind` - The spell the tome teaches
+    ///
+    /// # Returns
+    /// A pre-configured tome named after the spell
+    pub fn create_tome(kind: SpellKind) -> Self {
+        let spell = kind.spell();
+        Item::new(
+            format!("Tome of {}", spell.name),
+            ItemType::Scroll(ScrollKind::Teach(kind)),
+            spell.power,
+            format!("Teaches the {} spell.", spell.name),
+        )
+    }
+
+    /// Creates a scroll of identify.
+    ///
+    /// # Returns
+    /// A pre-configured scroll that identifies an unknown item
+    pub fn create_identify_scroll() -> Self {
+        Item::new(
+            "Scroll of Identify".to_string(),
+            ItemType::Scroll(ScrollKind::Identify),
+            0,
+            "Reveals what an unknown potion or scroll does.".to_string(),
+        )
+    }
+
+    /// Creates a fire potion.
+    ///
+    /// # Returns
+    /// A pre-configured fire potion with 20 damage value that is meant to
+    /// be thrown
+    pub fn create_fire_potion() -> Self {
+        Item::new(
+            "Fire Potion".to_string(),
+            ItemType::Potion(PotionKind::Fire),
+            20,
+            "Bursts into flames when shattered.".to_string(),
+        )
+    }
+
+    /// Creates a poison potion.
+    ///
+    /// # Returns
+    /// A pre-configured poison potion dealing 3 damage per turn
+    pub fn create_poison_potion() -> Self {
+        Item::new(
+            "Poison Potion".to_string(),
+            ItemType::Potion(PotionKind::Poison),
+            3,
+            "A cloud of toxic fumes in a bottle.".to_string(),
+        )
+    }
 }
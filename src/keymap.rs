@@ -0,0 +1,338 @@
+//! Keymap module mapping typed keys to commands.
+//!
+//! A keymap starts from one of the built-in presets, picked in the options,
+//! and can be changed by a config file with lines of the form
+//! `command = key key ...`, e.g. `move_north = k 8`.
+
+use std::fs;
+
+use crate::command::{Command, Direction};
+
+/// Default file the key bindings are loaded from.
+pub const KEYMAP_FILE: &str = "keys.cfg";
+
+/// Represents the built-in sets of key bindings.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Preset {
+    /// `w a s d` to move, no diagonal moves
+    Wasd,
+    /// `h j k l y u b n` to move, like vi and classic roguelikes
+    Vi,
+    /// Number keys `1`-`9` to move, with `5` to wait
+    Numpad,
+}
+
+impl Preset {
+    /// All presets in the order they are offered to the player.
+    pub const ALL: [Preset; 3] = [Preset::Wasd, Preset::Vi, Preset::Numpad];
+
+    /// Gets the name used for the preset in config files.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Preset::Wasd => "wasd",
+            Preset::Vi => "vi",
+            Preset::Numpad => "numpad",
+        }
+    }
+
+    /// Finds a preset by its name.
+    ///
+    /// # Returns
+    /// * `Some(Preset)` if a preset with this name exists
+    /// * `None` otherwise
+    pub fn from_name(name: &str) -> Option<Preset> {
+        Preset::ALL.into_iter().find(|preset| preset.name() == name)
+    }
+
+    /// Gets the key bindings of the preset.
+    fn bindings(&self) -> Vec<(&'static str, Command)> {
+        use Direction::*;
+
+        let moves: &[(&str, Direction)] = match self {
+            Preset::Wasd => &[("w", North), ("s", South), ("a", West), ("d", East)],
+            Preset::Vi => &[
+                ("k", North),
+                ("j", South),
+                ("h", West),
+                ("l", East),
+                ("y", NorthWest),
+                ("u", NorthEast),
+                ("b", SouthWest),
+                ("n", SouthEast),
+            ],
+            Preset::Numpad => &[
+                ("8", North),
+                ("2", South),
+                ("4", West),
+                ("6", East),
+                ("7", NorthWest),
+                ("9", NorthEast),
+                ("1", SouthWest),
+                ("3", SouthEast),
+            ],
+        };
+        let (unlock, search, look, wait, next_target) = match self {
+            Preset::Wasd => ("u", "x", "l", ".", "n"),
+            Preset::Vi => ("U", "s", "x", ".", "+"),
+            Preset::Numpad => ("u", "x", "l", "5", "+"),
+        };
+
+        let mut bindings: Vec<(&str, Command)> = moves
+            .iter()
+            .map(|&(key, direction)| (key, Command::Move(direction)))
+            .collect();
+        bindings.extend([
+            ("o", Command::Open(None)),
+            ("c", Command::Close(None)),
+            (unlock, Command::Unlock(None)),
+            ("g", Command::PickUp),
+            (search, Command::Search),
+            (">", Command::Descend),
+            ("e", Command::Equip(None)),
+            ("r", Command::Use(None, None)),
+            ("t", Command::Throw(None, None)),
+            ("f", Command::Fire(None)),
+            ("z", Command::Cast(None, None)),
+            (look, Command::Look),
+            (wait, Command::Wait),
+            (next_target, Command::NextTarget),
+            ("q", Command::Menu),
+            ("?", Command::Help),
+        ]);
+        bindings
+    }
+}
+
+/// Represents the active mapping from keys to commands.
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    /// Preset the keymap started from
+    pub preset: Preset,
+    /// Bound keys with their commands, in help screen order
+    bindings: Vec<(String, Command)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Keymap::new(Preset::Wasd)
+    }
+}
+
+impl Keymap {
+    /// Creates a keymap from a preset.
+    ///
+    /// # Arguments
+    /// * `preset` - The preset to take the bindings from
+    ///
+    /// # Returns
+    /// A new Keymap with the preset's bindings
+    pub fn new(preset: Preset) -> Self {
+        Keymap {
+            preset,
+            bindings: preset
+                .bindings()
+                .into_iter()
+                .map(|(key, command)| (key.to_string(), command))
+                .collect(),
+        }
+    }
+
+    /// Loads a keymap from a config file.
+    ///
+    /// A missing file yields the plain preset.
+    ///
+    /// # Arguments
+    /// * `path` - Path of the config file
+    /// * `preset` - The preset the overrides apply to
+    ///
+    /// # Returns
+    /// * `Ok(Keymap)` with the configured bindings
+    /// * `Err(String)` if the file cannot be read, is malformed or binds a
+    ///   key to more than one command
+    pub fn load(path: &str, preset: Preset) -> Result<Self, String> {
+        match fs::read_to_string(path) {
+            Ok(content) => Keymap::parse(&content, preset).map_err(|e| format!("{}: {}", path, e)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Keymap::new(preset)),
+            Err(e) => Err(format!("Cannot read {}: {}", path, e)),
+        }
+    }
+
+    /// Parses the content of a config file.
+    ///
+    /// # Arguments
+    /// * `content` - The content of the config file
+    /// * `preset` - The preset the overrides apply to
+    ///
+    /// # Returns
+    /// * `Ok(Keymap)` with the configured bindings
+    /// * `Err(String)` naming the broken line or the conflicting keys
+    pub fn parse(content: &str, preset: Preset) -> Result<Self, String> {
+        let mut overrides: Vec<(Command, Vec<String>)> = Vec::new();
+
+        for (number, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let error = |message: String| format!("line {}: {}", number + 1, message);
+            let (name, value) = line
+                .split_once('=')
+                .ok_or_else(|| error("expected 'command = keys'".to_string()))?;
+            let (name, value) = (name.trim(), value.trim());
+            let command = Command::from_name(name)
+                .ok_or_else(|| error(format!("unknown command '{}'", name)))?;
+            overrides.push((
+                command,
+                value.split_whitespace().map(String::from).collect(),
+            ));
+        }
+
+        let mut keymap = Keymap::new(preset);
+        for (command, keys) in overrides {
+            keymap.rebind(command, &keys);
+        }
+
+        let conflicts = keymap.conflicts();
+        if !conflicts.is_empty() {
+            return Err(conflicts.join(", "));
+        }
+        Ok(keymap)
+    }
+
+    /// Replaces all keys of a command.
+    ///
+    /// Binding a key that is already used by another command creates a
+    /// conflict, see [`Keymap::conflicts`].
+    ///
+    /// # Arguments
+    /// * `command` - The command to bind
+    /// * `keys` - The new keys of the command, none to unbind it
+    pub fn rebind(&mut self, command: Command, keys: &[String]) {
+        let position = self
+            .bindings
+            .iter()
+            .position(|(_, bound)| *bound == command)
+            .unwrap_or(self.bindings.len());
+        self.bindings.retain(|(_, bound)| *bound != command);
+
+        let position = position.min(self.bindings.len());
+        for (offset, key) in keys.iter().enumerate() {
+            self.bindings
+                .insert(position + offset, (key.clone(), command));
+        }
+    }
+
+    /// Finds keys that are bound to more than one command.
+    ///
+    /// # Returns
+    /// A description of every conflicting key, empty if there are none
+    pub fn conflicts(&self) -> Vec<String> {
+        let mut conflicts = Vec::new();
+        for (index, (key, command)) in self.bindings.iter().enumerate() {
+            let first = self.bindings.iter().position(|(other, _)| other == key);
+            if first != Some(index) {
+                continue;
+            }
+            let mut names: Vec<String> = self
+                .bindings
+                .iter()
+                .filter(|(other, bound)| other == key && bound != command)
+                .map(|(_, bound)| bound.name())
+                .collect();
+            if !names.is_empty() {
+                names.insert(0, command.name());
+                conflicts.push(format!("key '{}' is bound to {}", key, names.join(" and ")));
+            }
+        }
+        conflicts
+    }
+
+    /// Gets the command bound to a key.
+    ///
+    /// # Returns
+    /// * `Some(Command)` if the key is bound
+    /// * `None` otherwise
+    pub fn command(&self, key: &str) -> Option<Command> {
+        self.bindings
+            .iter()
+            .find(|(bound, _)| bound == key)
+            .map(|(_, command)| *command)
+    }
+
+    /// Gets the direction of a movement key.
+    ///
+    /// # Returns
+    /// * `Some(Direction)` if the key is bound to a move command
+    /// * `None` otherwise
+    pub fn direction(&self, key: &str) -> Option<Direction> {
+        match self.command(key) {
+            Some(Command::Move(direction)) => Some(direction),
+            _ => None,
+        }
+    }
+
+    /// Gets all keys bound to a command.
+    ///
+    /// # Returns
+    /// The keys in the order they were bound, empty if the command is unbound
+    pub fn keys(&self, command: Command) -> Vec<&str> {
+        self.bindings
+            .iter()
+            .filter(|(_, bound)| *bound == command)
+            .map(|(key, _)| key.as_str())
+            .collect()
+    }
+
+    /// Gets a short hint listing the movement keys.
+    ///
+    /// # Returns
+    /// The keys of the straight directions, e.g. `w/a/s/d`
+    pub fn direction_hint(&self) -> String {
+        [
+            Direction::North,
+            Direction::West,
+            Direction::South,
+            Direction::East,
+        ]
+        .into_iter()
+        .filter_map(|direction| self.keys(Command::Move(direction)).first().copied())
+        .collect::<Vec<_>>()
+        .join("/")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_presets_have_no_conflicts() {
+        for preset in Preset::ALL {
+            let keymap = Keymap::new(preset);
+            assert!(keymap.conflicts().is_empty(), "{}", preset.name());
+            assert!(!keymap.keys(Command::Menu).is_empty());
+        }
+        assert_eq!(
+            Keymap::new(Preset::Vi).direction("y"),
+            Some(Direction::NorthWest)
+        );
+    }
+
+    #[test]
+    fn test_overrides_replace_keys_and_report_conflicts() {
+        let keymap = Keymap::parse("# my keys\nwait = . 5\nhelp = h\n", Preset::Numpad).unwrap();
+        assert_eq!(keymap.command("."), Some(Command::Wait));
+        assert_eq!(keymap.command("h"), Some(Command::Help));
+        assert_eq!(keymap.command("?"), None);
+
+        let error = Keymap::parse("search = o", Preset::Wasd).unwrap_err();
+        assert!(
+            error.contains("key 'o' is bound to open and search"),
+            "{}",
+            error
+        );
+
+        let error = Keymap::parse("\njump = j", Preset::Vi).unwrap_err();
+        assert!(error.starts_with("line 2"), "{}", error);
+    }
+}
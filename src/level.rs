@@ -0,0 +1,369 @@
+//! Level module for generating populated dungeon levels.
+
+use crate::{
+    analysis,
+    item::MapItem,
+    light::LightLevel,
+    loot,
+    map::Tile,
+    mapgen::{self, MapStyle},
+    rng::Rng,
+    trap::{Trap, TrapKind},
+    vault::{self, Feature},
+    Chest, Item, Map, Monster,
+};
+
+/// Width of a generated level in tiles.
+pub const LEVEL_WIDTH: i32 = 40;
+/// Height of a generated level in tiles.
+pub const LEVEL_HEIGHT: i32 = 15;
+/// Monsters on maps without rooms keep at least this distance to the spawn.
+const SPAWN_SAFE_DISTANCE: i32 = 6;
+/// Chance in percent that a level gets a vault.
+const VAULT_CHANCE: i32 = 40;
+/// Number of layouts tried before a poor one is kept.
+const LAYOUT_ATTEMPTS: u32 = 5;
+/// Fewest steps from the spawn to the stairs of a good layout.
+const MIN_STAIRS_DISTANCE: i32 = 12;
+/// Smallest share of walkable tiles of a good layout.
+const MIN_OPEN_FLOOR_RATIO: f64 = 0.15;
+/// Shallowest depth a fire beetle can appear on.
+const FIRE_BEETLE_DEPTH: i32 = 3;
+/// Shallowest depth that can be dark.
+const DARK_DEPTH: i32 = 3;
+/// Chance in percent per depth below [`DARK_DEPTH`] that a level is dark.
+const DARK_CHANCE_PER_DEPTH: i32 = 15;
+
+/// Represents a freshly generated level with everything placed on it.
+#[derive(Debug)]
+pub struct Level {
+    /// The level layout
+    pub map: Map,
+    /// Style the layout was generated in
+    pub style: MapStyle,
+    /// Starting position of the player
+    pub spawn: (i32, i32),
+    /// Monsters placed on the level
+    pub monsters: Vec<Monster>,
+    /// Chests placed on the level
+    pub chests: Vec<Chest>,
+    /// Items lying on the floor
+    pub items: Vec<MapItem>,
+    /// Traps hidden on the level
+    pub traps: Vec<Trap>,
+}
+
+/// Generates a new level for a dungeon depth.
+///
+/// Every level picks a layout style, see [`MapStyle::for_depth`], and may
+/// get one of the vaults allowed on its depth. Layouts with the stairs
+/// right next to the spawn or with little floor are generated again.
+/// Deeper levels get more monsters, a higher trap density and a growing
+/// chance to be dark, with a few braziers as the only light besides the
+/// player's torch.
+///
+/// # Arguments
+/// * `depth` - Dungeon depth, starting at 1
+/// * `rng` - Random number generator
+///
+/// # Returns
+/// A new Level with rooms, corridors, stairs and all entities placed
+pub fn generate(depth: i32, rng: &mut Rng) -> Level {
+    let style = MapStyle::for_depth(depth, rng);
+    let (map, spawn, stairs) = generate_layout(style, rng);
+
+    let mut level = Level {
+        map,
+        style,
+        spawn,
+        monsters: Vec::new(),
+        chests: Vec::new(),
+        items: Vec::new(),
+        traps: Vec::new(),
+    };
+    let mut occupied = vec![spawn, stairs];
+    if rng.chance(VAULT_CHANCE) {
+        place_vault(&mut level, depth, rng, &mut occupied);
+    }
+
+    let monster_count = (2 + depth).min(8);
+    for _ in 0..monster_count {
+        if let Some((x, y)) = random_floor(&level.map, rng, &occupied, Some(spawn)) {
+            occupied.push((x, y));
+            if depth >= FIRE_BEETLE_DEPTH && rng.chance(15) {
+                level.monsters.push(Monster::create_fire_beetle(x, y));
+            } else if rng.chance(30 + depth * 10) {
+                level.monsters.push(Monster::create_goblin(x, y));
+            } else {
+                level.monsters.push(Monster::create_rat(x, y));
+            }
+        }
+    }
+
+    let mut next_key_id = 1;
+    for _ in 0..rng.range(1, 3) {
+        let Some((x, y)) = (0..10)
+            .filter_map(|_| random_floor(&level.map, rng, &occupied, None))
+            .find(|&(x, y)| is_open_floor(&level.map, x, y))
+        else {
+            continue;
+        };
+        occupied.push((x, y));
+
+        if rng.chance(35) {
+            if let Some((kx, ky)) = random_floor(&level.map, rng, &occupied, None) {
+                occupied.push((kx, ky));
+                level
+                    .chests
+                    .push(Chest::new_locked(x, y, "chest".to_string(), next_key_id));
+                level.items.push(MapItem {
+                    x: kx,
+                    y: ky,
+                    item: Item::create_key(next_key_id, true),
+                });
+                next_key_id += 1;
+                continue;
+            }
+        }
+        level.chests.push(Chest::new(x, y, "chest".to_string()));
+    }
+
+    let floor_tiles = level
+        .map
+        .tiles
+        .iter()
+        .filter(|tile| **tile == Tile::Floor)
+        .count() as i32;
+    let trap_count = floor_tiles * (depth + 2) / 150;
+    for _ in 0..trap_count {
+        if let Some((x, y)) = random_floor(&level.map, rng, &occupied, None) {
+            occupied.push((x, y));
+            let kind = match rng.range(0, 10) {
+                0..=3 => TrapKind::Spike,
+                4..=6 => TrapKind::PoisonGas,
+                7..=8 => TrapKind::Teleport,
+                _ => TrapKind::Alarm,
+            };
+            level.traps.push(Trap::new(x, y, kind));
+        }
+    }
+
+    let dark = depth >= DARK_DEPTH && rng.chance((depth - DARK_DEPTH + 1) * DARK_CHANCE_PER_DEPTH);
+    let brazier_count = if dark {
+        level.map.ambient_light = LightLevel::Dark;
+        rng.range(3, 6)
+    } else {
+        rng.range(0, 3)
+    };
+    place_braziers(&mut level.map, brazier_count, rng);
+
+    level
+}
+
+/// Generates layouts until one has the stairs far enough from the spawn
+/// and enough open floor, keeping the last one otherwise.
+///
+/// # Returns
+/// The map with its stairs placed, the spawn and the stairs position
+fn generate_layout(style: MapStyle, rng: &mut Rng) -> (Map, (i32, i32), (i32, i32)) {
+    let mut attempt = 1;
+    loop {
+        let mut map =
+            mapgen::generate_connected(style.generator().as_ref(), LEVEL_WIDTH, LEVEL_HEIGHT, rng);
+        let (spawn, stairs) = spawn_and_stairs(&map, rng);
+        map.set_tile(stairs.0, stairs.1, Tile::StairsDown);
+
+        let far_enough = analysis::stairs_distance(&map, spawn)
+            .is_some_and(|steps| steps >= MIN_STAIRS_DISTANCE);
+        let open_enough = analysis::open_floor_ratio(&map) >= MIN_OPEN_FLOOR_RATIO;
+        if (far_enough && open_enough) || attempt == LAYOUT_ATTEMPTS {
+            return (map, spawn, stairs);
+        }
+        attempt += 1;
+    }
+}
+
+/// Places a random vault allowed on the depth, with the monsters, items,
+/// chests and traps of its legend.
+fn place_vault(level: &mut Level, depth: i32, rng: &mut Rng, occupied: &mut Vec<(i32, i32)>) {
+    let vaults = vault::builtin();
+    let Some(vault) = vault::choose(&vaults, depth, rng) else {
+        return;
+    };
+    let Some(placed) = vault::place(&mut level.map, vault, rng) else {
+        return;
+    };
+
+    for spot in placed {
+        let (x, y) = (spot.x, spot.y);
+        occupied.push((x, y));
+        match spot.feature {
+            Feature::Monster(name) => level.monsters.extend(vault::create_monster(&name, x, y)),
+            Feature::Item(table) => {
+                let items = loot::find_table(&table).map(|table| table.roll(depth, rng));
+                for item in items.unwrap_or_default() {
+                    level.items.push(MapItem { x, y, item });
+                }
+            }
+            Feature::Chest(table) => level.chests.push(Chest::new(x, y, table)),
+            Feature::Trap(kind) => level.traps.push(Trap::new(x, y, kind)),
+        }
+    }
+}
+
+/// Turns random walls next to floor into braziers.
+///
+/// # Arguments
+/// * `count` - Number of braziers to place at most
+fn place_braziers(map: &mut Map, count: i32, rng: &mut Rng) {
+    let mut walls: Vec<(i32, i32)> = map
+        .tiles
+        .iter_points()
+        .filter(|(_, tile)| **tile == Tile::Wall)
+        .map(|(point, _)| point)
+        .filter(|&point| {
+            map.tiles
+                .neighbours(point)
+                .any(|neighbour| map.tiles[neighbour] == Tile::Floor)
+        })
+        .map(|point| (point.x, point.y))
+        .collect();
+
+    for _ in 0..count {
+        if walls.is_empty() {
+            return;
+        }
+        let (x, y) = walls.swap_remove(rng.range(0, walls.len() as i32) as usize);
+        map.set_tile(x, y, Tile::Brazier);
+    }
+}
+
+/// Checks whether a tile and all eight tiles around it are floor, so a chest
+/// placed there cannot block a passage.
+fn is_open_floor(map: &Map, x: i32, y: i32) -> bool {
+    (-1..=1).all(|dy| (-1..=1).all(|dx| map.get_tile(x + dx, y + dy) == Some(&Tile::Floor)))
+}
+
+/// Picks where the player starts and where the stairs lead down.
+///
+/// On maps with rooms the player starts in the first room and the stairs
+/// are in the last one. Otherwise the player starts on a random floor tile
+/// and the stairs are as far away as possible.
+fn spawn_and_stairs(map: &Map, rng: &mut Rng) -> ((i32, i32), (i32, i32)) {
+    let is_floor = |(x, y): (i32, i32)| map.get_tile(x, y) == Some(&Tile::Floor);
+    if let (Some(first), Some(last)) = (map.rooms.first(), map.rooms.last()) {
+        if map.rooms.len() > 1 && is_floor(first.center()) && is_floor(last.center()) {
+            return (first.center(), last.center());
+        }
+    }
+
+    let floor: Vec<(i32, i32)> = (0..map.height)
+        .flat_map(|y| (0..map.width).map(move |x| (x, y)))
+        .filter(|&position| is_floor(position))
+        .collect();
+    let spawn = floor[rng.range(0, floor.len() as i32) as usize];
+    let steps = mapgen::distances(map, spawn);
+    let stairs = floor
+        .iter()
+        .copied()
+        .max_by_key(|&position| steps[position])
+        .unwrap_or(spawn);
+    (spawn, stairs)
+}
+
+/// Picks a random free floor tile, inside one of the rooms if the map has
+/// any.
+///
+/// # Arguments
+/// * `spawn` - Where the player starts, if monsters should keep away: they
+///   avoid the first room, or stay a few steps away on maps without rooms
+fn random_floor(
+    map: &Map,
+    rng: &mut Rng,
+    occupied: &[(i32, i32)],
+    spawn: Option<(i32, i32)>,
+) -> Option<(i32, i32)> {
+    if map.rooms.is_empty() {
+        return random_open_floor(map, rng, occupied, spawn);
+    }
+    let first = if spawn.is_some() && map.rooms.len() > 1 {
+        1
+    } else {
+        0
+    };
+
+    for _ in 0..50 {
+        let room = &map.rooms[rng.range(first, map.rooms.len() as i32) as usize];
+        let x = rng.range(room.x + 1, room.x + room.width - 1);
+        let y = rng.range(room.y + 1, room.y + room.height - 1);
+        if map.get_tile(x, y) == Some(&Tile::Floor) && !occupied.contains(&(x, y)) {
+            return Some((x, y));
+        }
+    }
+    None
+}
+
+/// Picks a random free floor tile anywhere on the map.
+fn random_open_floor(
+    map: &Map,
+    rng: &mut Rng,
+    occupied: &[(i32, i32)],
+    spawn: Option<(i32, i32)>,
+) -> Option<(i32, i32)> {
+    for _ in 0..100 {
+        let (x, y) = (rng.range(0, map.width), rng.range(0, map.height));
+        let near_spawn =
+            spawn.is_some_and(|(sx, sy)| (x - sx).abs().max((y - sy).abs()) < SPAWN_SAFE_DISTANCE);
+        if map.get_tile(x, y) == Some(&Tile::Floor) && !occupied.contains(&(x, y)) && !near_spawn {
+            return Some((x, y));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generated_levels_are_playable() {
+        let mut rng = Rng::new(11);
+        for depth in 1..=8 {
+            for _ in 0..5 {
+                let level = generate(depth, &mut rng);
+                let map = &level.map;
+                assert_eq!(
+                    map.get_tile(level.spawn.0, level.spawn.1),
+                    Some(&Tile::Floor)
+                );
+
+                let steps = mapgen::distances(map, level.spawn);
+                let stairs: Vec<(i32, i32)> = (0..map.height)
+                    .flat_map(|y| (0..map.width).map(move |x| (x, y)))
+                    .filter(|&(x, y)| map.get_tile(x, y) == Some(&Tile::StairsDown))
+                    .collect();
+                assert_eq!(stairs.len(), 1, "depth {}", depth);
+                assert!(steps[stairs[0]].is_some(), "stairs unreachable");
+
+                let mut positions: Vec<(i32, i32)> = level
+                    .monsters
+                    .iter()
+                    .map(|m| (m.x, m.y))
+                    .chain(level.chests.iter().map(|c| (c.x, c.y)))
+                    .chain(level.traps.iter().map(|t| (t.x, t.y)))
+                    .collect();
+                assert!(positions.iter().all(|&(x, y)| map.is_walkable(x, y)));
+                assert!(!positions.contains(&level.spawn));
+                let count = positions.len();
+                positions.sort();
+                positions.dedup();
+                assert_eq!(positions.len(), count, "entities share a tile");
+
+                assert!(level.traps.iter().all(|trap| trap.hidden));
+                if depth < DARK_DEPTH {
+                    assert_eq!(map.ambient_light, LightLevel::Lit);
+                }
+            }
+        }
+    }
+}
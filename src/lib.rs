@@ -1,14 +1,48 @@
+pub mod affix;
+pub mod analysis;
+pub mod backend;
+pub mod bot;
+pub mod character;
+pub mod chest;
+pub mod cli;
+pub mod command;
+pub mod engine;
+pub mod equipment;
+pub mod export;
+pub mod fov;
 pub mod game;
+pub mod grid;
+pub mod highscore;
+pub mod hunger;
+pub mod identify;
 pub mod item;
+pub mod keymap;
+pub mod level;
+pub mod light;
+pub mod look;
+pub mod loot;
 pub mod map;
+pub mod mapgen;
+pub mod monster;
+pub mod options;
 pub mod player;
+pub mod replay;
+pub mod rng;
 pub mod room;
+pub mod save;
+pub mod spell;
+pub mod status;
+pub mod targeting;
+pub mod trap;
 pub mod ui;
 pub mod utils;
+pub mod vault;
 
+pub use chest::Chest;
 pub use game::{Game, GameState};
 pub use item::{Item, ItemType};
 pub use map::Map;
+pub use monster::Monster;
 pub use player::Player;
 pub use room::Room;
 pub use ui::UI;
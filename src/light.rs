@@ -0,0 +1,130 @@
+//! Light module computing how brightly every tile is lit.
+//!
+//! Every level has an ambient light level. Light sources such as the
+//! player's torch, braziers and glowing monsters brighten the tiles they
+//! can reach in a straight line. Dark tiles cannot be seen from afar, see
+//! [`compute_fov`](crate::fov::compute_fov).
+
+use crate::{fov::has_line_of_sight, Map};
+
+/// Represents how brightly a tile is lit, darkest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LightLevel {
+    /// No light at all, only adjacent tiles can be seen
+    Dark,
+    /// Faint light, enough to see by but stealthy monsters stay hidden
+    Dim,
+    /// Normal light
+    Lit,
+    /// Close to a light source
+    Bright,
+}
+
+impl LightLevel {
+    /// All light levels, darkest first.
+    pub const ALL: [LightLevel; 4] = [
+        LightLevel::Dark,
+        LightLevel::Dim,
+        LightLevel::Lit,
+        LightLevel::Bright,
+    ];
+
+    /// Gets the display name of the light level.
+    ///
+    /// # Returns
+    /// A short lowercase name
+    pub fn name(&self) -> &'static str {
+        match self {
+            LightLevel::Dark => "dark",
+            LightLevel::Dim => "dim",
+            LightLevel::Lit => "lit",
+            LightLevel::Bright => "bright",
+        }
+    }
+
+    /// Gets the light a source gives at a distance.
+    ///
+    /// # Arguments
+    /// * `radius` - Radius of the source in tiles
+    /// * `distance_squared` - Squared distance from the source
+    ///
+    /// # Returns
+    /// Bright within half the radius, dim at the edge and dark beyond
+    pub fn from_source(radius: i32, distance_squared: i32) -> LightLevel {
+        if distance_squared > radius * radius {
+            LightLevel::Dark
+        } else if distance_squared * 4 <= radius * radius {
+            LightLevel::Bright
+        } else if distance_squared <= (radius - 1) * (radius - 1) {
+            LightLevel::Lit
+        } else {
+            LightLevel::Dim
+        }
+    }
+}
+
+/// Represents something that gives off light.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LightSource {
+    /// X-coordinate of the source
+    pub x: i32,
+    /// Y-coordinate of the source
+    pub y: i32,
+    /// Distance in tiles the light reaches
+    pub radius: i32,
+}
+
+/// Recomputes the light level of every tile.
+///
+/// # Arguments
+/// * `map` - The map whose light layer is updated
+/// * `sources` - All light sources on the level
+pub fn compute_light(map: &mut Map, sources: &[LightSource]) {
+    map.light.fill(map.ambient_light);
+    for source in sources {
+        for y in source.y - source.radius..=source.y + source.radius {
+            for x in source.x - source.radius..=source.x + source.radius {
+                let Some(&current) = map.light.get((x, y)) else {
+                    continue;
+                };
+                let (dx, dy) = (x - source.x, y - source.y);
+                let level = LightLevel::from_source(source.radius, dx * dx + dy * dy);
+                if level > current && has_line_of_sight(map, (source.x, source.y), (x, y)) {
+                    map.light[(x, y)] = level;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_light_falls_off_and_stops_at_walls() {
+        let mut map = Map::from_ascii(
+            "###########\n\
+             #.........#\n\
+             #.....#...#\n\
+             ###########",
+        )
+        .unwrap();
+        map.ambient_light = LightLevel::Dark;
+        compute_light(
+            &mut map,
+            &[LightSource {
+                x: 2,
+                y: 2,
+                radius: 6,
+            }],
+        );
+
+        assert_eq!(map.light[(2, 2)], LightLevel::Bright);
+        assert_eq!(map.light[(5, 2)], LightLevel::Bright);
+        assert_eq!(map.light[(6, 2)], LightLevel::Lit);
+        assert_eq!(map.light[(7, 1)], LightLevel::Dim);
+        assert_eq!(map.light[(7, 2)], LightLevel::Dark);
+        assert_eq!(map.light[(8, 1)], LightLevel::Dark);
+    }
+}
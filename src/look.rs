@@ -0,0 +1,152 @@
+//! Look module describing what the player knows about a map position.
+//!
+//! Explored tiles are described as remembered, while monsters are only
+//! described while the player can see them.
+
+use crate::{
+    light::LightLevel,
+    map::{DoorState, Tile},
+    Game,
+};
+
+impl Game {
+    /// Finds the positions worth looking at: visible monsters and the items,
+    /// chests and traps the player knows of.
+    ///
+    /// # Returns
+    /// The positions, closest to the player first
+    pub fn look_targets(&self) -> Vec<(i32, i32)> {
+        let mut targets = self.visible_targets(i32::MAX);
+        let features = self
+            .items
+            .iter()
+            .map(|map_item| (map_item.x, map_item.y))
+            .chain(self.chests.iter().map(|chest| (chest.x, chest.y)))
+            .chain(
+                self.traps
+                    .iter()
+                    .filter(|trap| !trap.hidden)
+                    .map(|trap| (trap.x, trap.y)),
+            );
+        for position in features {
+            if self.map.is_explored(position.0, position.1) && !targets.contains(&position) {
+                targets.push(position);
+            }
+        }
+
+        let (px, py) = (self.player.x, self.player.y);
+        targets.sort_by_key(|&(x, y)| (x - px).abs().max((y - py).abs()));
+        targets
+    }
+
+    /// Describes what the player knows about a map position.
+    ///
+    /// # Arguments
+    /// * `x` - The x-coordinate
+    /// * `y` - The y-coordinate
+    ///
+    /// # Returns
+    /// Lines describing the tile, a visible monster with a guess at its
+    /// health, and every item with its description
+    pub fn describe_at(&self, x: i32, y: i32) -> Vec<String> {
+        if !self.map.is_explored(x, y) {
+            return vec!["You have not explored this place.".to_string()];
+        }
+
+        let mut tile = match self.map.get_tile(x, y).unwrap_or(&Tile::Empty) {
+            Tile::Floor => "Stone floor",
+            Tile::Wall => "A solid wall",
+            Tile::Door(DoorState::Open) => "An open door",
+            Tile::Door(DoorState::Closed) => "A closed door",
+            Tile::Door(DoorState::Locked(_)) => "A locked door",
+            Tile::Empty => "Solid rock",
+            Tile::StairsDown => "Stairs leading down",
+            Tile::Brazier => "A brazier burning on the wall",
+        }
+        .to_string();
+        if let Some(chest) = self.chest_at(x, y).map(|i| &self.chests[i]) {
+            tile.push_str(match (chest.opened, chest.lock) {
+                (true, _) => " with an empty chest",
+                (false, Some(_)) => " with a locked chest",
+                (false, None) => " with a chest",
+            });
+        }
+        if let Some(trap) = self
+            .trap_at(x, y)
+            .map(|i| &self.traps[i])
+            .filter(|trap| !trap.hidden)
+        {
+            tile.push_str(&format!(" with a {}", trap.kind.name()));
+        }
+        if !self.map.is_visible(x, y) {
+            tile.push_str(", out of sight");
+        } else if self.map.light[(x, y)] <= LightLevel::Dim {
+            tile.push_str(&format!(", {}", self.map.light[(x, y)].name()));
+        }
+
+        let mut lines = vec![format!("{}.", tile)];
+        if (x, y) == (self.player.x, self.player.y) {
+            lines.push("You are standing here.".to_string());
+        } else if let Some(monster) = self
+            .monster_at(x, y)
+            .map(|i| &self.monsters[i])
+            .filter(|monster| self.can_see_monster(monster))
+        {
+            let mut line = format!("{} ({}).", monster.name, monster.health_estimate());
+            for effect in &monster.status_effects {
+                line.push_str(&format!(" {}.", effect.kind.name()));
+            }
+            lines.push(line);
+        }
+        for map_item in self.items.iter().filter(|i| i.x == x && i.y == y) {
+            lines.push(format!(
+                "{}: {}",
+                self.identification.name(&map_item.item),
+                self.identification.describe(&map_item.item)
+            ));
+        }
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        character::{Background, Class},
+        item::MapItem,
+        map::Tile,
+        rng::Rng,
+        Game, Item, Monster, Player,
+    };
+
+    #[test]
+    fn test_describe_at_lists_monster_and_items() {
+        let player = Player::create("Ann".to_string(), Class::Warrior, Background::Soldier);
+        let mut game = Game::generate(player, Rng::new(3));
+        game.monsters.clear();
+        game.items.clear();
+        game.chests.clear();
+        game.traps.clear();
+        let (x, y) = (game.player.x, game.player.y);
+        assert_eq!(game.describe_at(x, y)[1], "You are standing here.");
+
+        let (tx, ty) = (x + 1, y);
+        game.map.set_tile(tx, ty, Tile::Floor);
+        game.map.reveal(tx, ty);
+        game.map.visible[(tx, ty)] = true;
+        let mut goblin = Monster::create_goblin(tx, ty);
+        goblin.health = goblin.max_health / 3;
+        game.monsters.push(goblin);
+        game.items.push(MapItem {
+            x: tx,
+            y: ty,
+            item: Item::create_sword(),
+        });
+
+        let lines = game.describe_at(tx, ty);
+        assert!(lines[0].starts_with("Stone floor"), "{:?}", lines);
+        assert_eq!(lines[1], "Goblin (badly wounded).");
+        assert_eq!(lines[2], "Sword: A simple sword.");
+        assert_eq!(game.look_targets().first(), Some(&(tx, ty)));
+    }
+}
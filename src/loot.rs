@@ -0,0 +1,289 @@
+//! Loot module providing weighted loot tables for chests and monster drops.
+
+use crate::{affix, rng::Rng, spell::SpellKind, Item};
+
+/// Represents a single result a loot table can produce.
+#[derive(Debug, Clone)]
+pub enum LootEntry {
+    /// A single item, cloned each time it is rolled
+    Item(Item),
+    /// A nested table that is rolled in place of this entry
+    Table(Box<LootTable>),
+    /// Produces no item
+    Nothing,
+}
+
+/// Represents a loot entry together with its chance of being picked.
+#[derive(Debug, Clone)]
+pub struct WeightedEntry {
+    /// The entry to produce
+    pub entry: LootEntry,
+    /// Relative weight at depth 1
+    pub weight: i32,
+    /// Weight added for every level below depth 1
+    pub weight_per_depth: i32,
+    /// Shallowest depth at which the entry can be picked
+    pub min_depth: i32,
+}
+
+impl WeightedEntry {
+    /// Calculates the weight of the entry at a dungeon depth.
+    ///
+    /// # Arguments
+    /// * `depth` - Current dungeon depth, starting at 1
+    ///
+    /// # Returns
+    /// The scaled weight, or 0 if the entry is not available at this depth
+    pub fn weight_at(&self, depth: i32) -> i32 {
+        if depth < self.min_depth {
+            return 0;
+        }
+        (self.weight + self.weight_per_depth * (depth - 1)).max(0)
+    }
+}
+
+/// Represents a weighted loot table.
+///
+/// Guaranteed entries are always produced, then `rolls` weighted picks are
+/// made from the remaining entries.
+#[derive(Debug, Clone)]
+pub struct LootTable {
+    /// Number of weighted picks per roll
+    pub rolls: i32,
+    /// Entries that are always produced
+    pub guaranteed: Vec<LootEntry>,
+    /// Entries that are picked by weight
+    pub entries: Vec<WeightedEntry>,
+}
+
+impl LootTable {
+    /// Creates a new empty loot table.
+    ///
+    /// # Arguments
+    /// * `rolls` - Number of weighted picks per roll
+    ///
+    /// # Returns
+    /// A new LootTable instance without entries
+    pub fn new(rolls: i32) -> Self {
+        LootTable {
+            rolls,
+            guaranteed: Vec::new(),
+            entries: Vec::new(),
+        }
+    }
+
+    /// Adds an entry that is produced on every roll.
+    pub fn guarantee(mut self, entry: LootEntry) -> Self {
+        self.guaranteed.push(entry);
+        self
+    }
+
+    /// Adds an entry with a fixed weight.
+    pub fn add(self, entry: LootEntry, weight: i32) -> Self {
+        self.add_scaled(entry, weight, 0, 1)
+    }
+
+    /// Adds an entry whose weight changes with depth.
+    ///
+    /// # Arguments
+    /// * `entry` - The entry to add
+    /// * `weight` - Relative weight at depth 1
+    /// * `weight_per_depth` - Weight added for every level below depth 1
+    /// * `min_depth` - Shallowest depth at which the entry can be picked
+    pub fn add_scaled(
+        mut self,
+        entry: LootEntry,
+        weight: i32,
+        weight_per_depth: i32,
+        min_depth: i32,
+    ) -> Self {
+        self.entries.push(WeightedEntry {
+            entry,
+            weight,
+            weight_per_depth,
+            min_depth,
+        });
+        self
+    }
+
+    /// Rolls the table.
+    ///
+    /// # Arguments
+    /// * `depth` - Current dungeon depth used to scale entry weights
+    /// * `rng` - Random number generator
+    ///
+    /// # Returns
+    /// All items produced by the guaranteed entries and weighted picks
+    pub fn roll(&self, depth: i32, rng: &mut Rng) -> Vec<Item> {
+        let mut items = Vec::new();
+
+        for entry in &self.guaranteed {
+            entry.resolve(depth, rng, &mut items);
+        }
+
+        let total: i32 = self.entries.iter().map(|e| e.weight_at(depth)).sum();
+        if total <= 0 {
+            return items;
+        }
+
+        for _ in 0..self.rolls {
+            let mut pick = rng.range(0, total);
+            for weighted in &self.entries {
+                let weight = weighted.weight_at(depth);
+                if pick < weight {
+                    weighted.entry.resolve(depth, rng, &mut items);
+                    break;
+                }
+                pick -= weight;
+            }
+        }
+
+        items
+    }
+}
+
+impl LootEntry {
+    /// Produces the items of this entry into `items`. Equipment gets a
+    /// rarity and affixes for the depth, see [`affix::enchant`].
+    fn resolve(&self, depth: i32, rng: &mut Rng, items: &mut Vec<Item>) {
+        match self {
+            LootEntry::Item(item) => items.push(affix::enchant(item.clone(), depth, rng)),
+            LootEntry::Table(table) => items.extend(table.roll(depth, rng)),
+            LootEntry::Nothing => {}
+        }
+    }
+}
+
+/// Builds the table of potions.
+fn potion_table() -> LootTable {
+    LootTable::new(1)
+        .add(LootEntry::Item(Item::create_health_potion()), 4)
+        .add_scaled(
+            LootEntry::Item(Item::create_greater_health_potion()),
+            0,
+            1,
+            2,
+        )
+        .add(LootEntry::Item(Item::create_fire_potion()), 1)
+        .add(LootEntry::Item(Item::create_poison_potion()), 1)
+}
+
+/// Builds the table of scrolls and tomes.
+fn scroll_table() -> LootTable {
+    LootTable::new(1)
+        .add(LootEntry::Item(Item::create_scroll(SpellKind::Bolt)), 3)
+        .add(LootEntry::Item(Item::create_scroll(SpellKind::Heal)), 2)
+        .add(LootEntry::Item(Item::create_scroll(SpellKind::Blink)), 2)
+        .add(LootEntry::Item(Item::create_scroll(SpellKind::Detect)), 2)
+        .add(LootEntry::Item(Item::create_identify_scroll()), 3)
+        .add_scaled(
+            LootEntry::Item(Item::create_scroll(SpellKind::Blast)),
+            0,
+            1,
+            2,
+        )
+        .add_scaled(LootEntry::Item(Item::create_tome(SpellKind::Bolt)), 0, 1, 2)
+        .add_scaled(
+            LootEntry::Item(Item::create_tome(SpellKind::Blast)),
+            0,
+            1,
+            4,
+        )
+}
+
+/// Builds the table of weapons.
+fn weapon_table() -> LootTable {
+    LootTable::new(1)
+        .add(LootEntry::Item(Item::create_dagger()), 4)
+        .add(LootEntry::Item(Item::create_sword()), 3)
+        .add(LootEntry::Item(Item::create_short_bow()), 2)
+        .add_scaled(LootEntry::Item(Item::create_battle_axe()), 1, 1, 3)
+}
+
+/// Builds the table of armor.
+fn armor_table() -> LootTable {
+    LootTable::new(1)
+        .add(LootEntry::Item(Item::create_leather_armor()), 3)
+        .add_scaled(LootEntry::Item(Item::create_chain_mail()), 1, 1, 2)
+        .add(LootEntry::Item(Item::create_helmet()), 2)
+        .add(LootEntry::Item(Item::create_gloves()), 2)
+        .add(LootEntry::Item(Item::create_boots()), 2)
+        .add(LootEntry::Item(Item::create_wooden_shield()), 2)
+}
+
+/// Builds the table of rings and amulets.
+fn jewelry_table() -> LootTable {
+    LootTable::new(1)
+        .add(LootEntry::Item(Item::create_ring()), 3)
+        .add_scaled(LootEntry::Item(Item::create_amulet()), 1, 1, 3)
+}
+
+/// Looks up a loot table by name.
+///
+/// # Arguments
+/// * `name` - Name of the table, e.g. `"chest"`, `"goblin"` or `"potion"`
+///
+/// # Returns
+/// * `Some(LootTable)` if a table with this name exists
+/// * `None` otherwise
+pub fn find_table(name: &str) -> Option<LootTable> {
+    let table = match name {
+        "chest" => LootTable::new(2)
+            .guarantee(LootEntry::Table(Box::new(potion_table())))
+            .add(LootEntry::Table(Box::new(weapon_table())), 2)
+            .add(LootEntry::Table(Box::new(armor_table())), 2)
+            .add(LootEntry::Table(Box::new(jewelry_table())), 1)
+            .add(LootEntry::Table(Box::new(potion_table())), 3)
+            .add(LootEntry::Item(Item::create_arrows(10)), 2)
+            .add(LootEntry::Item(Item::create_torch()), 2)
+            .add(LootEntry::Item(Item::create_food_ration()), 2)
+            .add(LootEntry::Table(Box::new(scroll_table())), 2)
+            .add_scaled(LootEntry::Nothing, 2, -1, 1),
+        "goblin" => LootTable::new(1)
+            .add(LootEntry::Nothing, 5)
+            .add(LootEntry::Table(Box::new(potion_table())), 2)
+            .add(LootEntry::Table(Box::new(weapon_table())), 1)
+            .add(LootEntry::Item(Item::create_torch()), 1)
+            .add(LootEntry::Item(Item::create_apple()), 2),
+        "rat" => LootTable::new(1)
+            .add(LootEntry::Nothing, 8)
+            .add(LootEntry::Table(Box::new(potion_table())), 1),
+        "potion" => potion_table(),
+        "scroll" => scroll_table(),
+        "weapon" => weapon_table(),
+        "armor" => armor_table(),
+        "jewelry" => jewelry_table(),
+        _ => return None,
+    };
+    Some(table)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_guaranteed_and_depth_gated_entries() {
+        let table = LootTable::new(3)
+            .guarantee(LootEntry::Item(Item::create_sword()))
+            .add_scaled(LootEntry::Item(Item::create_battle_axe()), 1, 0, 5);
+        let mut rng = Rng::new(7);
+
+        let shallow = table.roll(1, &mut rng);
+        assert_eq!(shallow.len(), 1);
+        assert_eq!(shallow[0].name, "Sword");
+
+        let deep = table.roll(5, &mut rng);
+        assert_eq!(deep.len(), 4);
+    }
+
+    #[test]
+    fn test_nested_tables_resolve_to_items() {
+        let inner = LootTable::new(2).add(LootEntry::Item(Item::create_dagger()), 1);
+        let outer = LootTable::new(1).add(LootEntry::Table(Box::new(inner)), 1);
+
+        let items = outer.roll(1, &mut Rng::new(1));
+        assert_eq!(items.len(), 2);
+        assert!(items.iter().all(|item| item.name == "Dagger"));
+    }
+}
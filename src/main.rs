@@ -1,4 +1,26 @@
-use kd_rusty_crawler::{ui::Content, Game, Map, Room, UI};
+use kd_rusty_crawler::{
+    analysis,
+    bot::{self, Bot},
+    character::{Background, Class},
+    cli::{self, Action, Args, USAGE},
+    command::Command,
+    engine::Event,
+    export,
+    highscore::{HallOfFame, HIGH_SCORE_FILE},
+    keymap::{Keymap, KEYMAP_FILE},
+    level,
+    options::{ColorMode, Options, OPTIONS_FILE},
+    replay::{Recorder, Replay, Start, REPLAY_FILE},
+    rng::Rng,
+    save::{describe_slot, load_game, save_game, AUTOSAVE_SLOT, SAVE_SLOTS},
+    targeting::Targeting,
+    ui::Content,
+    Game, GameState, Player, UI,
+};
+use std::time::Duration;
+
+/// Replay speeds offered, in commands per second.
+const REPLAY_SPEEDS: [u64; 5] = [1, 2, 5, 10, 20];
 
 /// Application state representing either the main menu or active game.
 ///
@@ -10,12 +32,152 @@ enum AppState {
     InGame(Box<Game>),
 }
 
+/// Player settings: the options and the key bindings built from them.
+struct Settings {
+    /// Options loaded from the options file
+    options: Options,
+    /// Key bindings for the chosen preset with the player's overrides
+    keymap: Keymap,
+    /// Path of the options file
+    path: String,
+    /// Whether colours were turned off on the command line
+    no_color: bool,
+}
+
+impl Settings {
+    /// Loads the options and key bindings, showing any errors in the files.
+    ///
+    /// Invalid files are reported and replaced by the defaults.
+    fn load(ui: &mut UI, args: &Args) -> Self {
+        let path = args.config.as_deref().unwrap_or(OPTIONS_FILE).to_string();
+        let options = Options::load(&path).unwrap_or_else(|e| {
+            ui.show_dialog(&e);
+            ui.update_content(Content::Empty);
+            Options::default()
+        });
+
+        let mut settings = Settings {
+            keymap: Keymap::new(options.keymap_preset),
+            options,
+            path,
+            no_color: args.no_color,
+        };
+        settings.apply(ui);
+        settings.reload_keymap(ui);
+        settings
+    }
+
+    /// Applies the options to the UI, keeping colours off if `--no-color`
+    /// was given.
+    fn apply(&self, ui: &mut UI) {
+        let mut options = self.options.clone();
+        if self.no_color {
+            options.color = ColorMode::Monochrome;
+        }
+        ui.apply_options(&options);
+    }
+
+    /// Rebuilds the key bindings after the preset has changed.
+    fn reload_keymap(&mut self, ui: &mut UI) {
+        let preset = self.options.keymap_preset;
+        self.keymap = Keymap::load(KEYMAP_FILE, preset).unwrap_or_else(|e| {
+            ui.show_dialog(&e);
+            ui.update_content(Content::Empty);
+            Keymap::new(preset)
+        });
+    }
+
+    /// Shows the settings screen until the player goes back.
+    ///
+    /// Every change is applied and written to the options file at once.
+    fn edit(&mut self, ui: &mut UI) {
+        loop {
+            ui.update_content(Content::Empty);
+            ui.update_content(Content::Settings(&self.options));
+
+            let input = ui.get_input();
+            let name = match input.parse::<usize>() {
+                _ if input.is_empty() || input == "q" => break,
+                Ok(n) if (1..=Options::NAMES.len()).contains(&n) => Options::NAMES[n - 1],
+                _ => {
+                    ui.show_dialog(&format!("Please select 1-{}", Options::NAMES.len()));
+                    continue;
+                }
+            };
+
+            self.options.cycle(name);
+            self.apply(ui);
+            if name == "keymap_preset" {
+                self.reload_keymap(ui);
+            }
+            if let Err(e) = self.options.save(&self.path) {
+                ui.show_dialog(&e);
+            }
+        }
+        ui.update_content(Content::Empty);
+    }
+}
+
 fn main() {
-    let ui = UI::new();
+    let args = match cli::parse(std::env::args().skip(1)) {
+        Ok(Action::Run(args)) => args,
+        Ok(Action::Help) => {
+            print!("{}", USAGE);
+            return;
+        }
+        Ok(Action::Version) => {
+            println!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
+            return;
+        }
+        Err(e) => {
+            eprintln!("error: {}\nRun with --help to see all options.", e);
+            std::process::exit(2);
+        }
+    };
+    if let Some(turns) = args.headless {
+        run_headless(&args, turns);
+        return;
+    }
+    if let Some(levels) = args.map_report {
+        print_map_report(&args, levels);
+        return;
+    }
+    if let Some(path) = &args.export_map {
+        if let Err(e) = export_map(&args, path) {
+            eprintln!("error: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let mut ui = UI::new();
     let mut app_state = AppState::Menu;
+    let mut recorder: Option<Recorder> = None;
 
     ui.draw_frame();
-    ui.update_content(Content::MainMenu);
+    let mut settings = Settings::load(&mut ui, &args);
+
+    if let Some(path) = &args.replay {
+        watch_replay(&mut ui, path);
+        return;
+    }
+    if let Some(slot) = args.load {
+        match load_game(slot) {
+            Ok(game) => {
+                recorder = start_recording(&mut ui, Recorder::for_game(REPLAY_FILE, &game));
+                ui.update_content(Content::Empty);
+                ui.update_content(Content::Game(&game));
+                app_state = AppState::InGame(Box::new(game));
+            }
+            Err(e) => {
+                ui.show_dialog(&e);
+                ui.update_content(Content::Empty);
+            }
+        }
+    }
+    if let AppState::Menu = app_state {
+        ui.update_content(Content::MainMenu);
+    }
 
     loop {
         let input = ui.get_input();
@@ -24,45 +186,612 @@ fn main() {
             AppState::Menu => match input.as_str() {
                 "1" => {
                     ui.update_content(Content::Empty);
-                    ui.show_dialog("Enter your hero's name: ");
-                    let player_name = ui.get_input();
+                    let player_name = match &args.name {
+                        Some(name) => name.clone(),
+                        None => {
+                            ui.show_dialog("Enter your hero's name: ");
+                            ui.get_input()
+                        }
+                    };
+                    let class = choose(&mut ui, Content::ChooseClass, &Class::ALL);
+                    let background = choose(&mut ui, Content::ChooseBackground, &Background::ALL);
+
+                    let seed = args.seed.unwrap_or_else(|| Rng::from_time().next_u64());
+                    let start = Start::New {
+                        seed,
+                        name: player_name.clone(),
+                        class,
+                        background,
+                    };
+                    recorder = start_recording(&mut ui, Recorder::create(REPLAY_FILE, &start));
 
-                    let mut map = Map::new(40, 15);
-                    map.create_room(Room::new(40, 15));
+                    let player = Player::create(player_name, class, background);
+                    let game = Game::generate(player, Rng::new(seed));
 
-                    app_state = AppState::InGame(Box::new(Game::new(player_name, map)));
+                    app_state = AppState::InGame(Box::new(game));
                     ui.update_content(Content::Empty);
                     if let AppState::InGame(game) = &app_state {
                         ui.update_content(Content::Game(game));
                     }
                 }
                 "2" => {
+                    let loaded =
+                        choose_slot(&mut ui, "Load which game?", AUTOSAVE_SLOT).map(load_game);
+                    ui.update_content(Content::Empty);
+                    match loaded {
+                        Some(Ok(game)) => {
+                            recorder =
+                                start_recording(&mut ui, Recorder::for_game(REPLAY_FILE, &game));
+                            ui.update_content(Content::Game(&game));
+                            app_state = AppState::InGame(Box::new(game));
+                        }
+                        Some(Err(e)) => {
+                            ui.show_dialog(&e);
+                            ui.update_content(Content::Empty);
+                            ui.update_content(Content::MainMenu);
+                        }
+                        None => ui.update_content(Content::MainMenu),
+                    }
+                }
+                "3" => {
+                    let hall = match HallOfFame::load(HIGH_SCORE_FILE) {
+                        Ok(hall) => hall,
+                        Err(e) => {
+                            ui.show_dialog(&e);
+                            HallOfFame::default()
+                        }
+                    };
+                    ui.update_content(Content::Empty);
+                    ui.update_content(Content::HallOfFame(&hall));
+                    ui.get_input();
                     ui.update_content(Content::Empty);
-                    ui.show_dialog("Load game not implemented yet!");
-                    std::thread::sleep(std::time::Duration::from_secs(2));
                     ui.update_content(Content::MainMenu);
                 }
-                "3" | "q" => {
+                "4" => {
+                    settings.edit(&mut ui);
+                    ui.update_content(Content::MainMenu);
+                }
+                "5" | "q" => {
                     ui.update_content(Content::Empty);
                     ui.show_dialog("Thanks for playing!");
                     break;
                 }
                 _ => {
-                    ui.show_dialog("Please select 1-3");
+                    ui.show_dialog("Please select 1-5");
                     ui.update_content(Content::MainMenu);
                 }
             },
-            AppState::InGame(game) => match input.as_str() {
-                "q" => {
-                    app_state = AppState::Menu;
+            AppState::InGame(game) => match settings.keymap.command(&input) {
+                Some(Command::Menu) => {
+                    if pause_menu(&mut ui, game, &mut settings) {
+                        app_state = AppState::Menu;
+                        recorder = None;
+                        ui.update_content(Content::Empty);
+                        ui.update_content(Content::MainMenu);
+                    } else {
+                        ui.update_content(Content::Game(game));
+                    }
+                }
+                Some(Command::Help) => {
+                    show_help(&mut ui, &settings.keymap);
+                    ui.update_content(Content::Game(game));
+                }
+                Some(Command::Look) => {
+                    look_around(&mut ui, game, &settings.keymap);
                     ui.update_content(Content::Empty);
-                    ui.update_content(Content::MainMenu);
+                    ui.update_content(Content::Game(game));
                 }
                 _ => {
-                    game.update();
+                    let depth = game.depth;
+                    if let Some(command) = read_command(&mut ui, game, &settings.keymap, &input) {
+                        if let Some(Err(e)) =
+                            recorder.as_mut().map(|r| r.record(game.turns, command))
+                        {
+                            game.log(e);
+                            recorder = None;
+                        }
+                        for event in game.apply(command) {
+                            if let Event::Projectile { path, glyph } = event {
+                                ui.animate_projectile(game, &path, glyph);
+                            }
+                        }
+                    }
+                    if settings.options.auto_save && game.depth != depth {
+                        auto_save(game);
+                    }
                     ui.update_content(Content::Game(game));
+
+                    if game.state == GameState::GameOver {
+                        let rank = record_run(&mut ui, game);
+                        ui.get_input();
+                        ui.update_content(Content::Empty);
+                        ui.update_content(Content::GameOver(game, rank));
+                        ui.get_input();
+                        app_state = AppState::Menu;
+                        recorder = None;
+                        ui.update_content(Content::Empty);
+                        ui.update_content(Content::MainMenu);
+                    }
                 }
             },
         };
     }
 }
+
+/// Lets a bot play without the UI and prints how the run went.
+///
+/// # Arguments
+/// * `args` - The command line settings, for the seed and the hero's name
+/// * `turns` - Number of turns for the bot to play
+fn run_headless(args: &Args, turns: u32) {
+    let seed = args.seed.unwrap_or_else(|| Rng::from_time().next_u64());
+    let name = args.name.clone().unwrap_or_else(|| "Bot".to_string());
+    let player = Player::create(name, Class::Warrior, Background::Soldier);
+    let mut game = Game::generate(player, Rng::new(seed));
+
+    bot::play(&mut game, &mut Bot::new(), turns);
+
+    let score = game.high_score();
+    let outcome = match &game.cause_of_death {
+        Some(cause) => cause.clone(),
+        None => "alive".to_string(),
+    };
+    println!("Seed:    {}", seed);
+    println!("Turns:   {}", score.turns);
+    println!("Depth:   {}", score.depth);
+    println!("Level:   {}", score.level);
+    println!("Kills:   {}", score.kills);
+    println!("Outcome: {}", outcome);
+    println!("Score:   {}", score.score());
+}
+
+/// Generates levels one after another and prints each with its analysis.
+///
+/// # Arguments
+/// * `args` - The command line settings, for the seed
+/// * `levels` - Number of levels to generate, starting at depth 1
+fn print_map_report(args: &Args, levels: u32) {
+    let seed = args.seed.unwrap_or_else(|| Rng::from_time().next_u64());
+    let mut rng = Rng::new(seed);
+    println!("Seed: {}", seed);
+    for depth in 1..=levels as i32 {
+        let level = level::generate(depth, &mut rng);
+        println!();
+        println!("Depth {} ({})", depth, level.style.name());
+        print!("{}", level.map.to_ascii());
+        print!("{}", analysis::analyze(&level.map, level.spawn));
+    }
+}
+
+/// Generates a new game and writes it as JSON, see [`export::game_json`].
+///
+/// # Arguments
+/// * `args` - The command line settings, for the seed and the hero's name
+/// * `path` - Path of the file to write
+///
+/// # Returns
+/// * `Ok(())` if the file was written
+/// * `Err(String)` if it could not be written
+fn export_map(args: &Args, path: &str) -> Result<(), String> {
+    let seed = args.seed.unwrap_or_else(|| Rng::from_time().next_u64());
+    let name = args.name.clone().unwrap_or_else(|| "Hero".to_string());
+    let player = Player::create(name, Class::Warrior, Background::Soldier);
+    let game = Game::generate(player, Rng::new(seed));
+    std::fs::write(path, export::game_json(&game))
+        .map_err(|e| format!("Cannot write {}: {}", path, e))?;
+    println!("Seed: {}", seed);
+    println!("Wrote depth {} to {}", game.depth, path);
+    Ok(())
+}
+
+/// Reports a replay file that could not be created.
+///
+/// # Returns
+/// * `Some(Recorder)` if recording started
+/// * `None` if it failed, the game is played without recording then
+fn start_recording(ui: &mut UI, recorder: Result<Recorder, String>) -> Option<Recorder> {
+    match recorder {
+        Ok(recorder) => Some(recorder),
+        Err(e) => {
+            ui.show_dialog(&e);
+            ui.update_content(Content::Empty);
+            None
+        }
+    }
+}
+
+/// Plays a recorded session back.
+///
+/// Playback starts paused. Enter steps through one command, a number plays
+/// that many, `p` plays to the end, `+` and `-` change the speed and `q`
+/// quits. Pressing Enter while the replay plays pauses it again.
+///
+/// # Arguments
+/// * `ui` - The UI to draw on
+/// * `path` - Path of the replay file
+fn watch_replay(ui: &mut UI, path: &str) {
+    let loaded = Replay::load(path).and_then(|replay| Ok((replay.start.game()?, replay)));
+    let (mut game, replay) = match loaded {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            ui.show_dialog(&e);
+            return;
+        }
+    };
+    let total = replay.commands.len();
+    let mut next = 0;
+    let mut speed = 2;
+    let mut diverged = false;
+
+    loop {
+        let status = if next < total {
+            format!(
+                "Replay {}/{} at {}/s - [Enter] step, <n> steps, p play, +/- speed, q quit: ",
+                next, total, REPLAY_SPEEDS[speed]
+            )
+        } else {
+            format!("Replay {}/{} - end of the recording, q quit: ", next, total)
+        };
+        ui.update_content(Content::Replay(&game, &status));
+
+        let steps = match ui.get_input().as_str() {
+            "q" => break,
+            "" => 1,
+            "p" => total,
+            "+" => {
+                speed = (speed + 1).min(REPLAY_SPEEDS.len() - 1);
+                continue;
+            }
+            "-" => {
+                speed = speed.saturating_sub(1);
+                continue;
+            }
+            input => input.parse().unwrap_or(0),
+        };
+
+        for step in 0..steps {
+            let Some(&(turns, command)) = replay.commands.get(next) else {
+                break;
+            };
+            if game.turns != turns && !diverged {
+                diverged = true;
+                ui.show_dialog(&format!(
+                    "The game differs from the recording at command {}.",
+                    next + 1
+                ));
+            }
+            game.apply(command);
+            next += 1;
+
+            if step + 1 < steps {
+                let status = format!("Replay {}/{} - [Enter] pause", next, total);
+                ui.update_content(Content::Replay(&game, &status));
+                let frame = Duration::from_millis(1000 / REPLAY_SPEEDS[speed]);
+                if ui.poll_input(frame).is_some() {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// Records a finished run in the Hall of Fame file.
+///
+/// # Returns
+/// * `Some(usize)` with the 0-based rank of the run
+/// * `None` if the run did not make it or could not be recorded
+fn record_run(ui: &mut UI, game: &Game) -> Option<usize> {
+    let result = HallOfFame::load(HIGH_SCORE_FILE).and_then(|mut hall| {
+        let rank = hall.add(game.high_score());
+        hall.save(HIGH_SCORE_FILE).map(|()| rank)
+    });
+
+    match result {
+        Ok(rank) => rank,
+        Err(e) => {
+            ui.show_dialog(&e);
+            None
+        }
+    }
+}
+
+/// Saves the game to the auto-save slot, logging failures.
+fn auto_save(game: &mut Game) {
+    if let Err(e) = save_game(game, AUTOSAVE_SLOT) {
+        game.log(format!("Auto-save failed: {}", e));
+    }
+}
+
+/// Shows the in-game menu until the player resumes or quits.
+///
+/// The game is paused while the menu is open.
+///
+/// # Returns
+/// `true` if the player quit to the main menu
+fn pause_menu(ui: &mut UI, game: &mut Game, settings: &mut Settings) -> bool {
+    game.pause();
+    loop {
+        ui.update_content(Content::PauseMenu(game));
+        match ui.get_input().as_str() {
+            "1" | "" => break,
+            key if settings.keymap.command(key) == Some(Command::Menu) => break,
+            "2" => {
+                if let Some(slot) = choose_slot(ui, "Save to which slot?", 1) {
+                    let message = match save_game(game, slot) {
+                        Ok(()) => format!("Game saved to slot {}.", slot),
+                        Err(e) => e,
+                    };
+                    game.log(message);
+                }
+                ui.update_content(Content::Empty);
+            }
+            "3" => settings.edit(ui),
+            "4" => show_help(ui, &settings.keymap),
+            "5" => {
+                let options = &settings.options;
+                let question = if options.auto_save {
+                    "Quit to menu? (y/n) "
+                } else {
+                    "Quit to menu? Unsaved progress is lost. (y/n) "
+                };
+                if !options.confirm_quit || ui.prompt(question).eq_ignore_ascii_case("y") {
+                    if options.auto_save {
+                        auto_save(game);
+                    }
+                    return true;
+                }
+            }
+            _ => {}
+        }
+    }
+    game.resume();
+    false
+}
+
+/// Shows the list of commands until the player presses Enter.
+fn show_help(ui: &mut UI, keymap: &Keymap) {
+    ui.update_content(Content::Empty);
+    ui.update_content(Content::Help(keymap));
+    ui.get_input();
+    ui.update_content(Content::Empty);
+}
+
+/// Shows the save slots until one is picked.
+///
+/// # Arguments
+/// * `ui` - The UI to draw on
+/// * `title` - Heading shown above the slots
+/// * `first` - First slot offered, [`AUTOSAVE_SLOT`] to include the auto-save
+///
+/// # Returns
+/// * `Some(u32)` with the picked slot number
+/// * `None` if the player cancelled with `q`
+fn choose_slot(ui: &mut UI, title: &str, first: u32) -> Option<u32> {
+    let slots: Vec<(String, String)> = (first..=SAVE_SLOTS)
+        .map(|slot| {
+            let name = match slot {
+                AUTOSAVE_SLOT => "Autosave".to_string(),
+                _ => format!("Slot {}", slot),
+            };
+            (name, describe_slot(slot))
+        })
+        .collect();
+
+    loop {
+        ui.update_content(Content::Empty);
+        ui.update_content(Content::ChooseSlot(title, &slots));
+
+        let input = ui.get_input();
+        match input.parse::<u32>() {
+            _ if input == "q" => return None,
+            Ok(n) if (first..=SAVE_SLOTS).contains(&n) => return Some(n),
+            _ => ui.show_dialog(&format!("Please select {}-{} or q", first, SAVE_SLOTS)),
+        }
+    }
+}
+
+/// Shows a character creation screen until a valid option is picked.
+///
+/// # Arguments
+/// * `ui` - The UI to draw on
+/// * `content` - The choice screen to show
+/// * `options` - The values offered on the screen, in display order
+///
+/// # Returns
+/// The option picked by the player
+fn choose<T: Copy>(ui: &mut UI, content: Content, options: &[T]) -> T {
+    loop {
+        ui.update_content(Content::Empty);
+        ui.update_content(content);
+
+        let input = ui.get_input();
+        match input.parse::<usize>() {
+            Ok(n) if (1..=options.len()).contains(&n) => return options[n - 1],
+            _ => ui.show_dialog(&format!("Please select 1-{}", options.len())),
+        }
+    }
+}
+
+/// Lets the player pick a target with the targeting cursor.
+///
+/// # Arguments
+/// * `ui` - The UI to draw on
+/// * `game` - Reference to the current game state
+/// * `keymap` - The active key bindings
+/// * `range` - Maximum distance in tiles to the target
+///
+/// # Returns
+/// * `Some((x, y))` with the confirmed target position
+/// * `None` if targeting was cancelled
+fn pick_target(ui: &mut UI, game: &Game, keymap: &Keymap, range: i32) -> Option<(i32, i32)> {
+    let origin = (game.player.x, game.player.y);
+    let mut targeting = Targeting::new(origin, game.visible_targets(range), range);
+
+    loop {
+        ui.update_content(Content::Targeting(game, &targeting, keymap));
+        let input = ui.get_input();
+        if input.is_empty() {
+            return Some(targeting.cursor);
+        }
+        match keymap.command(&input) {
+            Some(Command::Fire(_) | Command::Throw(..) | Command::Cast(..) | Command::Use(..)) => {
+                return Some(targeting.cursor)
+            }
+            Some(Command::NextTarget) => targeting.next_target(),
+            Some(Command::Menu) => return None,
+            Some(Command::Move(direction)) => {
+                let (dx, dy) = direction.delta();
+                targeting.move_cursor(dx, dy);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Lets the player move a cursor over the map and examine what is there.
+///
+/// The cursor starts on the player and can jump between visible monsters and
+/// known items. Enter on a visible monster shows all details about it,
+/// anywhere else it leaves look mode.
+///
+/// # Arguments
+/// * `ui` - The UI to draw on
+/// * `game` - Reference to the current game state
+/// * `keymap` - The active key bindings
+fn look_around(ui: &mut UI, game: &Game, keymap: &Keymap) {
+    let origin = (game.player.x, game.player.y);
+    let mut targeting = Targeting::new(origin, game.look_targets(), i32::MAX);
+    targeting.cursor = origin;
+
+    loop {
+        ui.update_content(Content::Look(game, &targeting, keymap));
+        let input = ui.get_input();
+        let (x, y) = targeting.cursor;
+        let monster = game
+            .monster_at(x, y)
+            .filter(|&i| game.can_see_monster(&game.monsters[i]));
+
+        match keymap.command(&input) {
+            _ if input.is_empty() => match monster {
+                Some(index) => {
+                    ui.update_content(Content::Empty);
+                    ui.update_content(Content::MonsterInfo(&game.monsters[index]));
+                    ui.get_input();
+                    ui.update_content(Content::Empty);
+                }
+                None => return,
+            },
+            Some(Command::NextTarget) => targeting.next_target(),
+            Some(Command::Menu | Command::Look) => return,
+            Some(Command::Move(direction)) => {
+                let (dx, dy) = direction.delta();
+                if game.map.get_tile(x + dx, y + dy).is_some() {
+                    targeting.move_cursor(dx, dy);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Shows a screen listing the inventory until the player picks an item or
+/// goes back.
+///
+/// # Arguments
+/// * `ui` - The UI to draw on
+/// * `game` - The game whose inventory is listed
+/// * `screen` - The screen to show, [`Content::Equipment`] or
+///   [`Content::Inventory`]
+///
+/// # Returns
+/// * `Some(usize)` with the 0-based inventory index of the picked item
+/// * `None` if the player went back with Enter
+fn choose_item(ui: &mut UI, game: &Game, screen: Content) -> Option<usize> {
+    loop {
+        ui.update_content(Content::Empty);
+        ui.update_content(screen);
+
+        let input = ui.get_input();
+        if input.is_empty() {
+            ui.update_content(Content::Empty);
+            return None;
+        }
+        match parse_slot(Some(&input)) {
+            Some(index) if index < game.player.inventory.len() => {
+                ui.update_content(Content::Empty);
+                return Some(index);
+            }
+            _ => ui.show_dialog(&format!(
+                "Please select 1-{} or Enter",
+                game.player.inventory.len()
+            )),
+        }
+    }
+}
+
+/// Parses a 1-based inventory slot as shown in the stats panel.
+///
+/// # Returns
+/// * `Some(usize)` with the 0-based inventory index
+/// * `None` if the argument is not a positive number
+fn parse_slot(argument: Option<&str>) -> Option<usize> {
+    argument
+        .and_then(|arg| arg.parse::<usize>().ok())
+        .filter(|&slot| slot > 0)
+        .map(|slot| slot - 1)
+}
+
+/// Reads an in-game command with all its arguments.
+///
+/// The first word of the input is looked up in the keymap. Commands that
+/// act on a direction or an inventory item read it from the second word,
+/// e.g. `o w` to open the door above or `e 2` to equip the second item.
+/// Without a number the item is picked from a list of the inventory. Aimed
+/// commands let the player pick the target with the cursor.
+///
+/// # Returns
+/// * `Some(Command)` ready to be applied to the game
+/// * `None` if the input is incomplete or aiming was cancelled
+fn read_command(ui: &mut UI, game: &mut Game, keymap: &Keymap, input: &str) -> Option<Command> {
+    let mut words = input.split_whitespace();
+    let key = words.next().unwrap_or_default();
+    let argument = words.next();
+
+    let Some(command) = keymap.command(key) else {
+        game.log(format!("Unknown command: {}", input));
+        return None;
+    };
+
+    let direction = argument.and_then(|arg| keymap.direction(arg));
+    let slot = parse_slot(argument);
+    let command = match command {
+        Command::Open(_) | Command::Close(_) | Command::Unlock(_) if direction.is_none() => {
+            game.log(format!("Which direction? ({})", keymap.direction_hint()));
+            return None;
+        }
+        Command::Equip(_) if slot.is_none() => {
+            Command::Equip(Some(choose_item(ui, game, Content::Equipment(game))?))
+        }
+        Command::Use(..) if slot.is_none() => {
+            let screen = Content::Inventory(game, "use");
+            Command::Use(Some(choose_item(ui, game, screen)?), None)
+        }
+        Command::Throw(..) if slot.is_none() => {
+            let screen = Content::Inventory(game, "throw");
+            Command::Throw(Some(choose_item(ui, game, screen)?), None)
+        }
+        Command::Open(_) => Command::Open(direction),
+        Command::Close(_) => Command::Close(direction),
+        Command::Unlock(_) => Command::Unlock(direction),
+        Command::Equip(_) => Command::Equip(slot),
+        Command::Use(..) => Command::Use(slot, None),
+        Command::Throw(..) => Command::Throw(slot, None),
+        Command::Cast(..) => Command::Cast(slot, None),
+        command => command,
+    };
+
+    match game.target_range(command) {
+        Some(range) => pick_target(ui, game, keymap, range).map(|t| command.with_target(t)),
+        None => Some(command),
+    }
+}
@@ -1,6 +1,17 @@
 //! Map system module for managing game world and level generation.
 
-use crate::Room;
+use crate::{grid::Grid, light::LightLevel, Room};
+
+/// Represents the state of a door tile.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum DoorState {
+    /// Open door that can be walked and seen through
+    Open,
+    /// Closed door that blocks movement and sight until opened
+    Closed,
+    /// Locked door that only opens with the key of the given id
+    Locked(u32),
+}
 
 /// Represents different types of tiles in the game map.
 #[derive(Clone, PartialEq, Debug)]
@@ -10,9 +21,52 @@ pub enum Tile {
     /// Solid wall tile that blocks movement
     Wall,
     /// Door tile that can be opened/closed
-    Door,
+    Door(DoorState),
     /// Empty space (void/unused space)
     Empty,
+    /// Stairs leading to the next dungeon level
+    StairsDown,
+    /// Wall with a burning brazier that lights the tiles around it
+    Brazier,
+}
+
+impl Tile {
+    /// Gets the symbol the tile is drawn with on the game screen.
+    ///
+    /// # Returns
+    /// The glyph of the tile; closed and locked doors look the same
+    pub fn glyph(&self) -> char {
+        match self {
+            Tile::Floor => '.',
+            Tile::Wall => '#',
+            Tile::Door(DoorState::Open) => '\'',
+            Tile::Door(_) => '+',
+            Tile::Empty => ' ',
+            Tile::StairsDown => '>',
+            Tile::Brazier => '*',
+        }
+    }
+
+    /// Finds the tile drawn with a symbol.
+    ///
+    /// # Arguments
+    /// * `glyph` - A symbol as returned by [`Tile::glyph`]
+    ///
+    /// # Returns
+    /// * `Some(Tile)` for a known glyph, `+` being a closed door
+    /// * `None` otherwise
+    pub fn from_glyph(glyph: char) -> Option<Tile> {
+        match glyph {
+            '.' => Some(Tile::Floor),
+            '#' => Some(Tile::Wall),
+            '\'' => Some(Tile::Door(DoorState::Open)),
+            '+' => Some(Tile::Door(DoorState::Closed)),
+            ' ' => Some(Tile::Empty),
+            '>' => Some(Tile::StairsDown),
+            '*' => Some(Tile::Brazier),
+            _ => None,
+        }
+    }
 }
 
 /// Represents the game world map structure.
@@ -22,10 +76,18 @@ pub struct Map {
     pub width: i32,
     /// Height of the map in tiles
     pub height: i32,
-    /// 2D vector of tiles representing the map layout
-    pub tiles: Vec<Vec<Tile>>,
+    /// Tiles of the map layout
+    pub tiles: Grid<Tile>,
     /// Collection of rooms in the map
     pub rooms: Vec<Room>,
+    /// Tiles the player has seen at least once
+    pub explored: Grid<bool>,
+    /// Tiles the player can currently see
+    pub visible: Grid<bool>,
+    /// Light level of every tile, see [`crate::light::compute_light`]
+    pub light: Grid<LightLevel>,
+    /// Light level of tiles no light source reaches
+    pub ambient_light: LightLevel,
 }
 
 impl Map {
@@ -36,14 +98,17 @@ impl Map {
     /// * `height` - The height of the map in tiles
     ///
     /// # Returns
-    /// A new Map instance filled with Empty tiles
+    /// A new Map instance filled with Empty tiles, lit everywhere
     pub fn new(width: i32, height: i32) -> Self {
-        let tiles = vec![vec![Tile::Empty; width as usize]; height as usize];
         Map {
             width,
             height,
-            tiles,
+            tiles: Grid::new(width, height, Tile::Empty),
             rooms: Vec::new(),
+            explored: Grid::new(width, height, false),
+            visible: Grid::new(width, height, false),
+            light: Grid::new(width, height, LightLevel::Lit),
+            ambient_light: LightLevel::Lit,
         }
     }
 
@@ -57,11 +122,54 @@ impl Map {
     /// * `Some(&Tile)` if coordinates are valid
     /// * `None` if coordinates are out of bounds
     pub fn get_tile(&self, x: i32, y: i32) -> Option<&Tile> {
-        if x >= 0 && x < self.width && y >= 0 && y < self.height {
-            Some(&self.tiles[y as usize][x as usize])
-        } else {
-            None
+        self.tiles.get((x, y))
+    }
+
+    /// Creates a map from rows of tile glyphs.
+    ///
+    /// Shorter rows are padded with empty tiles, so trailing spaces may be
+    /// left out. The map has no rooms.
+    ///
+    /// # Arguments
+    /// * `text` - One line per row, using the glyphs of [`Tile::glyph`]
+    ///
+    /// # Returns
+    /// * `Ok(Map)` with the tiles of the text
+    /// * `Err(String)` naming the first unknown glyph
+    pub fn from_ascii(text: &str) -> Result<Map, String> {
+        let lines: Vec<&str> = text.lines().collect();
+        let width = lines
+            .iter()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0);
+        let mut map = Map::new(width as i32, lines.len() as i32);
+        for (y, line) in lines.iter().enumerate() {
+            for (x, glyph) in line.chars().enumerate() {
+                let tile = Tile::from_glyph(glyph).ok_or_else(|| {
+                    format!("line {}, column {}: unknown tile '{}'", y + 1, x + 1, glyph)
+                })?;
+                map.tiles[(x as i32, y as i32)] = tile;
+            }
         }
+        Ok(map)
+    }
+
+    /// Draws the tiles of the map as text.
+    ///
+    /// # Returns
+    /// One line per row using the glyphs of [`Tile::glyph`], each ending in
+    /// a newline
+    pub fn to_ascii(&self) -> String {
+        self.tiles
+            .rows()
+            .map(|row| {
+                row.iter()
+                    .map(Tile::glyph)
+                    .chain(['\n'])
+                    .collect::<String>()
+            })
+            .collect()
     }
 
     /// Sets a tile at the specified coordinates.
@@ -72,11 +180,72 @@ impl Map {
     /// * `tile` - The tile to set
     ///
     /// # Note
-    /// Silently fails if coordinates are out of bounds
+    /// Silently fails if coordinates are out of bounds, see
+    /// [`Map::try_set_tile`] for a variant that reports them
     pub fn set_tile(&mut self, x: i32, y: i32, tile: Tile) {
-        if x >= 0 && x < self.width && y >= 0 && y < self.height {
-            self.tiles[y as usize][x as usize] = tile;
+        self.tiles.set((x, y), tile);
+    }
+
+    /// Sets a tile, failing for coordinates outside the map.
+    ///
+    /// # Arguments
+    /// * `x` - The x-coordinate
+    /// * `y` - The y-coordinate
+    /// * `tile` - The tile to set
+    ///
+    /// # Returns
+    /// * `Ok(())` if the tile was set
+    /// * `Err(String)` if the coordinates are out of bounds
+    pub fn try_set_tile(&mut self, x: i32, y: i32, tile: Tile) -> Result<(), String> {
+        self.check_bounds(x, y)?;
+        self.tiles[(x, y)] = tile;
+        Ok(())
+    }
+
+    /// Marks a tile as explored, failing for coordinates outside the map.
+    ///
+    /// # Returns
+    /// * `Ok(())` if the tile was marked
+    /// * `Err(String)` if the coordinates are out of bounds
+    pub fn try_reveal(&mut self, x: i32, y: i32) -> Result<(), String> {
+        self.check_bounds(x, y)?;
+        self.explored[(x, y)] = true;
+        Ok(())
+    }
+
+    /// Checks that coordinates lie on the map.
+    fn check_bounds(&self, x: i32, y: i32) -> Result<(), String> {
+        if self.get_tile(x, y).is_none() {
+            return Err(format!(
+                "({}, {}) is outside the {}x{} map",
+                x, y, self.width, self.height
+            ));
         }
+        Ok(())
+    }
+
+    /// Checks whether the player currently sees a tile.
+    ///
+    /// # Returns
+    /// `true` if the tile is in view, `false` otherwise or if out of bounds
+    pub fn is_visible(&self, x: i32, y: i32) -> bool {
+        self.visible.get((x, y)) == Some(&true)
+    }
+
+    /// Checks whether the player has seen a tile before.
+    ///
+    /// # Returns
+    /// `true` if the tile was explored, `false` otherwise or if out of bounds
+    pub fn is_explored(&self, x: i32, y: i32) -> bool {
+        self.explored.get((x, y)) == Some(&true)
+    }
+
+    /// Marks a tile as explored without making it visible.
+    ///
+    /// # Note
+    /// Silently fails if coordinates are out of bounds
+    pub fn reveal(&mut self, x: i32, y: i32) {
+        self.explored.set((x, y), true);
     }
 
     /// Creates a room centered in the map.
@@ -100,4 +269,163 @@ impl Map {
             }
         }
     }
+
+    /// Carves a room at its own position and adds it to the room list.
+    ///
+    /// # Arguments
+    /// * `room` - The room to carve
+    ///
+    /// # Note
+    /// Creates walls around the perimeter and floor tiles inside
+    pub fn place_room(&mut self, room: Room) {
+        for y in room.y..room.y + room.height {
+            for x in room.x..room.x + room.width {
+                if room.contains(x, y) {
+                    self.set_tile(x, y, Tile::Floor);
+                } else {
+                    self.set_tile(x, y, Tile::Wall);
+                }
+            }
+        }
+        self.rooms.push(room);
+    }
+
+    /// Finds the starting position for the player.
+    ///
+    /// # Returns
+    /// * `Some((x, y))` of the floor tile closest to the map center
+    /// * `None` if the map has no floor tiles
+    pub fn spawn_point(&self) -> Option<(i32, i32)> {
+        let (cx, cy) = (self.width / 2, self.height / 2);
+        (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| (x, y)))
+            .filter(|&(x, y)| self.get_tile(x, y) == Some(&Tile::Floor))
+            .min_by_key(|&(x, y)| (x - cx).abs() + (y - cy).abs())
+    }
+
+    /// Checks whether a tile can be walked on.
+    ///
+    /// # Arguments
+    /// * `x` - The x-coordinate
+    /// * `y` - The y-coordinate
+    ///
+    /// # Returns
+    /// `true` for floor tiles and open doors, `false` otherwise
+    pub fn is_walkable(&self, x: i32, y: i32) -> bool {
+        matches!(
+            self.get_tile(x, y),
+            Some(Tile::Floor) | Some(Tile::StairsDown) | Some(Tile::Door(DoorState::Open))
+        )
+    }
+
+    /// Checks whether a tile blocks line of sight.
+    ///
+    /// # Arguments
+    /// * `x` - The x-coordinate
+    /// * `y` - The y-coordinate
+    ///
+    /// # Returns
+    /// `true` for walls, closed or locked doors and out of bounds coordinates
+    pub fn blocks_sight(&self, x: i32, y: i32) -> bool {
+        match self.get_tile(x, y) {
+            Some(Tile::Floor) | Some(Tile::Empty) | Some(Tile::StairsDown) => false,
+            Some(Tile::Door(state)) => *state != DoorState::Open,
+            Some(Tile::Wall) | Some(Tile::Brazier) | None => true,
+        }
+    }
+
+    /// Opens the door at the specified coordinates.
+    ///
+    /// # Arguments
+    /// * `x` - The x-coordinate
+    /// * `y` - The y-coordinate
+    ///
+    /// # Returns
+    /// * `Ok(())` if the door was opened
+    /// * `Err(String)` if there is no closed door or it is locked
+    pub fn open_door(&mut self, x: i32, y: i32) -> Result<(), String> {
+        match self.get_tile(x, y) {
+            Some(Tile::Door(DoorState::Closed)) => {
+                self.set_tile(x, y, Tile::Door(DoorState::Open));
+                Ok(())
+            }
+            Some(Tile::Door(DoorState::Open)) => Err("The door is already open.".to_string()),
+            Some(Tile::Door(DoorState::Locked(_))) => Err("The door is locked.".to_string()),
+            _ => Err("There is no door there.".to_string()),
+        }
+    }
+
+    /// Closes the door at the specified coordinates.
+    ///
+    /// # Arguments
+    /// * `x` - The x-coordinate
+    /// * `y` - The y-coordinate
+    ///
+    /// # Returns
+    /// * `Ok(())` if the door was closed
+    /// * `Err(String)` if there is no open door
+    pub fn close_door(&mut self, x: i32, y: i32) -> Result<(), String> {
+        match self.get_tile(x, y) {
+            Some(Tile::Door(DoorState::Open)) => {
+                self.set_tile(x, y, Tile::Door(DoorState::Closed));
+                Ok(())
+            }
+            Some(Tile::Door(_)) => Err("The door is already closed.".to_string()),
+            _ => Err("There is no door there.".to_string()),
+        }
+    }
+
+    /// Unlocks the door at the specified coordinates.
+    ///
+    /// # Arguments
+    /// * `x` - The x-coordinate
+    /// * `y` - The y-coordinate
+    /// * `key_id` - Id of the key used on the door
+    ///
+    /// # Returns
+    /// * `Ok(())` if the door was unlocked and is now closed
+    /// * `Err(String)` if there is no locked door or the key does not fit
+    pub fn unlock_door(&mut self, x: i32, y: i32, key_id: u32) -> Result<(), String> {
+        match self.get_tile(x, y) {
+            Some(Tile::Door(DoorState::Locked(id))) if *id == key_id => {
+                self.set_tile(x, y, Tile::Door(DoorState::Closed));
+                Ok(())
+            }
+            Some(Tile::Door(DoorState::Locked(_))) => Err("The key does not fit.".to_string()),
+            Some(Tile::Door(_)) => Err("The door is not locked.".to_string()),
+            _ => Err("There is no door there.".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ascii_round_trip() {
+        let text = "#####\n#..>#\n#+#'#\n  # \n";
+        let map = Map::from_ascii(text).unwrap();
+        assert_eq!((map.width, map.height), (5, 4));
+        assert_eq!(map.get_tile(1, 2), Some(&Tile::Door(DoorState::Closed)));
+        assert_eq!(map.get_tile(4, 3), Some(&Tile::Empty));
+        assert_eq!(map.to_ascii(), "#####\n#..>#\n#+#'#\n  #  \n");
+        assert_eq!(
+            Map::from_ascii("##\n#x").unwrap_err(),
+            "line 2, column 2: unknown tile 'x'"
+        );
+    }
+
+    #[test]
+    fn test_strict_variants_report_out_of_bounds() {
+        let mut map = Map::new(3, 2);
+        assert_eq!(map.try_set_tile(2, 1, Tile::Wall), Ok(()));
+        assert_eq!(map.get_tile(2, 1), Some(&Tile::Wall));
+        assert_eq!(
+            map.try_set_tile(3, 0, Tile::Wall),
+            Err("(3, 0) is outside the 3x2 map".to_string())
+        );
+        assert!(map.try_reveal(-1, 0).is_err());
+        assert!(map.try_reveal(0, 0).is_ok() && map.is_explored(0, 0));
+    }
 }
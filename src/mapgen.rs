@@ -0,0 +1,661 @@
+//! Map generator module with the layout styles a level can be built in.
+//!
+//! Every generator implements [`MapGenerator`]. Layouts are checked with a
+//! flood fill over `Map::tiles` afterwards: separate regions are joined by
+//! tunnels, tiny pockets are filled in, and a map that still falls apart is
+//! generated again.
+
+use std::collections::VecDeque;
+
+use crate::{
+    grid::{Grid, Point},
+    map::{DoorState, Tile},
+    rng::Rng,
+    vault::Transform,
+    Map, Room,
+};
+
+/// Number of layouts tried before the last one is joined by force.
+const GENERATION_ATTEMPTS: usize = 10;
+/// Regions with fewer tiles are filled in instead of being joined.
+const MIN_REGION_SIZE: usize = 4;
+
+/// Trait for algorithms that lay out the tiles of a level.
+pub trait MapGenerator {
+    /// Gets the name of the layout style, e.g. for debug output.
+    fn name(&self) -> &'static str;
+
+    /// Lays out a new map.
+    ///
+    /// The result may still consist of several regions,
+    /// [`generate_connected`] joins them.
+    ///
+    /// # Arguments
+    /// * `width` - Width of the map in tiles
+    /// * `height` - Height of the map in tiles
+    /// * `rng` - Random number generator
+    ///
+    /// # Returns
+    /// A new Map, with its rooms listed if the style has any
+    fn generate(&self, width: i32, height: i32, rng: &mut Rng) -> Map;
+}
+
+/// Represents the layout styles a floor can pick from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MapStyle {
+    /// Rectangular rooms joined by L-shaped corridors
+    Rooms,
+    /// Rooms in the leaves of a binary space partition
+    Bsp,
+    /// Natural caves grown by a cellular automaton
+    Caves,
+    /// Winding tunnels dug by a random walk
+    DrunkardsWalk,
+    /// Hand-made room shapes mixed with random rooms
+    Prefab,
+}
+
+impl MapStyle {
+    /// All layout styles.
+    pub const ALL: [MapStyle; 5] = [
+        MapStyle::Rooms,
+        MapStyle::Bsp,
+        MapStyle::Caves,
+        MapStyle::DrunkardsWalk,
+        MapStyle::Prefab,
+    ];
+
+    /// Gets the display name of the style.
+    pub fn name(&self) -> &'static str {
+        self.generator().name()
+    }
+
+    /// Gets the generator building maps in this style.
+    pub fn generator(&self) -> Box<dyn MapGenerator> {
+        match self {
+            MapStyle::Rooms => Box::new(RoomsAndCorridors),
+            MapStyle::Bsp => Box::new(Bsp),
+            MapStyle::Caves => Box::new(Caves),
+            MapStyle::DrunkardsWalk => Box::new(DrunkardsWalk),
+            MapStyle::Prefab => Box::new(PrefabMix),
+        }
+    }
+
+    /// Picks the style of a floor.
+    ///
+    /// The first floor always uses plain rooms, deeper floors pick any style.
+    ///
+    /// # Arguments
+    /// * `depth` - Dungeon depth, starting at 1
+    /// * `rng` - Random number generator
+    pub fn for_depth(depth: i32, rng: &mut Rng) -> MapStyle {
+        if depth <= 1 {
+            return MapStyle::Rooms;
+        }
+        MapStyle::ALL[rng.range(0, MapStyle::ALL.len() as i32) as usize]
+    }
+}
+
+/// Generates a map and makes sure every walkable tile can be reached.
+///
+/// # Arguments
+/// * `generator` - The layout style to use
+/// * `width` - Width of the map in tiles
+/// * `height` - Height of the map in tiles
+/// * `rng` - Random number generator
+///
+/// # Returns
+/// A connected Map with at least one floor tile
+pub fn generate_connected(
+    generator: &dyn MapGenerator,
+    width: i32,
+    height: i32,
+    rng: &mut Rng,
+) -> Map {
+    let mut map = generator.generate(width, height, rng);
+    for _ in 1..GENERATION_ATTEMPTS {
+        connect_regions(&mut map, rng);
+        if has_floor(&map) && regions(&map).len() == 1 {
+            return map;
+        }
+        map = generator.generate(width, height, rng);
+    }
+
+    connect_regions(&mut map, rng);
+    if !has_floor(&map) {
+        map = RoomsAndCorridors.generate(width, height, rng);
+    }
+    map
+}
+
+/// Checks whether a tile counts as walkable for connectivity.
+///
+/// Doors count as well, since they can be opened or unlocked.
+pub fn is_passable(map: &Map, x: i32, y: i32) -> bool {
+    matches!(
+        map.get_tile(x, y),
+        Some(Tile::Floor | Tile::StairsDown | Tile::Door(_))
+    )
+}
+
+/// Checks whether the map has any floor tile.
+fn has_floor(map: &Map) -> bool {
+    map.tiles.iter().any(|tile| *tile == Tile::Floor)
+}
+
+/// Finds every tile reachable from a start tile in straight steps.
+///
+/// # Arguments
+/// * `map` - The map to search
+/// * `start` - The tile to start from
+///
+/// # Returns
+/// A grid marking the reached tiles, all `false` if the start is not
+/// walkable
+pub fn flood_fill(map: &Map, start: (i32, i32)) -> Grid<bool> {
+    distances(map, start).map(Option::is_some)
+}
+
+/// Measures how many straight steps every tile is away from a start tile.
+///
+/// # Arguments
+/// * `map` - The map to measure
+/// * `start` - The tile to start from
+///
+/// # Returns
+/// A grid with the number of steps, `None` for tiles that cannot be
+/// reached
+pub fn distances(map: &Map, start: (i32, i32)) -> Grid<Option<i32>> {
+    let mut steps = Grid::new(map.width, map.height, None);
+    if !is_passable(map, start.0, start.1) {
+        return steps;
+    }
+
+    steps[start] = Some(0);
+    let mut queue = VecDeque::from([(Point::from(start), 0)]);
+    while let Some((point, distance)) = queue.pop_front() {
+        for next in Point::STRAIGHT.map(|offset| point + offset) {
+            if is_passable(map, next.x, next.y) && steps[next].is_none() {
+                steps[next] = Some(distance + 1);
+                queue.push_back((next, distance + 1));
+            }
+        }
+    }
+    steps
+}
+
+/// Splits the walkable tiles of a map into connected regions.
+///
+/// # Returns
+/// The tiles of every region, largest region first
+pub fn regions(map: &Map) -> Vec<Vec<(i32, i32)>> {
+    let mut assigned = Grid::new(map.width, map.height, false);
+    let mut regions = Vec::new();
+
+    for y in 0..map.height {
+        for x in 0..map.width {
+            if assigned[(x, y)] || !is_passable(map, x, y) {
+                continue;
+            }
+            let mut region = Vec::new();
+            for (point, _) in flood_fill(map, (x, y))
+                .iter_points()
+                .filter(|(_, inside)| **inside)
+            {
+                assigned[point] = true;
+                region.push(point.into());
+            }
+            regions.push(region);
+        }
+    }
+    regions.sort_by_key(|region| std::cmp::Reverse(region.len()));
+    regions
+}
+
+/// Joins all regions of a map to the largest one.
+///
+/// Pockets smaller than a few tiles are filled with wall instead, and new
+/// floor tiles get walls around them.
+fn connect_regions(map: &mut Map, rng: &mut Rng) {
+    let mut regions = regions(map);
+    if regions.len() < 2 {
+        return;
+    }
+
+    let mut main = regions.remove(0);
+    for region in regions {
+        if region.len() < MIN_REGION_SIZE {
+            for &(x, y) in &region {
+                map.set_tile(x, y, Tile::Wall);
+            }
+            continue;
+        }
+        let from = region[rng.range(0, region.len() as i32) as usize];
+        let to = main
+            .iter()
+            .copied()
+            .min_by_key(|&(x, y)| (x - from.0).abs() + (y - from.1).abs())
+            .unwrap_or(from);
+        dig_tunnel(map, from, to);
+        main.extend(region);
+    }
+    surround_with_walls(map);
+}
+
+/// Digs an L-shaped tunnel of floor tiles, keeping existing doors.
+fn dig_tunnel(map: &mut Map, from: (i32, i32), to: (i32, i32)) {
+    let (mut x, mut y) = from;
+    while (x, y) != to {
+        if x != to.0 {
+            x += (to.0 - x).signum();
+        } else {
+            y += (to.1 - y).signum();
+        }
+        if !matches!(map.get_tile(x, y), Some(Tile::Door(_) | Tile::StairsDown)) {
+            map.set_tile(x, y, Tile::Floor);
+        }
+    }
+}
+
+/// Turns every empty tile next to a walkable tile into a wall.
+pub fn surround_with_walls(map: &mut Map) {
+    for y in 0..map.height {
+        for x in 0..map.width {
+            if map.get_tile(x, y) != Some(&Tile::Empty) {
+                continue;
+            }
+            let next_to_floor = (-1..=1)
+                .flat_map(|dy| (-1..=1).map(move |dx| (dx, dy)))
+                .any(|(dx, dy)| is_passable(map, x + dx, y + dy));
+            if next_to_floor {
+                map.set_tile(x, y, Tile::Wall);
+            }
+        }
+    }
+}
+
+/// Layout of rectangular rooms joined by L-shaped corridors with doors.
+pub struct RoomsAndCorridors;
+
+/// Maximum number of rooms placed on a level.
+const MAX_ROOMS: usize = 6;
+/// Number of random room placements tried before giving up.
+const ROOM_ATTEMPTS: usize = 200;
+
+impl MapGenerator for RoomsAndCorridors {
+    fn name(&self) -> &'static str {
+        "rooms"
+    }
+
+    fn generate(&self, width: i32, height: i32, rng: &mut Rng) -> Map {
+        let mut rooms: Vec<Room> = Vec::new();
+        place_random_rooms(&mut rooms, MAX_ROOMS, width, height, rng);
+        if rooms.is_empty() {
+            rooms.push(Room::at(0, 0, width, height));
+        }
+        build_room_map(rooms, width, height, rng)
+    }
+}
+
+/// Places non-overlapping random rooms until there are enough of them.
+fn place_random_rooms(rooms: &mut Vec<Room>, count: usize, width: i32, height: i32, rng: &mut Rng) {
+    for _ in 0..ROOM_ATTEMPTS {
+        if rooms.len() >= count {
+            break;
+        }
+        let room_width = rng.range(5, 11);
+        let room_height = rng.range(4, 7);
+        let room = Room::at(
+            rng.range(0, width - room_width + 1),
+            rng.range(0, height - room_height + 1),
+            room_width,
+            room_height,
+        );
+        if !rooms.iter().any(|other| other.intersects(&room, 1)) {
+            rooms.push(room);
+        }
+    }
+}
+
+/// Carves rooms from left to right and joins neighbours with corridors.
+fn build_room_map(rooms: Vec<Room>, width: i32, height: i32, rng: &mut Rng) -> Map {
+    let mut map = carve_rooms(rooms, width, height);
+    connect_rooms(&mut map, rng);
+    map
+}
+
+/// Carves rooms into an empty map, sorted from left to right.
+fn carve_rooms(mut rooms: Vec<Room>, width: i32, height: i32) -> Map {
+    let mut map = Map::new(width, height);
+    rooms.sort_by_key(|room| room.x);
+    for room in rooms {
+        map.place_room(room);
+    }
+    map
+}
+
+/// Joins every room to the next one with a corridor and fixes the doors.
+fn connect_rooms(map: &mut Map, rng: &mut Rng) {
+    for i in 1..map.rooms.len() {
+        let from = map.rooms[i - 1].center();
+        let to = map.rooms[i].center();
+        carve_corridor(map, from, to, rng.chance(50));
+    }
+    fix_doors(map, rng);
+}
+
+/// Carves an L-shaped corridor, breaking through walls with doors.
+fn carve_corridor(map: &mut Map, from: (i32, i32), to: (i32, i32), horizontal_first: bool) {
+    let corner = if horizontal_first {
+        (to.0, from.1)
+    } else {
+        (from.0, to.1)
+    };
+
+    for (start, end) in [(from, corner), (corner, to)] {
+        let (mut x, mut y) = start;
+        loop {
+            match map.get_tile(x, y) {
+                Some(Tile::Empty) => map.set_tile(x, y, Tile::Floor),
+                Some(Tile::Wall) => map.set_tile(x, y, Tile::Door(DoorState::Closed)),
+                _ => {}
+            }
+            if (x, y) == end {
+                break;
+            }
+            x += (end.0 - x).signum();
+            y += (end.1 - y).signum();
+        }
+    }
+}
+
+/// Turns doors that are not framed by walls, or that sit next to another
+/// door, into plain floor. Some of the remaining doors are left open.
+fn fix_doors(map: &mut Map, rng: &mut Rng) {
+    let is_door = |map: &Map, x: i32, y: i32| matches!(map.get_tile(x, y), Some(Tile::Door(_)));
+    let is_wall = |map: &Map, x: i32, y: i32| map.get_tile(x, y) == Some(&Tile::Wall);
+
+    let doors: Vec<(i32, i32)> = (0..map.height)
+        .flat_map(|y| (0..map.width).map(move |x| (x, y)))
+        .filter(|&(x, y)| is_door(map, x, y))
+        .collect();
+
+    let broken: Vec<(i32, i32)> = doors
+        .iter()
+        .copied()
+        .filter(|&(x, y)| {
+            let framed = (is_wall(map, x - 1, y) && is_wall(map, x + 1, y))
+                || (is_wall(map, x, y - 1) && is_wall(map, x, y + 1));
+            let crowded = [(0, -1), (0, 1), (-1, 0), (1, 0)]
+                .iter()
+                .any(|(dx, dy)| is_door(map, x + dx, y + dy));
+            !framed || crowded
+        })
+        .collect();
+
+    for (x, y) in doors {
+        if broken.contains(&(x, y)) {
+            map.set_tile(x, y, Tile::Floor);
+        } else if rng.chance(20) {
+            map.set_tile(x, y, Tile::Door(DoorState::Open));
+        }
+    }
+}
+
+/// Layout splitting the map in halves until the parts are room-sized, with
+/// one room in every part.
+pub struct Bsp;
+
+/// Smallest width of a BSP part that still holds a room.
+const BSP_MIN_WIDTH: i32 = 7;
+/// Smallest height of a BSP part that still holds a room.
+const BSP_MIN_HEIGHT: i32 = 6;
+
+impl MapGenerator for Bsp {
+    fn name(&self) -> &'static str {
+        "bsp"
+    }
+
+    fn generate(&self, width: i32, height: i32, rng: &mut Rng) -> Map {
+        let mut leaves = Vec::new();
+        split(Room::at(0, 0, width, height), rng, &mut leaves);
+
+        let rooms = leaves
+            .into_iter()
+            .map(|leaf| {
+                let room_width = rng.range(5.min(leaf.width), leaf.width + 1);
+                let room_height = rng.range(4.min(leaf.height), leaf.height + 1);
+                Room::at(
+                    rng.range(leaf.x, leaf.x + leaf.width - room_width + 1),
+                    rng.range(leaf.y, leaf.y + leaf.height - room_height + 1),
+                    room_width,
+                    room_height,
+                )
+            })
+            .collect();
+        build_room_map(rooms, width, height, rng)
+    }
+}
+
+/// Splits a part of the map in two until it is too small to split again.
+///
+/// Parts keep a column or row free towards their neighbour, so the rooms
+/// inside them never touch.
+fn split(part: Room, rng: &mut Rng, leaves: &mut Vec<Room>) {
+    let can_split_x = part.width > BSP_MIN_WIDTH * 2;
+    let can_split_y = part.height > BSP_MIN_HEIGHT * 2;
+    let split_x = match (can_split_x, can_split_y) {
+        (false, false) => {
+            leaves.push(part);
+            return;
+        }
+        (true, true) => part.width * 2 >= part.height * 3 || rng.chance(50),
+        (split_x, _) => split_x,
+    };
+
+    if split_x {
+        let left = rng.range(BSP_MIN_WIDTH, part.width - BSP_MIN_WIDTH);
+        split(Room::at(part.x, part.y, left, part.height), rng, leaves);
+        let right = Room::at(
+            part.x + left + 1,
+            part.y,
+            part.width - left - 1,
+            part.height,
+        );
+        split(right, rng, leaves);
+    } else {
+        let top = rng.range(BSP_MIN_HEIGHT, part.height - BSP_MIN_HEIGHT);
+        split(Room::at(part.x, part.y, part.width, top), rng, leaves);
+        let bottom = Room::at(part.x, part.y + top + 1, part.width, part.height - top - 1);
+        split(bottom, rng, leaves);
+    }
+}
+
+/// Layout of natural caves grown by a cellular automaton.
+pub struct Caves;
+
+/// Chance in percent that a tile starts out as rock.
+const CAVE_FILL: i32 = 45;
+/// Number of smoothing steps of the automaton.
+const CAVE_STEPS: usize = 4;
+
+impl MapGenerator for Caves {
+    fn name(&self) -> &'static str {
+        "caves"
+    }
+
+    fn generate(&self, width: i32, height: i32, rng: &mut Rng) -> Map {
+        let border = |x: i32, y: i32| x == 0 || y == 0 || x == width - 1 || y == height - 1;
+        let mut rock = Grid::from_fn(width, height, |p| border(p.x, p.y) || rng.chance(CAVE_FILL));
+
+        for _ in 0..CAVE_STEPS {
+            let count = |point: Point| {
+                let around = Point::AROUND
+                    .iter()
+                    .filter(|offset| rock.get(point + **offset) != Some(&false))
+                    .count();
+                around + usize::from(rock[point])
+            };
+            rock = Grid::from_fn(width, height, |p| border(p.x, p.y) || count(p) >= 5);
+        }
+
+        let mut map = Map::new(width, height);
+        map.tiles = rock.map(|&is_rock| if is_rock { Tile::Wall } else { Tile::Floor });
+        map
+    }
+}
+
+/// Layout of tunnels dug by a walker stumbling around at random.
+pub struct DrunkardsWalk;
+
+/// Share of the tiles in percent the walker digs out.
+const WALK_FLOOR_PERCENT: i32 = 35;
+
+impl MapGenerator for DrunkardsWalk {
+    fn name(&self) -> &'static str {
+        "drunkard's walk"
+    }
+
+    fn generate(&self, width: i32, height: i32, rng: &mut Rng) -> Map {
+        let mut map = Map::new(width, height);
+        let goal = (width - 2) * (height - 2) * WALK_FLOOR_PERCENT / 100;
+        let (mut x, mut y) = (width / 2, height / 2);
+        let mut dug = 0;
+
+        for _ in 0..goal * 20 {
+            if dug >= goal {
+                break;
+            }
+            if map.get_tile(x, y) != Some(&Tile::Floor) {
+                map.set_tile(x, y, Tile::Floor);
+                dug += 1;
+            }
+            let (dx, dy) = [(0, -1), (0, 1), (-1, 0), (1, 0)][rng.range(0, 4) as usize];
+            x = (x + dx).clamp(1, width - 2);
+            y = (y + dy).clamp(1, height - 2);
+        }
+
+        surround_with_walls(&mut map);
+        map
+    }
+}
+
+/// Layout of hand-made room shapes mixed with random rectangular rooms.
+pub struct PrefabMix;
+
+/// Room shapes placed by [`PrefabMix`] in a random orientation: `#` is wall,
+/// `.` floor and a space stays empty. The centre of every shape is floor.
+const PREFAB_ROOMS: [&[&str]; 3] = [
+    &[
+        "  #####  ",
+        " ##...## ",
+        "##.....##",
+        "#.......#",
+        "##.....##",
+        " ##...## ",
+        "  #####  ",
+    ],
+    &[
+        "###########",
+        "#.........#",
+        "#.#.#.#.#.#",
+        "#.........#",
+        "#.#.#.#.#.#",
+        "#.........#",
+        "###########",
+    ],
+    &[
+        "   ###   ",
+        "   #.#   ",
+        "####.####",
+        "#.......#",
+        "####.####",
+        "   #.#   ",
+        "   ###   ",
+    ],
+];
+
+impl MapGenerator for PrefabMix {
+    fn name(&self) -> &'static str {
+        "prefab rooms"
+    }
+
+    fn generate(&self, width: i32, height: i32, rng: &mut Rng) -> Map {
+        let mut rooms: Vec<Room> = Vec::new();
+        let mut shapes = Vec::new();
+        for _ in 0..rng.range(1, 3) {
+            let shape = PREFAB_ROOMS[rng.range(0, PREFAB_ROOMS.len() as i32) as usize];
+            let transform = Transform::ALL[rng.range(0, Transform::ALL.len() as i32) as usize];
+            let rows: Vec<Vec<char>> = shape.iter().map(|line| line.chars().collect()).collect();
+            let shape = transform.apply(&rows);
+            let (shape_width, shape_height) = (shape[0].len() as i32, shape.len() as i32);
+            for _ in 0..ROOM_ATTEMPTS {
+                let room = Room::at(
+                    rng.range(0, width - shape_width + 1),
+                    rng.range(0, height - shape_height + 1),
+                    shape_width,
+                    shape_height,
+                );
+                if !rooms.iter().any(|other| other.intersects(&room, 1)) {
+                    shapes.push((room.x, room.y, shape));
+                    rooms.push(room);
+                    break;
+                }
+            }
+        }
+        place_random_rooms(&mut rooms, MAX_ROOMS, width, height, rng);
+
+        let mut map = carve_rooms(rooms, width, height);
+        for (left, top, shape) in shapes {
+            for (dy, row) in shape.iter().enumerate() {
+                for (dx, symbol) in row.iter().enumerate() {
+                    let tile = match symbol {
+                        '#' => Tile::Wall,
+                        '.' => Tile::Floor,
+                        _ => Tile::Empty,
+                    };
+                    map.set_tile(left + dx as i32, top + dy as i32, tile);
+                }
+            }
+        }
+        connect_rooms(&mut map, rng);
+        map
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_style_is_connected() {
+        for style in MapStyle::ALL {
+            for seed in 0..20 {
+                let mut rng = Rng::new(seed);
+                let map = generate_connected(style.generator().as_ref(), 40, 15, &mut rng);
+                let regions = regions(&map);
+                assert_eq!(regions.len(), 1, "{} with seed {}", style.name(), seed);
+                assert!(regions[0].len() > 20, "{} with seed {}", style.name(), seed);
+            }
+        }
+    }
+
+    #[test]
+    fn test_connect_regions_joins_separate_rooms() {
+        let mut map = Map::from_ascii(
+            "######      ######\n\
+             #....#      #....#\n\
+             #....#      #....#\n\
+             #....#      #....#\n\
+             #....#      #....#\n\
+             ######      ### ##\n\
+             \x20              .",
+        )
+        .unwrap();
+        assert_eq!(regions(&map).len(), 3);
+
+        connect_regions(&mut map, &mut Rng::new(1));
+        assert_eq!(regions(&map).len(), 1);
+        assert_eq!(map.get_tile(15, 6), Some(&Tile::Wall));
+    }
+}
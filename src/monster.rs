@@ -0,0 +1,250 @@
+//! Monster module containing hostile creatures and their stats.
+
+use crate::{
+    map::{DoorState, Tile},
+    status::{merge_status, StatusEffect, StatusKind},
+    Map,
+};
+
+/// Represents a hostile creature on the map.
+#[derive(Debug, Clone)]
+pub struct Monster {
+    /// Name of the monster
+    pub name: String,
+    /// Character used to draw the monster on the map
+    pub glyph: char,
+    /// X-coordinate on the map
+    pub x: i32,
+    /// Y-coordinate on the map
+    pub y: i32,
+    /// Current health points
+    pub health: i32,
+    /// Maximum health points
+    pub max_health: i32,
+    /// Attack power
+    pub attack: i32,
+    /// Defense value
+    pub defense: i32,
+    /// Experience awarded to the player on death
+    pub experience: i32,
+    /// Whether the monster is able to open closed doors
+    pub opens_doors: bool,
+    /// Name of the loot table rolled when the monster dies
+    pub loot_table: Option<String>,
+    /// Whether the monster hunts the player even without seeing it
+    pub alerted: bool,
+    /// Radius in tiles of the light the monster gives off, 0 for none
+    pub glow: i32,
+    /// Whether the monster can only be seen in good light or up close
+    pub stealthy: bool,
+    /// Active status effects
+    pub status_effects: Vec<StatusEffect>,
+}
+
+impl Monster {
+    /// Creates a new monster at the map origin.
+    ///
+    /// # Arguments
+    /// * `name` - The name of the monster
+    /// * `glyph` - The map symbol of the monster
+    /// * `health` - Starting and maximum health
+    /// * `attack` - Attack power
+    /// * `defense` - Defense value
+    /// * `experience` - Experience awarded on death
+    ///
+    /// # Returns
+    /// A new Monster instance that cannot open doors, drops nothing, gives
+    /// off no light and is not stealthy
+    pub fn new(
+        name: String,
+        glyph: char,
+        health: i32,
+        attack: i32,
+        defense: i32,
+        experience: i32,
+    ) -> Self {
+        Monster {
+            name,
+            glyph,
+            x: 0,
+            y: 0,
+            health,
+            max_health: health,
+            attack,
+            defense,
+            experience,
+            opens_doors: false,
+            loot_table: None,
+            alerted: false,
+            glow: 0,
+            stealthy: false,
+            status_effects: Vec::new(),
+        }
+    }
+
+    /// Creates a goblin at the given position.
+    ///
+    /// # Returns
+    /// A pre-configured goblin that can open doors and hides in shadows
+    pub fn create_goblin(x: i32, y: i32) -> Self {
+        Monster {
+            x,
+            y,
+            opens_doors: true,
+            stealthy: true,
+            loot_table: Some("goblin".to_string()),
+            ..Monster::new("Goblin".to_string(), 'g', 30, 14, 4, 35)
+        }
+    }
+
+    /// Creates a giant rat at the given position.
+    ///
+    /// # Returns
+    /// A pre-configured rat that is stopped by closed doors
+    pub fn create_rat(x: i32, y: i32) -> Self {
+        Monster {
+            x,
+            y,
+            loot_table: Some("rat".to_string()),
+            ..Monster::new("Giant Rat".to_string(), 'r', 12, 12, 1, 15)
+        }
+    }
+
+    /// Creates a fire beetle at the given position.
+    ///
+    /// # Returns
+    /// A pre-configured beetle that lights up the tiles around it
+    pub fn create_fire_beetle(x: i32, y: i32) -> Self {
+        Monster {
+            x,
+            y,
+            glow: 3,
+            ..Monster::new("Fire Beetle".to_string(), 'b', 20, 13, 5, 25)
+        }
+    }
+
+    /// Applies damage to the monster.
+    ///
+    /// # Arguments
+    /// * `amount` - Raw damage amount before defense calculation
+    ///
+    /// # Returns
+    /// `true` if the monster dies from this damage, `false` otherwise
+    pub fn take_damage(&mut self, amount: i32) -> bool {
+        let damage = (amount - self.defense).max(1);
+        self.health -= damage;
+        self.health <= 0
+    }
+
+    /// Restores health, up to the monster's maximum.
+    ///
+    /// # Arguments
+    /// * `amount` - Amount of health to restore
+    pub fn heal(&mut self, amount: i32) {
+        self.health = (self.health + amount).min(self.max_health);
+    }
+
+    /// Applies a status effect to the monster.
+    ///
+    /// # Arguments
+    /// * `effect` - The effect to apply
+    pub fn add_status(&mut self, effect: StatusEffect) {
+        merge_status(&mut self.status_effects, effect);
+    }
+
+    /// Applies all active status effects for one turn.
+    ///
+    /// # Returns
+    /// `true` if the monster dies from its effects, `false` otherwise
+    pub fn tick_status_effects(&mut self) -> bool {
+        for effect in &mut self.status_effects {
+            match effect.kind {
+                StatusKind::Poisoned => self.health -= effect.potency,
+            }
+            effect.turns -= 1;
+        }
+
+        self.status_effects.retain(|effect| effect.turns > 0);
+        !self.is_alive()
+    }
+
+    /// Gets a rough description of the monster's health, as the player
+    /// would judge it at a glance.
+    ///
+    /// # Returns
+    /// A phrase from "unhurt" to "almost dead"
+    pub fn health_estimate(&self) -> &'static str {
+        let percent = self.health.max(0) * 100 / self.max_health.max(1);
+        match percent {
+            100.. => "unhurt",
+            75..=99 => "lightly wounded",
+            50..=74 => "wounded",
+            25..=49 => "badly wounded",
+            _ => "almost dead",
+        }
+    }
+
+    /// Lists what the monster is able to do besides fighting.
+    ///
+    /// # Returns
+    /// Short sentences, one per ability
+    pub fn abilities(&self) -> Vec<&'static str> {
+        let mut abilities = vec![if self.opens_doors {
+            "Opens closed doors"
+        } else {
+            "Is stopped by closed doors"
+        }];
+        if self.loot_table.is_some() {
+            abilities.push("May drop loot");
+        }
+        if self.alerted {
+            abilities.push("Is hunting you");
+        }
+        abilities
+    }
+
+    /// Checks if the monster is alive.
+    ///
+    /// # Returns
+    /// `true` if health is above 0, `false` otherwise
+    pub fn is_alive(&self) -> bool {
+        self.health > 0
+    }
+
+    /// Checks whether the monster may step onto or through a tile.
+    ///
+    /// # Arguments
+    /// * `map` - The map the monster is on
+    /// * `x` - The x-coordinate
+    /// * `y` - The y-coordinate
+    ///
+    /// # Returns
+    /// `true` for walkable tiles, and for closed doors if the monster can open
+    /// them. Locked doors always stop monsters.
+    pub fn can_pass(&self, map: &Map, x: i32, y: i32) -> bool {
+        map.is_walkable(x, y)
+            || (self.opens_doors && map.get_tile(x, y) == Some(&Tile::Door(DoorState::Closed)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_door_openers_pass_closed_doors() {
+        let mut map = Map::from_ascii("#####\n#.+.#\n#####").unwrap();
+        let goblin = Monster::create_goblin(1, 1);
+        let rat = Monster::create_rat(1, 1);
+
+        assert!(goblin.can_pass(&map, 1, 1) && rat.can_pass(&map, 1, 1));
+        assert!(goblin.can_pass(&map, 2, 1));
+        assert!(!rat.can_pass(&map, 2, 1));
+        assert!(!goblin.can_pass(&map, 0, 1));
+
+        map.set_tile(2, 1, Tile::Door(DoorState::Locked(1)));
+        assert!(!goblin.can_pass(&map, 2, 1));
+        map.set_tile(2, 1, Tile::Door(DoorState::Open));
+        assert!(rat.can_pass(&map, 2, 1));
+    }
+}
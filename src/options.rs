@@ -0,0 +1,300 @@
+//! Options module holding the player's settings and their config file.
+//!
+//! The options file has one `name = value` pair per line, e.g.
+//! `message_speed = fast`. Everything after a `#` is a comment.
+
+use std::{fs, time::Duration};
+
+use crate::keymap::Preset;
+
+/// Default file the options are stored in.
+pub const OPTIONS_FILE: &str = "options.cfg";
+
+/// Represents how the map is coloured.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColorMode {
+    /// Monsters, items and features are drawn in colour
+    Color,
+    /// Everything is drawn in the terminal's default colour
+    Monochrome,
+}
+
+impl ColorMode {
+    /// All colour modes in the order they are cycled through.
+    pub const ALL: [ColorMode; 2] = [ColorMode::Color, ColorMode::Monochrome];
+
+    /// Gets the name used for the colour mode in the options file.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ColorMode::Color => "color",
+            ColorMode::Monochrome => "monochrome",
+        }
+    }
+}
+
+/// Represents how long messages stay on screen.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MessageSpeed {
+    /// Messages do not wait at all
+    Instant,
+    /// Short pauses
+    Fast,
+    /// Medium pauses
+    Normal,
+    /// Long pauses
+    Slow,
+}
+
+impl MessageSpeed {
+    /// All message speeds in the order they are cycled through.
+    pub const ALL: [MessageSpeed; 4] = [
+        MessageSpeed::Instant,
+        MessageSpeed::Fast,
+        MessageSpeed::Normal,
+        MessageSpeed::Slow,
+    ];
+
+    /// Gets the name used for the message speed in the options file.
+    pub fn name(&self) -> &'static str {
+        match self {
+            MessageSpeed::Instant => "instant",
+            MessageSpeed::Fast => "fast",
+            MessageSpeed::Normal => "normal",
+            MessageSpeed::Slow => "slow",
+        }
+    }
+
+    /// Gets how long a dialog message stays on screen.
+    pub fn dialog_delay(&self) -> Duration {
+        Duration::from_millis(match self {
+            MessageSpeed::Instant => 0,
+            MessageSpeed::Fast => 600,
+            MessageSpeed::Normal => 1200,
+            MessageSpeed::Slow => 2000,
+        })
+    }
+}
+
+/// Represents all settings of the game.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Options {
+    /// How the map is coloured
+    pub color: ColorMode,
+    /// Key binding preset, overrides from the keymap file apply on top
+    pub keymap_preset: Preset,
+    /// How long messages stay on screen
+    pub message_speed: MessageSpeed,
+    /// Whether the game saves automatically on new levels and when quitting
+    pub auto_save: bool,
+    /// Whether quitting a running game asks for confirmation
+    pub confirm_quit: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            color: ColorMode::Color,
+            keymap_preset: Preset::Wasd,
+            message_speed: MessageSpeed::Slow,
+            auto_save: false,
+            confirm_quit: true,
+        }
+    }
+}
+
+impl Options {
+    /// Names of all options in the order they are shown and stored.
+    pub const NAMES: [&'static str; 5] = [
+        "color",
+        "keymap_preset",
+        "message_speed",
+        "auto_save",
+        "confirm_quit",
+    ];
+
+    /// Loads the options from a file.
+    ///
+    /// A missing file yields the default options.
+    ///
+    /// # Arguments
+    /// * `path` - Path of the options file
+    ///
+    /// # Returns
+    /// * `Ok(Options)` with the stored settings
+    /// * `Err(String)` if the file cannot be read or holds invalid settings
+    pub fn load(path: &str) -> Result<Self, String> {
+        match fs::read_to_string(path) {
+            Ok(content) => Options::parse(&content).map_err(|e| format!("{}: {}", path, e)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Options::default()),
+            Err(e) => Err(format!("Cannot read {}: {}", path, e)),
+        }
+    }
+
+    /// Writes the options to a file.
+    ///
+    /// # Arguments
+    /// * `path` - Path of the options file
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(String)` if the file cannot be written
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        let content: String = Options::NAMES
+            .iter()
+            .map(|name| format!("{} = {}\n", name, self.value(name)))
+            .collect();
+        fs::write(path, content).map_err(|e| format!("Cannot write {}: {}", path, e))
+    }
+
+    /// Parses the content of an options file.
+    ///
+    /// Options that are not listed keep their default value.
+    ///
+    /// # Returns
+    /// * `Ok(Options)` with the parsed settings
+    /// * `Err(String)` naming the line and what is wrong with it
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let mut options = Options::default();
+        for (number, line) in content.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let (name, value) = line
+                .split_once('=')
+                .ok_or_else(|| format!("line {}: expected 'name = value'", number + 1))?;
+            options
+                .set(name.trim(), value.trim())
+                .map_err(|e| format!("line {}: {}", number + 1, e))?;
+        }
+        Ok(options)
+    }
+
+    /// Sets an option from its stored text.
+    ///
+    /// # Arguments
+    /// * `name` - Name of the option
+    /// * `value` - New value of the option
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(String)` naming the allowed values if the value is invalid
+    pub fn set(&mut self, name: &str, value: &str) -> Result<(), String> {
+        fn pick<T: Copy>(
+            all: &[T],
+            name_of: fn(&T) -> &'static str,
+            option: &str,
+            value: &str,
+        ) -> Result<T, String> {
+            all.iter()
+                .find(|v| name_of(v) == value)
+                .copied()
+                .ok_or_else(|| {
+                    let allowed: Vec<&str> = all.iter().map(name_of).collect();
+                    format!(
+                        "invalid {} '{}', expected one of: {}",
+                        option,
+                        value,
+                        allowed.join(", ")
+                    )
+                })
+        }
+        let flag = |value: &str| match value {
+            "on" | "true" | "yes" => Ok(true),
+            "off" | "false" | "no" => Ok(false),
+            _ => Err(format!(
+                "invalid {} '{}', expected one of: on, off",
+                name, value
+            )),
+        };
+
+        match name {
+            "color" => self.color = pick(&ColorMode::ALL, ColorMode::name, name, value)?,
+            "keymap_preset" => self.keymap_preset = pick(&Preset::ALL, Preset::name, name, value)?,
+            "message_speed" => {
+                self.message_speed = pick(&MessageSpeed::ALL, MessageSpeed::name, name, value)?
+            }
+            "auto_save" => self.auto_save = flag(value)?,
+            "confirm_quit" => self.confirm_quit = flag(value)?,
+            _ => {
+                return Err(format!(
+                    "unknown option '{}', expected one of: {}",
+                    name,
+                    Options::NAMES.join(", ")
+                ))
+            }
+        }
+        Ok(())
+    }
+
+    /// Gets the stored text of an option.
+    ///
+    /// # Arguments
+    /// * `name` - Name of the option, one of [`Options::NAMES`]
+    pub fn value(&self, name: &str) -> &'static str {
+        let flag = |on: bool| if on { "on" } else { "off" };
+        match name {
+            "color" => self.color.name(),
+            "keymap_preset" => self.keymap_preset.name(),
+            "message_speed" => self.message_speed.name(),
+            "auto_save" => flag(self.auto_save),
+            "confirm_quit" => flag(self.confirm_quit),
+            _ => "",
+        }
+    }
+
+    /// Switches an option to its next value, wrapping around.
+    ///
+    /// # Arguments
+    /// * `name` - Name of the option, one of [`Options::NAMES`]
+    pub fn cycle(&mut self, name: &str) {
+        fn next<T: Copy + PartialEq>(all: &[T], current: T) -> T {
+            let index = all.iter().position(|v| *v == current).unwrap_or(0);
+            all[(index + 1) % all.len()]
+        }
+        match name {
+            "color" => self.color = next(&ColorMode::ALL, self.color),
+            "keymap_preset" => self.keymap_preset = next(&Preset::ALL, self.keymap_preset),
+            "message_speed" => self.message_speed = next(&MessageSpeed::ALL, self.message_speed),
+            "auto_save" => self.auto_save = !self.auto_save,
+            "confirm_quit" => self.confirm_quit = !self.confirm_quit,
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_and_cycle() {
+        let mut options = Options::default();
+        for name in Options::NAMES {
+            options.cycle(name);
+        }
+        let content: String = Options::NAMES
+            .iter()
+            .map(|name| format!("{} = {}\n", name, options.value(name)))
+            .collect();
+
+        assert_eq!(Options::parse(&content).unwrap(), options);
+        assert_eq!(options.keymap_preset, Preset::Vi);
+        assert!(!options.confirm_quit);
+    }
+
+    #[test]
+    fn test_invalid_values_are_explained() {
+        let error =
+            Options::parse("# mine\ncolor = monochrome # no colours\nmessage_speed = turbo")
+                .unwrap_err();
+        assert_eq!(
+            error,
+            "line 3: invalid message_speed 'turbo', expected one of: instant, fast, normal, slow"
+        );
+
+        let error = Options::parse("colour = color").unwrap_err();
+        assert!(error.contains("unknown option 'colour'"), "{}", error);
+        assert!(Options::parse("auto_save").is_err());
+    }
+}
@@ -1,14 +1,41 @@
 //! Player module containing player character stats and inventory management.
 
-use crate::{Item, ItemType};
+use crate::{
+    character::{Background, Class, Stats},
+    equipment::{Equipment, Slot},
+    hunger::{HungerState, MAX_FOOD, STARTING_FOOD},
+    item::PotionKind,
+    spell::Spellbook,
+    status::{merge_status, StatusEffect, StatusKind},
+    Item, ItemType,
+};
+
+/// Fuel left when the carried light starts to flicker.
+const LOW_FUEL: i32 = 25;
 
 /// Represents the player character and their attributes.
 #[derive(Debug)]
 pub struct Player {
     /// Player's name
     pub name: String,
+    /// Class chosen at character creation
+    pub class: Class,
+    /// Background chosen at character creation
+    pub background: Background,
+    /// X-coordinate on the map
+    pub x: i32,
+    /// Y-coordinate on the map
+    pub y: i32,
     /// Current health points
     pub health: i32,
+    /// Maximum health points
+    pub max_health: i32,
+    /// Current mana points used for casting spells
+    pub mana: i32,
+    /// Maximum mana points
+    pub max_mana: i32,
+    /// Spells the player has learned
+    pub spellbook: Spellbook,
     /// Base attack power + equipment bonus
     pub attack: i32,
     /// Base defense + equipment bonus
@@ -21,12 +48,16 @@ pub struct Player {
     pub experience: i32,
     /// Experience needed for next level
     pub experience_to_next_level: i32,
+    /// Food left, going down by one every turn
+    pub food: i32,
     /// Player's inventory of items
     pub inventory: Vec<Item>,
-    /// Currently equipped weapon
-    pub equipped_weapon: Option<Item>,
-    /// Currently equipped armor
-    pub equipped_armor: Option<Item>,
+    /// Currently worn weapons, armor and jewelry
+    pub equipment: Equipment,
+    /// Currently carried light source
+    pub equipped_light: Option<Item>,
+    /// Active status effects
+    pub status_effects: Vec<StatusEffect>,
 }
 
 impl Player {
@@ -36,20 +67,60 @@ impl Player {
     /// * `name` - The name of the player character
     ///
     /// # Returns
-    /// A new Player instance with default starting stats
+    /// A new Player instance with default warrior stats and no equipment
     pub fn new(name: String) -> Self {
+        Player::with_class(name, Class::Warrior, Background::Wanderer)
+    }
+
+    /// Creates a fully equipped player character from creation choices.
+    ///
+    /// # Arguments
+    /// * `name` - The name of the player character
+    /// * `class` - The chosen class
+    /// * `background` - The chosen background
+    ///
+    /// # Returns
+    /// A new Player instance with the class and background starting stats,
+    /// equipment and inventory
+    pub fn create(name: String, class: Class, background: Background) -> Self {
+        let mut player = Player::with_class(name, class, background);
+        player
+            .equipment
+            .set(Slot::MainHand, class.starting_weapon());
+        player.equipment.set(Slot::Body, class.starting_armor());
+        player.equipped_light = Some(Item::create_torch());
+        player.inventory.push(Item::create_food_ration());
+        player.inventory.extend(class.starting_inventory());
+        player.inventory.extend(background.starting_inventory());
+        player.update_stats();
+        player
+    }
+
+    /// Creates a player with the stats of a class and background.
+    fn with_class(name: String, class: Class, background: Background) -> Self {
+        let stats = class.base_stats().add(background.bonus());
         Player {
             name,
-            health: 100,
-            attack: 10,
-            defense: 10,
-            speed: 10,
+            class,
+            background,
+            x: 0,
+            y: 0,
+            health: stats.health,
+            max_health: stats.health,
+            mana: class.base_mana(),
+            max_mana: class.base_mana(),
+            spellbook: Spellbook::new(&class.starting_spells()),
+            attack: stats.attack,
+            defense: stats.defense,
+            speed: stats.speed,
             level: 1,
             experience: 0,
             experience_to_next_level: 100,
+            food: STARTING_FOOD,
             inventory: Vec::new(),
-            equipped_weapon: None,
-            equipped_armor: None,
+            equipment: Equipment::default(),
+            equipped_light: None,
+            status_effects: Vec::new(),
         }
     }
 
@@ -71,10 +142,20 @@ impl Player {
         self.level += 1;
         self.experience -= self.experience_to_next_level;
         self.experience_to_next_level = (self.experience_to_next_level as f32 * 1.1) as i32;
-        self.health += 10;
-        self.attack += 2;
-        self.defense += 2;
-        self.speed += 2;
+
+        let growth = self.class.growth();
+        self.health += growth.health;
+        self.max_mana += self.class.mana_growth();
+        self.mana += self.class.mana_growth();
+        self.update_stats();
+    }
+
+    /// Gets the level 1 stats from class and background.
+    ///
+    /// # Returns
+    /// The base stats before level ups and equipment
+    pub fn base_stats(&self) -> Stats {
+        self.class.base_stats().add(self.background.bonus())
     }
 
     /// Applies damage to the player.
@@ -95,7 +176,15 @@ impl Player {
     /// # Arguments
     /// * `amount` - Amount of health to restore
     pub fn heal(&mut self, amount: i32) {
-        self.health += amount;
+        self.health = (self.health + amount).min(self.max_health);
+    }
+
+    /// Restores mana, up to the player's maximum.
+    ///
+    /// # Arguments
+    /// * `amount` - Amount of mana to restore
+    pub fn restore_mana(&mut self, amount: i32) {
+        self.mana = (self.mana + amount).min(self.max_mana);
     }
 
     /// Checks if the player is alive.
@@ -108,6 +197,8 @@ impl Player {
 
     /// Equips an item from the inventory.
     ///
+    /// Items taken off to make room go back into the inventory.
+    ///
     /// # Arguments
     /// * `inventory_index` - Index of the item in the inventory to equip
     ///
@@ -120,26 +211,92 @@ impl Player {
         }
 
         let item = self.inventory[inventory_index].clone();
-        match item.item_type {
-            ItemType::Weapon => {
-                if let Some(old_weapon) = self.equipped_weapon.take() {
-                    self.inventory.push(old_weapon);
-                }
-                self.equipped_weapon = Some(item);
-                self.inventory.remove(inventory_index);
-                self.update_stats();
-                Ok(())
+        if let ItemType::Light { .. } = item.item_type {
+            if let Some(old_light) = self.equipped_light.take() {
+                self.inventory.push(old_light);
             }
-            ItemType::Armor => {
-                if let Some(old_armor) = self.equipped_armor.take() {
-                    self.inventory.push(old_armor);
-                }
-                self.equipped_armor = Some(item);
-                self.inventory.remove(inventory_index);
-                self.update_stats();
-                Ok(())
+            self.equipped_light = Some(item);
+            self.inventory.remove(inventory_index);
+            return Ok(());
+        }
+
+        let removed = self.equipment.equip(item)?;
+        self.inventory.remove(inventory_index);
+        self.inventory.extend(removed);
+        self.update_stats();
+        Ok(())
+    }
+
+    /// Gets how hungry the player is.
+    pub fn hunger(&self) -> HungerState {
+        HungerState::from_food(self.food)
+    }
+
+    /// Restores food, up to [`MAX_FOOD`].
+    ///
+    /// # Arguments
+    /// * `amount` - Nutrition of the food eaten
+    pub fn eat(&mut self, amount: i32) {
+        self.food = (self.food + amount).min(MAX_FOOD);
+        self.update_stats();
+    }
+
+    /// Uses up one turn of food. A starving player loses 1 health instead.
+    ///
+    /// # Returns
+    /// * `Some(String)` with a message when the hunger state gets worse
+    /// * `None` otherwise
+    pub fn tick_hunger(&mut self) -> Option<String> {
+        if self.food == 0 {
+            self.health -= 1;
+            return None;
+        }
+
+        let before = self.hunger();
+        self.food -= 1;
+        let after = self.hunger();
+        if after == before {
+            return None;
+        }
+        self.update_stats();
+        (after > HungerState::NotHungry).then(|| after.message().to_string())
+    }
+
+    /// Gets how far the carried light reaches.
+    ///
+    /// # Returns
+    /// * `Some(i32)` radius in tiles of a light with fuel left
+    /// * `None` if no light is carried
+    pub fn light_radius(&self) -> Option<i32> {
+        match &self.equipped_light {
+            Some(Item {
+                item_type: ItemType::Light { fuel },
+                value,
+                ..
+            }) if *fuel > 0 => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Burns one turn of fuel of the carried light.
+    ///
+    /// # Returns
+    /// * `Some(String)` with a message when the light runs low or burns out
+    /// * `None` otherwise
+    pub fn burn_light(&mut self) -> Option<String> {
+        let light = self.equipped_light.as_mut()?;
+        let ItemType::Light { fuel } = &mut light.item_type else {
+            return None;
+        };
+        *fuel -= 1;
+        match *fuel {
+            LOW_FUEL => Some(format!("Your {} flickers.", light.name.to_lowercase())),
+            fuel if fuel <= 0 => {
+                let name = light.name.to_lowercase();
+                self.equipped_light = None;
+                Some(format!("Your {} burns out.", name))
             }
-            _ => Err("This item cannot be used".to_string()),
+            _ => None,
         }
     }
 
@@ -158,28 +315,229 @@ impl Player {
 
         let item = &self.inventory[inventory_index];
         match item.item_type {
-            ItemType::Potion => {
-                self.health += item.value;
+            ItemType::Potion(kind) => {
+                let value = item.value;
+                self.inventory.remove(inventory_index);
+                match kind {
+                    PotionKind::Healing => self.heal(value),
+                    PotionKind::Fire => {
+                        self.take_damage(value);
+                    }
+                    PotionKind::Poison => {
+                        self.add_status(StatusEffect::new(StatusKind::Poisoned, 5, value));
+                    }
+                }
+                Ok(())
+            }
+            ItemType::Food => {
+                let value = item.value;
                 self.inventory.remove(inventory_index);
+                self.eat(value);
                 Ok(())
             }
             _ => Err("This Item cannot be used.".to_string()),
         }
     }
 
-    /// Updates player stats based on equipped items.
-    /// Called automatically when equipment changes.
-    fn update_stats(&mut self) {
-        self.attack = 10 + (self.level - 1) * 2;
+    /// Puts an item into the inventory.
+    ///
+    /// Ammunition is merged into an existing stack of the same name.
+    ///
+    /// # Arguments
+    /// * `item` - The item to store
+    pub fn store_item(&mut self, item: Item) {
+        if let ItemType::Ammo { quantity: added } = item.item_type {
+            for stored in &mut self.inventory {
+                if let ItemType::Ammo { quantity } = &mut stored.item_type {
+                    if stored.name == item.name {
+                        *quantity += added;
+                        return;
+                    }
+                }
+            }
+        }
+        self.inventory.push(item);
+    }
+
+    /// Gets the equipped ranged weapon.
+    ///
+    /// # Returns
+    /// * `Some((&Item, i32))` with the weapon and its range
+    /// * `None` if no ranged weapon is equipped
+    pub fn ranged_weapon(&self) -> Option<(&Item, i32)> {
+        match self.equipment.get(Slot::MainHand) {
+            Some(
+                weapon @ Item {
+                    item_type: ItemType::RangedWeapon { range },
+                    ..
+                },
+            ) => Some((weapon, *range)),
+            _ => None,
+        }
+    }
+
+    /// Takes one shot of ammunition from the inventory.
+    ///
+    /// Empty stacks are removed from the inventory.
+    ///
+    /// # Returns
+    /// * `Ok(Item)` with a single shot of the ammunition
+    /// * `Err(String)` if the player carries no ammunition
+    pub fn take_ammo(&mut self) -> Result<Item, String> {
+        let index = self
+            .inventory
+            .iter()
+            .position(|item| matches!(item.item_type, ItemType::Ammo { .. }))
+            .ok_or_else(|| "You have no ammunition.".to_string())?;
+
+        let stack = &mut self.inventory[index];
+        let mut shot = stack.clone();
+        shot.item_type = ItemType::Ammo { quantity: 1 };
+
+        if let ItemType::Ammo { quantity } = &mut stack.item_type {
+            *quantity -= 1;
+            if *quantity <= 0 {
+                self.inventory.remove(index);
+            }
+        }
+        Ok(shot)
+    }
+
+    /// Applies a status effect to the player.
+    ///
+    /// If the same kind of effect is already active, the longer duration and
+    /// the higher potency are kept.
+    ///
+    /// # Arguments
+    /// * `effect` - The effect to apply
+    pub fn add_status(&mut self, effect: StatusEffect) {
+        merge_status(&mut self.status_effects, effect);
+    }
+
+    /// Checks whether a status effect is active.
+    ///
+    /// # Returns
+    /// `true` if an effect of the given kind is active
+    pub fn has_status(&self, kind: StatusKind) -> bool {
+        self.status_effects.iter().any(|effect| effect.kind == kind)
+    }
+
+    /// Applies all active status effects for one turn.
+    ///
+    /// # Returns
+    /// Messages describing what happened to the player
+    pub fn tick_status_effects(&mut self) -> Vec<String> {
+        let mut messages = Vec::new();
 
-        if let Some(weapon) = &self.equipped_weapon {
-            self.attack += weapon.value;
+        for effect in &mut self.status_effects {
+            match effect.kind {
+                StatusKind::Poisoned => {
+                    self.health -= effect.potency;
+                    messages.push("The poison burns in your veins.".to_string());
+                }
+            }
+            effect.turns -= 1;
+            if effect.turns <= 0 {
+                messages.push(format!(
+                    "You are no longer {}.",
+                    effect.kind.name().to_lowercase()
+                ));
+            }
         }
 
-        self.defense = 10 + (self.level - 1) * 2;
+        self.status_effects.retain(|effect| effect.turns > 0);
+        messages
+    }
 
-        if let Some(armor) = &self.equipped_armor {
-            self.defense += armor.value;
+    /// Finds a key for the given lock in the inventory.
+    ///
+    /// # Arguments
+    /// * `key_id` - Id of the lock to find a key for
+    ///
+    /// # Returns
+    /// * `Some(usize)` with the inventory index of a matching key
+    /// * `None` if the player carries no matching key
+    pub fn find_key(&self, key_id: u32) -> Option<usize> {
+        self.inventory.iter().position(
+            |item| matches!(item.item_type, ItemType::Key { key_id: id, .. } if id == key_id),
+        )
+    }
+
+    /// Uses a key from the inventory on a lock.
+    ///
+    /// Single-use keys are removed from the inventory, reusable keys are kept.
+    ///
+    /// # Arguments
+    /// * `key_id` - Id of the lock to unlock
+    ///
+    /// # Returns
+    /// * `Ok(Item)` with a copy of the key that was used
+    /// * `Err(String)` with error message if no matching key is carried
+    pub fn use_key(&mut self, key_id: u32) -> Result<Item, String> {
+        let index = self
+            .find_key(key_id)
+            .ok_or_else(|| "You have no key for this lock.".to_string())?;
+
+        let key = self.inventory[index].clone();
+        if let ItemType::Key {
+            consumed_on_use: true,
+            ..
+        } = key.item_type
+        {
+            self.inventory.remove(index);
+        }
+        Ok(key)
+    }
+
+    /// Updates player stats based on level, equipped items with their
+    /// affixes and hunger. Called automatically when any of them changes.
+    fn update_stats(&mut self) {
+        let bonus = self.hunger().penalty().add(self.equipment.bonus());
+        let base = self.base_stats().add(bonus);
+        let growth = self.class.growth();
+
+        self.max_health = base.health + (self.level - 1) * growth.health;
+        self.health = self.health.min(self.max_health);
+        self.speed = base.speed + (self.level - 1) * growth.speed;
+        self.attack = base.attack + (self.level - 1) * growth.attack;
+        self.defense = base.defense + (self.level - 1) * growth.defense;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_equipped_light_burns_out() {
+        let mut player = Player::new("Ann".to_string());
+        let mut torch = Item::create_torch();
+        torch.item_type = ItemType::Light { fuel: LOW_FUEL + 2 };
+        player.inventory.push(torch);
+        player.inventory.push(Item::create_torch());
+
+        player.add_item(0).unwrap();
+        assert_eq!(player.light_radius(), Some(5));
+        assert_eq!(player.inventory.len(), 1);
+
+        assert_eq!(player.burn_light(), None);
+        assert_eq!(
+            player.burn_light(),
+            Some("Your torch flickers.".to_string())
+        );
+        for _ in 1..LOW_FUEL {
+            assert_eq!(player.burn_light(), None);
         }
+        assert_eq!(
+            player.burn_light(),
+            Some("Your torch burns out.".to_string())
+        );
+        assert!(player.equipped_light.is_none());
+        assert_eq!(player.light_radius(), None);
+        assert_eq!(player.burn_light(), None);
+
+        player.add_item(0).unwrap();
+        assert_eq!(player.light_radius(), Some(5));
+        assert!(player.inventory.is_empty());
     }
 }
@@ -0,0 +1,375 @@
+//! Replay module recording sessions and reading them back.
+//!
+//! A replay file stores how a game started and every command applied to it,
+//! one tab-separated record per line. Since the game only draws randomness
+//! from its seeded generator, applying the same commands to the same start
+//! plays out the same game.
+
+use std::{
+    fs::{self, File},
+    io::Write,
+};
+
+use crate::{
+    character::{Background, Class},
+    command::{Command, Direction},
+    rng::Rng,
+    save::{read_game, write_game},
+    Game, Player,
+};
+
+/// Default file the current session is recorded to.
+pub const REPLAY_FILE: &str = "last.replay";
+/// First line of every replay file.
+const REPLAY_HEADER: &str = "rusty-crawler-replay\t1";
+
+/// Represents how a recorded game started.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Start {
+    /// A new game generated from a seed
+    New {
+        /// Seed of the game's random number generator
+        seed: u64,
+        /// Name of the player character
+        name: String,
+        /// Class of the player character
+        class: Class,
+        /// Background of the player character
+        background: Background,
+    },
+    /// A loaded game, stored in the save format
+    Saved(String),
+}
+
+impl Start {
+    /// Creates the game in its starting state.
+    ///
+    /// # Returns
+    /// * `Ok(Game)` ready for the first recorded command
+    /// * `Err(String)` if the stored save is broken
+    pub fn game(&self) -> Result<Game, String> {
+        match self {
+            Start::New {
+                seed,
+                name,
+                class,
+                background,
+            } => {
+                let player = Player::create(name.clone(), *class, *background);
+                Ok(Game::generate(player, Rng::new(*seed)))
+            }
+            Start::Saved(content) => read_game(content),
+        }
+    }
+}
+
+/// Represents a recorded session.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Replay {
+    /// How the game started
+    pub start: Start,
+    /// Every applied command with the number of turns played before it
+    pub commands: Vec<(u32, Command)>,
+}
+
+impl Replay {
+    /// Loads a replay file.
+    ///
+    /// # Arguments
+    /// * `path` - Path of the replay file
+    ///
+    /// # Returns
+    /// * `Ok(Replay)` with the recorded session
+    /// * `Err(String)` if the file cannot be read or is broken
+    pub fn load(path: &str) -> Result<Self, String> {
+        let content =
+            fs::read_to_string(path).map_err(|e| format!("Cannot read {}: {}", path, e))?;
+        Replay::parse(&content).map_err(|e| format!("{}: {}", path, e))
+    }
+
+    /// Parses the content of a replay file.
+    ///
+    /// A last line cut off by a crash is ignored.
+    ///
+    /// # Returns
+    /// * `Ok(Replay)` with the recorded session
+    /// * `Err(String)` naming the first broken line
+    pub fn parse(content: &str) -> Result<Self, String> {
+        let complete = match content.rfind('\n') {
+            Some(end) => &content[..=end],
+            None => content,
+        };
+        let mut lines = complete.lines().enumerate();
+        if lines.next().map(|(_, line)| line) != Some(REPLAY_HEADER) {
+            return Err("not a replay file of this version".to_string());
+        }
+
+        let mut start = None;
+        let mut saved = String::new();
+        let mut commands = Vec::new();
+        for (number, line) in lines {
+            let error = |message: String| format!("line {}: {}", number + 1, message);
+            let (tag, rest) = line.split_once('\t').unwrap_or((line, ""));
+            match tag {
+                "new" => start = Some(parse_new(rest).map_err(error)?),
+                "saved" => {
+                    saved.push_str(rest);
+                    saved.push('\n');
+                }
+                "command" => {
+                    let (turn, text) = rest.split_once('\t').unwrap_or((rest, ""));
+                    let turn = turn
+                        .parse()
+                        .map_err(|_| error(format!("invalid turn '{}'", turn)))?;
+                    commands.push((turn, parse_command(text).map_err(error)?));
+                }
+                _ => return Err(error(format!("unknown record '{}'", tag))),
+            }
+        }
+
+        let start = match start {
+            Some(start) => start,
+            None if !saved.is_empty() => Start::Saved(saved),
+            None => return Err("the replay does not say how the game started".to_string()),
+        };
+        Ok(Replay { start, commands })
+    }
+}
+
+/// Parses the fields of a `new` record.
+fn parse_new(fields: &str) -> Result<Start, String> {
+    let fields: Vec<&str> = fields.split('\t').collect();
+    let [seed, name, class, background] = fields[..] else {
+        return Err("expected seed, name, class and background".to_string());
+    };
+    Ok(Start::New {
+        seed: seed
+            .parse()
+            .map_err(|_| format!("invalid seed '{}'", seed))?,
+        name: name.to_string(),
+        class: Class::ALL
+            .into_iter()
+            .find(|c| c.name() == class)
+            .ok_or_else(|| format!("unknown class '{}'", class))?,
+        background: Background::ALL
+            .into_iter()
+            .find(|b| b.name() == background)
+            .ok_or_else(|| format!("unknown background '{}'", background))?,
+    })
+}
+
+/// Writes a session to a replay file while it is played.
+///
+/// Every command is written at once, so the file is complete even if the
+/// game crashes.
+#[derive(Debug)]
+pub struct Recorder {
+    /// The open replay file
+    file: File,
+}
+
+impl Recorder {
+    /// Creates a replay file and records how the game started.
+    ///
+    /// # Arguments
+    /// * `path` - Path of the replay file, an existing file is replaced
+    /// * `start` - How the game started
+    ///
+    /// # Returns
+    /// * `Ok(Recorder)` ready to record commands
+    /// * `Err(String)` if the file cannot be written
+    pub fn create(path: &str, start: &Start) -> Result<Self, String> {
+        let mut content = format!("{}\n", REPLAY_HEADER);
+        match start {
+            Start::New {
+                seed,
+                name,
+                class,
+                background,
+            } => content.push_str(&format!(
+                "new\t{}\t{}\t{}\t{}\n",
+                seed,
+                name.replace('\t', " "),
+                class.name(),
+                background.name()
+            )),
+            Start::Saved(save) => {
+                for line in save.lines() {
+                    content.push_str(&format!("saved\t{}\n", line));
+                }
+            }
+        }
+
+        let mut file = File::create(path).map_err(|e| format!("Cannot write {}: {}", path, e))?;
+        file.write_all(content.as_bytes())
+            .map_err(|e| format!("Cannot write {}: {}", path, e))?;
+        Ok(Recorder { file })
+    }
+
+    /// Creates a replay file for a game loaded from a save.
+    ///
+    /// # Arguments
+    /// * `path` - Path of the replay file, an existing file is replaced
+    /// * `game` - The game in the state recording starts from
+    ///
+    /// # Returns
+    /// * `Ok(Recorder)` ready to record commands
+    /// * `Err(String)` if the file cannot be written
+    pub fn for_game(path: &str, game: &Game) -> Result<Self, String> {
+        Recorder::create(path, &Start::Saved(write_game(game)))
+    }
+
+    /// Records a command before it is applied.
+    ///
+    /// # Arguments
+    /// * `turns` - Number of turns played before the command
+    /// * `command` - The command with its arguments
+    ///
+    /// # Returns
+    /// * `Ok(())` if successful
+    /// * `Err(String)` if the file cannot be written
+    pub fn record(&mut self, turns: u32, command: Command) -> Result<(), String> {
+        writeln!(self.file, "command\t{}\t{}", turns, command_text(command))
+            .map_err(|e| format!("Cannot record command: {}", e))
+    }
+}
+
+/// Gets the text a command is recorded as.
+///
+/// The command name is followed by its arguments, e.g. `open north`,
+/// `equip 2` or `cast 0 12,7`, with 0-based indices.
+pub fn command_text(command: Command) -> String {
+    let mut words = vec![command.name()];
+    let index = |index: Option<usize>| index.map(|i| i.to_string());
+    let target = |target: Option<(i32, i32)>| target.map(|(x, y)| format!("{},{}", x, y));
+
+    let arguments = match command {
+        Command::Open(direction) | Command::Close(direction) | Command::Unlock(direction) => {
+            vec![direction.map(|d| d.name().to_string())]
+        }
+        Command::Equip(slot) => vec![index(slot)],
+        Command::Use(slot, aim) | Command::Throw(slot, aim) | Command::Cast(slot, aim) => {
+            vec![index(slot), target(aim)]
+        }
+        Command::Fire(aim) => vec![target(aim)],
+        _ => Vec::new(),
+    };
+    words.extend(
+        arguments
+            .into_iter()
+            .map(|a| a.unwrap_or_else(|| "-".to_string())),
+    );
+    while words.len() > 1 && words.last().map(String::as_str) == Some("-") {
+        words.pop();
+    }
+    words.join(" ")
+}
+
+/// Parses a command from its recorded text.
+///
+/// # Returns
+/// * `Ok(Command)` with its arguments
+/// * `Err(String)` if the command or one of its arguments is invalid
+pub fn parse_command(text: &str) -> Result<Command, String> {
+    let mut words = text.split_whitespace();
+    let name = words.next().unwrap_or_default();
+    let command = Command::from_name(name).ok_or_else(|| format!("unknown command '{}'", name))?;
+    let mut next = || words.next().filter(|&word| word != "-");
+
+    let direction = |word: Option<&str>| -> Result<Option<Direction>, String> {
+        word.map(|word| {
+            Direction::ALL
+                .into_iter()
+                .find(|d| d.name() == word)
+                .ok_or_else(|| format!("unknown direction '{}'", word))
+        })
+        .transpose()
+    };
+    let index = |word: Option<&str>| -> Result<Option<usize>, String> {
+        word.map(|word| {
+            word.parse()
+                .map_err(|_| format!("invalid index '{}'", word))
+        })
+        .transpose()
+    };
+    let target = |word: Option<&str>| -> Result<Option<(i32, i32)>, String> {
+        word.map(|word| {
+            word.split_once(',')
+                .and_then(|(x, y)| Some((x.parse().ok()?, y.parse().ok()?)))
+                .ok_or_else(|| format!("invalid target '{}'", word))
+        })
+        .transpose()
+    };
+
+    Ok(match command {
+        Command::Open(_) => Command::Open(direction(next())?),
+        Command::Close(_) => Command::Close(direction(next())?),
+        Command::Unlock(_) => Command::Unlock(direction(next())?),
+        Command::Equip(_) => Command::Equip(index(next())?),
+        Command::Use(..) => Command::Use(index(next())?, target(next())?),
+        Command::Throw(..) => Command::Throw(index(next())?, target(next())?),
+        Command::Cast(..) => Command::Cast(index(next())?, target(next())?),
+        Command::Fire(_) => Command::Fire(target(next())?),
+        command => command,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_text_round_trip() {
+        let commands = [
+            Command::Move(Direction::SouthWest),
+            Command::Open(Some(Direction::North)),
+            Command::Equip(Some(2)),
+            Command::Use(Some(0), None),
+            Command::Cast(None, Some((3, 4))),
+            Command::Throw(Some(1), Some((-1, 12))),
+            Command::Fire(None),
+            Command::Wait,
+        ];
+        for command in commands {
+            let text = command_text(command);
+            assert_eq!(parse_command(&text), Ok(command), "{}", text);
+        }
+        assert_eq!(
+            command_text(Command::Cast(None, Some((3, 4)))),
+            "cast - 3,4"
+        );
+        assert!(parse_command("open up").is_err());
+    }
+
+    #[test]
+    fn test_replay_replays_the_same_game() {
+        let start = Start::New {
+            seed: 99,
+            name: "Ann".to_string(),
+            class: Class::Rogue,
+            background: Background::Noble,
+        };
+        let path = std::env::temp_dir().join("rusty-crawler-test.replay");
+        let path = path.to_str().unwrap();
+
+        let mut game = start.game().unwrap();
+        let mut recorder = Recorder::create(path, &start).unwrap();
+        for direction in [Direction::East, Direction::South, Direction::West] {
+            for _ in 0..4 {
+                let command = Command::Move(direction);
+                recorder.record(game.turns, command).unwrap();
+                game.apply(command);
+            }
+        }
+
+        let replay = Replay::load(path).unwrap();
+        assert_eq!(replay.start, start);
+        let mut replayed = replay.start.game().unwrap();
+        for (turns, command) in replay.commands {
+            assert_eq!(replayed.turns, turns);
+            replayed.apply(command);
+        }
+        assert_eq!(replayed.observe(), game.observe());
+        let _ = fs::remove_file(path);
+    }
+}
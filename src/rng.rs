@@ -0,0 +1,81 @@
+//! Random number module providing a small seedable generator.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Seedable pseudo random number generator based on SplitMix64.
+///
+/// The same seed always produces the same sequence, which keeps loot rolls
+/// and level generation reproducible.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    /// Creates a new generator from a seed.
+    ///
+    /// # Arguments
+    /// * `seed` - The seed to start the sequence from
+    ///
+    /// # Returns
+    /// A new Rng instance
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    /// Creates a new generator seeded from the system clock.
+    ///
+    /// # Returns
+    /// A new Rng instance with a time based seed
+    pub fn from_time() -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or_default();
+        Rng::new(nanos)
+    }
+
+    /// Gets the internal state of the generator.
+    ///
+    /// # Returns
+    /// A seed that continues the sequence exactly where it is now
+    pub fn state(&self) -> u64 {
+        self.state
+    }
+
+    /// Generates the next raw 64-bit value.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Generates a number in the range `min..max`.
+    ///
+    /// # Arguments
+    /// * `min` - Inclusive lower bound
+    /// * `max` - Exclusive upper bound
+    ///
+    /// # Returns
+    /// A random number, or `min` if the range is empty
+    pub fn range(&mut self, min: i32, max: i32) -> i32 {
+        if max <= min {
+            return min;
+        }
+        let span = (max as i64 - min as i64) as u64;
+        (min as i64 + (self.next_u64() % span) as i64) as i32
+    }
+
+    /// Rolls a percentage chance.
+    ///
+    /// # Arguments
+    /// * `percent` - Chance of success from 0 to 100
+    ///
+    /// # Returns
+    /// `true` with the given probability
+    pub fn chance(&mut self, percent: i32) -> bool {
+        self.range(0, 100) < percent
+    }
+}
@@ -1,8 +1,12 @@
 //! Room module for dungeon room generation and management.
 
 /// Represents a room in the dungeon.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Room {
+    /// X-coordinate of the top left corner, including walls
+    pub x: i32,
+    /// Y-coordinate of the top left corner, including walls
+    pub y: i32,
     /// Width of the room in tiles
     pub width: i32,
     /// Height of the room in tiles
@@ -17,9 +21,33 @@ impl Room {
     /// * `height` - The height of the room in tiles
     ///
     /// # Returns
-    /// A new Room instance with the specified dimensions
+    /// A new Room instance with the specified dimensions at the map origin
     pub fn new(width: i32, height: i32) -> Self {
-        Room { width, height }
+        Room {
+            x: 0,
+            y: 0,
+            width,
+            height,
+        }
+    }
+
+    /// Creates a new room at a specific position.
+    ///
+    /// # Arguments
+    /// * `x` - The x-coordinate of the top left corner
+    /// * `y` - The y-coordinate of the top left corner
+    /// * `width` - The width of the room in tiles
+    /// * `height` - The height of the room in tiles
+    ///
+    /// # Returns
+    /// A new Room instance with the specified position and dimensions
+    pub fn at(x: i32, y: i32, width: i32, height: i32) -> Self {
+        Room {
+            x,
+            y,
+            width,
+            height,
+        }
     }
 
     /// Calculates the center coordinates of the room.
@@ -27,6 +55,29 @@ impl Room {
     /// # Returns
     /// A tuple (x, y) representing the center coordinates
     pub fn center(&self) -> (i32, i32) {
-        (self.width / 2, self.height / 2)
+        (self.x + self.width / 2, self.y + self.height / 2)
+    }
+
+    /// Checks whether two rooms overlap, keeping a gap between them.
+    ///
+    /// # Arguments
+    /// * `other` - The room to check against
+    /// * `gap` - Number of tiles that must stay free between the rooms
+    ///
+    /// # Returns
+    /// `true` if the rooms are closer than `gap` tiles
+    pub fn intersects(&self, other: &Room, gap: i32) -> bool {
+        self.x - gap < other.x + other.width
+            && self.x + self.width + gap > other.x
+            && self.y - gap < other.y + other.height
+            && self.y + self.height + gap > other.y
+    }
+
+    /// Checks whether a point lies on the floor inside the room walls.
+    ///
+    /// # Returns
+    /// `true` if the point is inside the room, excluding its walls
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        x > self.x && x < self.x + self.width - 1 && y > self.y && y < self.y + self.height - 1
     }
 }
@@ -0,0 +1,720 @@
+//! Save module storing and restoring games in numbered save slots.
+//!
+//! Saves are plain text files with one tab-separated record per line. The
+//! first field of every record names what the record holds.
+
+use std::{fmt::Debug, fmt::Display, fs, str::FromStr};
+
+use crate::{
+    affix::{Affix, AffixKind, Rarity},
+    character::{Background, Class},
+    equipment::Slot,
+    identify::{Appearance, MagicKind},
+    item::{MapItem, PotionKind, ScrollKind},
+    light::LightLevel,
+    map::{DoorState, Tile},
+    rng::Rng,
+    room::Room,
+    spell::{KnownSpell, SpellKind},
+    status::{StatusEffect, StatusKind},
+    trap::{Trap, TrapKind},
+    Chest, Game, GameState, Item, ItemType, Map, Monster, Player,
+};
+
+/// Directory the save slots are stored in.
+pub const SAVE_DIR: &str = "saves";
+/// Number of save slots offered to the player.
+pub const SAVE_SLOTS: u32 = 3;
+/// Slot the game is saved to automatically when auto-save is turned on.
+pub const AUTOSAVE_SLOT: u32 = 0;
+/// First field of the first line of every save file.
+const SAVE_HEADER: &str = "rusty-crawler-save";
+/// Version of the save format, increased on incompatible changes.
+const SAVE_VERSION: u32 = 6;
+
+/// Gets the path of a save slot.
+///
+/// # Arguments
+/// * `slot` - Number of the slot, starting at 1, or [`AUTOSAVE_SLOT`]
+///
+/// # Returns
+/// The path of the slot's save file
+pub fn slot_path(slot: u32) -> String {
+    format!("{}/slot{}.sav", SAVE_DIR, slot)
+}
+
+/// Describes the game stored in a save slot.
+///
+/// # Arguments
+/// * `slot` - Number of the slot, starting at 1
+///
+/// # Returns
+/// A short summary of the saved character, or why the slot cannot be used
+pub fn describe_slot(slot: u32) -> String {
+    match load_game(slot) {
+        Ok(game) => format!(
+            "{} the {}, level {}, depth {}",
+            game.player.name,
+            game.player.class.name(),
+            game.player.level,
+            game.depth
+        ),
+        Err(_) if fs::metadata(slot_path(slot)).is_err() => "Empty".to_string(),
+        Err(_) => "Unreadable save".to_string(),
+    }
+}
+
+/// Saves a game into a save slot.
+///
+/// # Arguments
+/// * `game` - The game to save
+/// * `slot` - Number of the slot, starting at 1
+///
+/// # Returns
+/// * `Ok(())` if successful
+/// * `Err(String)` if the file cannot be written
+pub fn save_game(game: &Game, slot: u32) -> Result<(), String> {
+    fs::create_dir_all(SAVE_DIR).map_err(|e| format!("Cannot create {}: {}", SAVE_DIR, e))?;
+    let path = slot_path(slot);
+    fs::write(&path, write_game(game)).map_err(|e| format!("Cannot write {}: {}", path, e))
+}
+
+/// Loads a game from a save slot.
+///
+/// # Arguments
+/// * `slot` - Number of the slot, starting at 1
+///
+/// # Returns
+/// * `Ok(Game)` with the restored game
+/// * `Err(String)` if the slot is empty or the save is broken
+pub fn load_game(slot: u32) -> Result<Game, String> {
+    let path = slot_path(slot);
+    let content = fs::read_to_string(&path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => format!("Slot {} is empty.", slot),
+        _ => format!("Cannot read {}: {}", path, e),
+    })?;
+    read_game(&content).map_err(|e| format!("{}: {}", path, e))
+}
+
+/// Serialises a game into the save format.
+///
+/// # Returns
+/// The content of a save file
+pub fn write_game(game: &Game) -> String {
+    let mut out = String::new();
+    record(&mut out, SAVE_HEADER, &[&SAVE_VERSION]);
+    record(
+        &mut out,
+        "game",
+        &[&game.depth, &game.turns, &game.kills, &game.rng.state()],
+    );
+    for appearance in &game.identification.appearances {
+        record(
+            &mut out,
+            "appearance",
+            &[
+                &item_type_name(&appearance.kind.item_type()),
+                &appearance.name,
+                &appearance.known,
+            ],
+        );
+    }
+
+    let player = &game.player;
+    record(
+        &mut out,
+        "player",
+        &[
+            &player.name,
+            &enum_name(&player.class),
+            &enum_name(&player.background),
+            &player.x,
+            &player.y,
+            &player.health,
+            &player.max_health,
+            &player.mana,
+            &player.max_mana,
+            &player.attack,
+            &player.defense,
+            &player.speed,
+            &player.level,
+            &player.experience,
+            &player.experience_to_next_level,
+            &player.food,
+        ],
+    );
+    for (slot, item) in player.equipment.iter() {
+        item_record(&mut out, "equipped", &[&enum_name(&slot)], item);
+    }
+    if let Some(light) = &player.equipped_light {
+        item_record(&mut out, "light", &[], light);
+    }
+    for item in &player.inventory {
+        item_record(&mut out, "inventory", &[], item);
+    }
+    for known in &player.spellbook.spells {
+        record(
+            &mut out,
+            "spell",
+            &[&enum_name(&known.spell.kind), &known.cooldown_left],
+        );
+    }
+    for effect in &player.status_effects {
+        status_record(&mut out, "status", effect);
+    }
+
+    let map = &game.map;
+    record(
+        &mut out,
+        "map",
+        &[&map.width, &map.height, &enum_name(&map.ambient_light)],
+    );
+    for room in &map.rooms {
+        record(
+            &mut out,
+            "room",
+            &[&room.x, &room.y, &room.width, &room.height],
+        );
+    }
+    for y in 0..map.height {
+        let row: String = (0..map.width).map(|x| tile_glyph(map, x, y)).collect();
+        let explored: String = (0..map.width)
+            .map(|x| if map.is_explored(x, y) { '1' } else { '0' })
+            .collect();
+        record(&mut out, "row", &[&row, &explored]);
+    }
+    for y in 0..map.height {
+        for x in 0..map.width {
+            if let Some(Tile::Door(DoorState::Locked(key_id))) = map.get_tile(x, y) {
+                record(&mut out, "lock", &[&x, &y, key_id]);
+            }
+        }
+    }
+
+    for monster in &game.monsters {
+        record(
+            &mut out,
+            "monster",
+            &[
+                &monster.name,
+                &monster.glyph,
+                &monster.x,
+                &monster.y,
+                &monster.health,
+                &monster.max_health,
+                &monster.attack,
+                &monster.defense,
+                &monster.experience,
+                &monster.opens_doors,
+                &monster.loot_table.clone().unwrap_or_default(),
+                &monster.alerted,
+                &monster.glow,
+                &monster.stealthy,
+            ],
+        );
+        for effect in &monster.status_effects {
+            status_record(&mut out, "monster_status", effect);
+        }
+    }
+    for chest in &game.chests {
+        let lock = chest.lock.map(|id| id.to_string()).unwrap_or_default();
+        record(
+            &mut out,
+            "chest",
+            &[&chest.x, &chest.y, &lock, &chest.loot_table, &chest.opened],
+        );
+    }
+    for map_item in &game.items {
+        item_record(
+            &mut out,
+            "item",
+            &[&map_item.x, &map_item.y],
+            &map_item.item,
+        );
+    }
+    for trap in &game.traps {
+        record(
+            &mut out,
+            "trap",
+            &[&trap.x, &trap.y, &enum_name(&trap.kind), &trap.hidden],
+        );
+    }
+    for message in &game.messages {
+        record(&mut out, "message", &[message]);
+    }
+    out
+}
+
+/// Restores a game from the save format.
+///
+/// # Arguments
+/// * `content` - The content of a save file
+///
+/// # Returns
+/// * `Ok(Game)` with the restored game
+/// * `Err(String)` naming the first broken line
+pub fn read_game(content: &str) -> Result<Game, String> {
+    let mut lines = content.lines().enumerate();
+    match lines.next() {
+        Some((_, line)) if line == format!("{}\t{}", SAVE_HEADER, SAVE_VERSION) => {}
+        _ => return Err("not a save file of this version".to_string()),
+    }
+
+    let mut game = Game::new(String::new(), Map::new(0, 0));
+    let mut row = 0;
+    for (number, line) in lines {
+        let record = Record::parse(line);
+        read_record(&mut game, &record, &mut row)
+            .map_err(|e| format!("line {}: {}", number + 1, e))?;
+    }
+
+    if row != game.map.height {
+        return Err(format!(
+            "expected {} map rows, found {}",
+            game.map.height, row
+        ));
+    }
+    game.state = GameState::Running;
+    game.refresh_fov();
+    Ok(game)
+}
+
+/// Applies a single record of a save file to the game being restored.
+///
+/// # Arguments
+/// * `game` - The game being restored
+/// * `record` - The record to apply
+/// * `row` - Number of map rows read so far
+fn read_record(game: &mut Game, record: &Record, row: &mut i32) -> Result<(), String> {
+    match record.tag {
+        "game" => {
+            game.depth = record.number(0)?;
+            game.turns = record.number(1)?;
+            game.kills = record.number(2)?;
+            game.rng = Rng::new(record.number(3)?);
+        }
+        "appearance" => {
+            let kind = match parse_item_type(record.text(0)?)? {
+                ItemType::Potion(kind) => MagicKind::Potion(kind),
+                ItemType::Scroll(kind) => MagicKind::Scroll(kind),
+                _ => return Err("appearance of an item that is always known".to_string()),
+            };
+            game.identification.appearances.push(Appearance {
+                kind,
+                name: record.text(1)?.to_string(),
+                known: record.number(2)?,
+            });
+        }
+        "player" => {
+            let mut player = Player::new(record.text(0)?.to_string());
+            player.class = parse_enum(&Class::ALL, record.text(1)?)?;
+            player.background = parse_enum(&Background::ALL, record.text(2)?)?;
+            player.x = record.number(3)?;
+            player.y = record.number(4)?;
+            player.health = record.number(5)?;
+            player.max_health = record.number(6)?;
+            player.mana = record.number(7)?;
+            player.max_mana = record.number(8)?;
+            player.attack = record.number(9)?;
+            player.defense = record.number(10)?;
+            player.speed = record.number(11)?;
+            player.level = record.number(12)?;
+            player.experience = record.number(13)?;
+            player.experience_to_next_level = record.number(14)?;
+            player.food = record.number(15)?;
+            player.spellbook.spells.clear();
+            game.player = player;
+        }
+        "equipped" => {
+            let slot = parse_enum(&Slot::ALL, record.text(0)?)?;
+            game.player.equipment.set(slot, Some(record.item(1)?));
+        }
+        "light" => game.player.equipped_light = Some(record.item(0)?),
+        "inventory" => game.player.inventory.push(record.item(0)?),
+        "spell" => {
+            let kind: SpellKind = parse_enum(&SpellKind::ALL, record.text(0)?)?;
+            game.player.spellbook.spells.push(KnownSpell {
+                spell: kind.spell(),
+                cooldown_left: record.number(1)?,
+            });
+        }
+        "status" => game.player.status_effects.push(record.status()?),
+        "map" => {
+            game.map = Map::new(record.number(0)?, record.number(1)?);
+            game.map.ambient_light = parse_enum(&LightLevel::ALL, record.text(2)?)?;
+        }
+        "room" => game.map.rooms.push(Room::at(
+            record.number(0)?,
+            record.number(1)?,
+            record.number(2)?,
+            record.number(3)?,
+        )),
+        "row" => {
+            if *row >= game.map.height {
+                return Err("more map rows than the map is high".to_string());
+            }
+            let tiles = record.text(0)?;
+            let explored = record.text(1)?;
+            if tiles.chars().count() != game.map.width as usize
+                || explored.chars().count() != game.map.width as usize
+            {
+                return Err("map row does not match the map width".to_string());
+            }
+            for (x, (glyph, seen)) in tiles.chars().zip(explored.chars()).enumerate() {
+                game.map.set_tile(x as i32, *row, parse_tile(glyph)?);
+                if seen == '1' {
+                    game.map.reveal(x as i32, *row);
+                }
+            }
+            *row += 1;
+        }
+        "lock" => {
+            let (x, y) = (record.number(0)?, record.number(1)?);
+            let key_id = record.number(2)?;
+            game.map
+                .set_tile(x, y, Tile::Door(DoorState::Locked(key_id)));
+        }
+        "monster" => {
+            let glyph = record.text(1)?.chars().next().ok_or("missing glyph")?;
+            let mut monster = Monster::new(
+                record.text(0)?.to_string(),
+                glyph,
+                record.number(5)?,
+                record.number(6)?,
+                record.number(7)?,
+                record.number(8)?,
+            );
+            monster.x = record.number(2)?;
+            monster.y = record.number(3)?;
+            monster.health = record.number(4)?;
+            monster.opens_doors = record.number(9)?;
+            monster.loot_table = Some(record.text(10)?)
+                .filter(|table| !table.is_empty())
+                .map(str::to_string);
+            monster.alerted = record.number(11)?;
+            monster.glow = record.number(12)?;
+            monster.stealthy = record.number(13)?;
+            game.monsters.push(monster);
+        }
+        "monster_status" => {
+            let effect = record.status()?;
+            game.monsters
+                .last_mut()
+                .ok_or("status without a monster")?
+                .status_effects
+                .push(effect);
+        }
+        "chest" => {
+            let mut chest = Chest::new(
+                record.number(0)?,
+                record.number(1)?,
+                record.text(3)?.to_string(),
+            );
+            let lock = record.text(2)?;
+            if !lock.is_empty() {
+                chest.lock = Some(record.number(2)?);
+            }
+            chest.opened = record.number(4)?;
+            game.chests.push(chest);
+        }
+        "item" => game.items.push(MapItem {
+            x: record.number(0)?,
+            y: record.number(1)?,
+            item: record.item(2)?,
+        }),
+        "trap" => {
+            let mut trap = Trap::new(
+                record.number(0)?,
+                record.number(1)?,
+                parse_enum(&TrapKind::ALL, record.text(2)?)?,
+            );
+            trap.hidden = record.number(3)?;
+            game.traps.push(trap);
+        }
+        "message" => game.messages.push(record.text(0)?.to_string()),
+        tag => return Err(format!("unknown record '{}'", tag)),
+    }
+    Ok(())
+}
+
+/// Appends a record to the save content.
+///
+/// Tabs and line breaks inside fields are replaced by spaces.
+fn record(out: &mut String, tag: &str, fields: &[&dyn Display]) {
+    out.push_str(tag);
+    for field in fields {
+        out.push('\t');
+        out.push_str(&field.to_string().replace(['\t', '\n'], " "));
+    }
+    out.push('\n');
+}
+
+/// Appends a record holding an item after some leading fields.
+fn item_record(out: &mut String, tag: &str, fields: &[&dyn Display], item: &Item) {
+    let item_type = item_type_name(&item.item_type);
+
+    let affixes: Vec<String> = item
+        .affixes
+        .iter()
+        .map(|affix| format!("{}:{}", enum_name(&affix.kind), affix.power))
+        .collect();
+    let (rarity, affixes) = (enum_name(&item.rarity), affixes.join(","));
+
+    let mut all = fields.to_vec();
+    all.extend_from_slice(&[
+        &item.name,
+        &item_type,
+        &item.value,
+        &item.description,
+        &rarity,
+        &affixes,
+    ]);
+    record(out, tag, &all);
+}
+
+/// Gets the name an item type is stored under, e.g. `Ammo:10`.
+fn item_type_name(item_type: &ItemType) -> String {
+    match *item_type {
+        ItemType::Weapon { two_handed } => format!("Weapon:{}", two_handed),
+        ItemType::RangedWeapon { range } => format!("RangedWeapon:{}", range),
+        ItemType::Ammo { quantity } => format!("Ammo:{}", quantity),
+        ItemType::Armor(slot) => format!("Armor:{:?}", slot),
+        ItemType::Potion(kind) => format!("Potion:{:?}", kind),
+        ItemType::Scroll(ScrollKind::Cast(spell)) => format!("Scroll:Cast:{:?}", spell),
+        ItemType::Scroll(ScrollKind::Teach(spell)) => format!("Scroll:Teach:{:?}", spell),
+        ItemType::Scroll(ScrollKind::Identify) => "Scroll:Identify".to_string(),
+        ItemType::Key {
+            key_id,
+            consumed_on_use,
+        } => format!("Key:{}:{}", key_id, consumed_on_use),
+        ItemType::Food => "Food".to_string(),
+        ItemType::Light { fuel } => format!("Light:{}", fuel),
+        ItemType::Jewelry(slot) => format!("Jewelry:{:?}", slot),
+    }
+}
+
+/// Parses an item type stored by [`item_type_name`].
+fn parse_item_type(item_type: &str) -> Result<ItemType, String> {
+    let parts: Vec<&str> = item_type.split(':').collect();
+    let part = |index: usize| {
+        parts
+            .get(index)
+            .copied()
+            .ok_or_else(|| format!("invalid item type '{}'", item_type))
+    };
+    let number = |index: usize| {
+        part(index)?
+            .parse::<i32>()
+            .map_err(|_| format!("invalid item type '{}'", item_type))
+    };
+
+    Ok(match parts[0] {
+        "Weapon" => ItemType::Weapon {
+            two_handed: part(1)? == "true",
+        },
+        "RangedWeapon" => ItemType::RangedWeapon { range: number(1)? },
+        "Ammo" => ItemType::Ammo {
+            quantity: number(1)?,
+        },
+        "Armor" => ItemType::Armor(parse_enum(&Slot::ALL, part(1)?)?),
+        "Potion" => ItemType::Potion(parse_enum(&PotionKind::ALL, part(1)?)?),
+        "Scroll" => match part(1)? {
+            "Cast" => ItemType::Scroll(ScrollKind::Cast(parse_enum(&SpellKind::ALL, part(2)?)?)),
+            "Teach" => ItemType::Scroll(ScrollKind::Teach(parse_enum(&SpellKind::ALL, part(2)?)?)),
+            "Identify" => ItemType::Scroll(ScrollKind::Identify),
+            _ => return Err(format!("invalid item type '{}'", item_type)),
+        },
+        "Key" => ItemType::Key {
+            key_id: number(1)? as u32,
+            consumed_on_use: part(2)? == "true",
+        },
+        "Food" => ItemType::Food,
+        "Light" => ItemType::Light { fuel: number(1)? },
+        "Jewelry" => ItemType::Jewelry(parse_enum(&Slot::ALL, part(1)?)?),
+        _ => return Err(format!("invalid item type '{}'", item_type)),
+    })
+}
+
+/// Appends a record holding a status effect.
+fn status_record(out: &mut String, tag: &str, effect: &StatusEffect) {
+    record(
+        out,
+        tag,
+        &[&enum_name(&effect.kind), &effect.turns, &effect.potency],
+    );
+}
+
+/// Gets the name an enum variant is stored under.
+fn enum_name<T: Debug>(value: &T) -> String {
+    format!("{:?}", value)
+}
+
+/// Finds the enum variant stored under a name.
+///
+/// # Arguments
+/// * `all` - All variants of the enum
+/// * `name` - The stored name
+fn parse_enum<T: Debug + Copy>(all: &[T], name: &str) -> Result<T, String> {
+    all.iter()
+        .find(|value| enum_name(*value) == name)
+        .copied()
+        .ok_or_else(|| format!("unknown value '{}'", name))
+}
+
+/// Gets the save glyph of a tile. Locked doors are stored as `L` with
+/// their key id in a separate `lock` record.
+fn tile_glyph(map: &Map, x: i32, y: i32) -> char {
+    match map.get_tile(x, y).unwrap_or(&Tile::Empty) {
+        Tile::Floor => '.',
+        Tile::Wall => '#',
+        Tile::Door(DoorState::Open) => '\'',
+        Tile::Door(DoorState::Closed) => '+',
+        Tile::Door(DoorState::Locked(_)) => 'L',
+        Tile::Empty => ' ',
+        Tile::StairsDown => '>',
+        Tile::Brazier => '*',
+    }
+}
+
+/// Parses a save glyph back into a tile.
+fn parse_tile(glyph: char) -> Result<Tile, String> {
+    match glyph {
+        '.' => Ok(Tile::Floor),
+        '#' => Ok(Tile::Wall),
+        '\'' => Ok(Tile::Door(DoorState::Open)),
+        '+' => Ok(Tile::Door(DoorState::Closed)),
+        'L' => Ok(Tile::Door(DoorState::Locked(0))),
+        ' ' => Ok(Tile::Empty),
+        '>' => Ok(Tile::StairsDown),
+        '*' => Ok(Tile::Brazier),
+        _ => Err(format!("unknown tile '{}'", glyph)),
+    }
+}
+
+/// Represents one line of a save file split into its fields.
+struct Record<'a> {
+    /// Name of the record
+    tag: &'a str,
+    /// Fields following the name
+    fields: Vec<&'a str>,
+}
+
+impl<'a> Record<'a> {
+    /// Splits a line into its fields.
+    fn parse(line: &'a str) -> Self {
+        let mut fields = line.split('\t');
+        Record {
+            tag: fields.next().unwrap_or_default(),
+            fields: fields.collect(),
+        }
+    }
+
+    /// Gets a field as text.
+    fn text(&self, index: usize) -> Result<&'a str, String> {
+        self.fields
+            .get(index)
+            .copied()
+            .ok_or_else(|| format!("'{}' is missing field {}", self.tag, index + 1))
+    }
+
+    /// Gets a field parsed as a number or flag.
+    fn number<T: FromStr>(&self, index: usize) -> Result<T, String> {
+        let text = self.text(index)?;
+        text.parse()
+            .map_err(|_| format!("invalid value '{}' in '{}'", text, self.tag))
+    }
+
+    /// Gets a status effect stored in the first three fields.
+    fn status(&self) -> Result<StatusEffect, String> {
+        Ok(StatusEffect::new(
+            parse_enum(&StatusKind::ALL, self.text(0)?)?,
+            self.number(1)?,
+            self.number(2)?,
+        ))
+    }
+
+    /// Gets an item stored in the six fields starting at `start`.
+    fn item(&self, start: usize) -> Result<Item, String> {
+        let item_type = parse_item_type(self.text(start + 1)?)?;
+        let mut item = Item::new(
+            self.text(start)?.to_string(),
+            item_type,
+            self.number(start + 2)?,
+            self.text(start + 3)?.to_string(),
+        );
+        item.rarity = parse_enum(&Rarity::ALL, self.text(start + 4)?)?;
+        for affix in self.text(start + 5)?.split(',').filter(|a| !a.is_empty()) {
+            let (kind, power) = affix
+                .split_once(':')
+                .ok_or_else(|| format!("invalid affix '{}'", affix))?;
+            item.affixes.push(Affix {
+                kind: parse_enum(&AffixKind::ALL, kind)?,
+                power: power
+                    .parse()
+                    .map_err(|_| format!("invalid affix '{}'", affix))?,
+            });
+        }
+        Ok(item)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::{Background, Class};
+
+    #[test]
+    fn test_round_trip_keeps_game_state() {
+        let player = Player::create("Ada".to_string(), Class::Mage, Background::Noble);
+        let mut game = Game::generate(player, Rng::new(42));
+        game.player.inventory.push(Item::create_key(7, true));
+        let mut sword = Item::create_sword();
+        sword.rarity = Rarity::Rare;
+        sword.affixes = vec![
+            Affix {
+                kind: AffixKind::Sharp,
+                power: 1,
+            },
+            Affix {
+                kind: AffixKind::Haste,
+                power: 1,
+            },
+        ];
+        game.player.inventory.push(sword);
+        game.player.spellbook.spells[0].cooldown_left = 3;
+        game.player
+            .equipment
+            .set(Slot::RightRing, Some(Item::create_ring()));
+        game.map.set_tile(1, 1, Tile::Door(DoorState::Locked(9)));
+        game.log("Hello\tworld");
+        game.identification
+            .identify(&Item::create_identify_scroll());
+
+        let restored = read_game(&write_game(&game)).unwrap();
+
+        assert_eq!(write_game(&restored), write_game(&game));
+        assert_eq!(restored.player.class, Class::Mage);
+        assert_eq!(restored.rng.state(), game.rng.state());
+        assert_eq!(restored.identification, game.identification);
+        let sword = restored.player.inventory.last().unwrap();
+        assert_eq!(sword.display_name(), "Sharp Sword of Haste");
+        assert_eq!(sword.rarity, Rarity::Rare);
+        let ring = restored.player.equipment.get(Slot::RightRing).unwrap();
+        assert!(matches!(ring.item_type, ItemType::Jewelry(Slot::LeftRing)));
+        assert_eq!(
+            restored.map.get_tile(1, 1),
+            Some(&Tile::Door(DoorState::Locked(9)))
+        );
+        assert_eq!(restored.messages.last().unwrap(), "Hello world");
+    }
+
+    #[test]
+    fn test_broken_save_names_line() {
+        let mut content = write_game(&Game::new("Bob".to_string(), Map::new(2, 2)));
+        content.push_str("monster\tGoblin\n");
+
+        let error = read_game(&content).unwrap_err();
+        assert!(error.starts_with("line "), "{}", error);
+        assert!(read_game("garbage").is_err());
+    }
+}
@@ -0,0 +1,140 @@
+//! Spell module defining spells as data and the player's spellbook.
+
+/// Represents the different spells that can be cast.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SpellKind {
+    /// Magic missile that hits the first monster in its path
+    Bolt,
+    /// Explosion that damages every monster around the target
+    Blast,
+    /// Restores the caster's health
+    Heal,
+    /// Teleports the caster to a visible spot
+    Blink,
+    /// Reveals the surrounding map and hidden traps
+    Detect,
+}
+
+/// Represents the definition of a spell.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spell {
+    /// Kind of the spell, which decides its effect
+    pub kind: SpellKind,
+    /// Display name of the spell
+    pub name: &'static str,
+    /// Mana needed to cast the spell
+    pub cost: i32,
+    /// Turns to wait before the spell can be cast again
+    pub cooldown: i32,
+    /// Maximum distance in tiles to the target, 0 for self-cast spells
+    pub range: i32,
+    /// Damage or healing done by the spell
+    pub power: i32,
+    /// Radius in tiles of the area the spell affects
+    pub radius: i32,
+}
+
+impl SpellKind {
+    /// All spells in the order they are listed.
+    pub const ALL: [SpellKind; 5] = [
+        SpellKind::Bolt,
+        SpellKind::Blast,
+        SpellKind::Heal,
+        SpellKind::Blink,
+        SpellKind::Detect,
+    ];
+
+    /// Gets the definition of the spell.
+    ///
+    /// # Returns
+    /// The spell data with costs, cooldown, range and power
+    pub fn spell(&self) -> Spell {
+        let (name, cost, cooldown, range, power, radius) = match self {
+            SpellKind::Bolt => ("Bolt", 5, 0, 8, 14, 0),
+            SpellKind::Blast => ("Blast", 12, 5, 6, 12, 1),
+            SpellKind::Heal => ("Heal", 10, 8, 0, 25, 0),
+            SpellKind::Blink => ("Blink", 8, 10, 6, 0, 0),
+            SpellKind::Detect => ("Detect", 6, 15, 0, 0, 10),
+        };
+
+        Spell {
+            kind: *self,
+            name,
+            cost,
+            cooldown,
+            range,
+            power,
+            radius,
+        }
+    }
+}
+
+impl Spell {
+    /// Checks whether the spell needs a target picked with the cursor.
+    ///
+    /// # Returns
+    /// `true` for spells with a range, `false` for self-cast spells
+    pub fn needs_target(&self) -> bool {
+        self.range > 0
+    }
+}
+
+/// Represents a spell the player knows, with its remaining cooldown.
+#[derive(Debug, Clone)]
+pub struct KnownSpell {
+    /// The spell definition
+    pub spell: Spell,
+    /// Turns left until the spell can be cast again
+    pub cooldown_left: i32,
+}
+
+/// Represents the collection of spells the player has learned.
+#[derive(Debug, Clone, Default)]
+pub struct Spellbook {
+    /// Known spells in the order they were learned
+    pub spells: Vec<KnownSpell>,
+}
+
+impl Spellbook {
+    /// Creates a new spellbook.
+    ///
+    /// # Arguments
+    /// * `kinds` - Spells known from the start
+    ///
+    /// # Returns
+    /// A new Spellbook with all given spells ready to cast
+    pub fn new(kinds: &[SpellKind]) -> Self {
+        let mut spellbook = Spellbook::default();
+        for kind in kinds {
+            spellbook.learn(*kind);
+        }
+        spellbook
+    }
+
+    /// Adds a spell to the spellbook.
+    ///
+    /// # Returns
+    /// `true` if the spell was new, `false` if it was already known
+    pub fn learn(&mut self, kind: SpellKind) -> bool {
+        if self.knows(kind) {
+            return false;
+        }
+        self.spells.push(KnownSpell {
+            spell: kind.spell(),
+            cooldown_left: 0,
+        });
+        true
+    }
+
+    /// Checks whether a spell is known.
+    pub fn knows(&self, kind: SpellKind) -> bool {
+        self.spells.iter().any(|known| known.spell.kind == kind)
+    }
+
+    /// Counts down all cooldowns by one turn.
+    pub fn tick(&mut self) {
+        for known in &mut self.spells {
+            known.cooldown_left = (known.cooldown_left - 1).max(0);
+        }
+    }
+}
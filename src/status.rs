@@ -0,0 +1,111 @@
+//! Status effect module for temporary conditions on creatures.
+
+/// Represents the different kinds of status effects.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StatusKind {
+    /// Loses health every turn, ignoring defense
+    Poisoned,
+}
+
+impl StatusKind {
+    /// All status effect kinds.
+    pub const ALL: [StatusKind; 1] = [StatusKind::Poisoned];
+
+    /// Gets the display name of the status effect.
+    ///
+    /// # Returns
+    /// A short adjective describing the effect
+    pub fn name(&self) -> &'static str {
+        match self {
+            StatusKind::Poisoned => "Poisoned",
+        }
+    }
+}
+
+/// Represents an active status effect with a remaining duration.
+#[derive(Debug, Clone)]
+pub struct StatusEffect {
+    /// Kind of the effect
+    pub kind: StatusKind,
+    /// Remaining turns before the effect wears off
+    pub turns: i32,
+    /// Strength of the effect, e.g. poison damage per turn
+    pub potency: i32,
+}
+
+impl StatusEffect {
+    /// Creates a new status effect.
+    ///
+    /// # Arguments
+    /// * `kind` - The kind of effect
+    /// * `turns` - Duration in turns
+    /// * `potency` - Strength of the effect
+    ///
+    /// # Returns
+    /// A new StatusEffect instance
+    pub fn new(kind: StatusKind, turns: i32, potency: i32) -> Self {
+        StatusEffect {
+            kind,
+            turns,
+            potency,
+        }
+    }
+}
+
+/// Adds a status effect to a list of active effects.
+///
+/// If the same kind of effect is already active, the longer duration and
+/// the higher potency are kept.
+///
+/// # Arguments
+/// * `effects` - The active effects of a creature
+/// * `effect` - The effect to add
+pub fn merge_status(effects: &mut Vec<StatusEffect>, effect: StatusEffect) {
+    match effects.iter_mut().find(|active| active.kind == effect.kind) {
+        Some(active) => {
+            active.turns = active.turns.max(effect.turns);
+            active.potency = active.potency.max(effect.potency);
+        }
+        None => effects.push(effect),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Player;
+
+    #[test]
+    fn test_poison_counts_down_and_expires() {
+        let mut player = Player::new("Ann".to_string());
+        let health = player.health;
+        player.add_status(StatusEffect::new(StatusKind::Poisoned, 2, 3));
+        player.add_status(StatusEffect::new(StatusKind::Poisoned, 1, 4));
+        assert_eq!(player.status_effects.len(), 1);
+        assert_eq!(
+            (
+                player.status_effects[0].turns,
+                player.status_effects[0].potency
+            ),
+            (2, 4)
+        );
+
+        let messages = player.tick_status_effects();
+        assert_eq!(messages, ["The poison burns in your veins."]);
+        assert_eq!(player.health, health - 4);
+        assert_eq!(player.status_effects[0].turns, 1);
+        assert!(player.has_status(StatusKind::Poisoned));
+
+        let messages = player.tick_status_effects();
+        assert_eq!(
+            messages,
+            [
+                "The poison burns in your veins.",
+                "You are no longer poisoned."
+            ]
+        );
+        assert_eq!(player.health, health - 8);
+        assert!(!player.has_status(StatusKind::Poisoned));
+        assert!(player.tick_status_effects().is_empty());
+    }
+}
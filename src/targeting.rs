@@ -0,0 +1,89 @@
+//! Targeting module for picking a map position with a cursor.
+
+/// Represents an active targeting cursor.
+///
+/// The cursor starts on the closest target and can either cycle through
+/// all targets or be moved freely across the map.
+#[derive(Debug, Clone)]
+pub struct Targeting {
+    /// Position the shot or throw starts from
+    pub origin: (i32, i32),
+    /// Current cursor position
+    pub cursor: (i32, i32),
+    /// Positions of all targets that can be cycled through
+    pub targets: Vec<(i32, i32)>,
+    /// Maximum distance in tiles to the cursor
+    pub range: i32,
+    /// Index of the selected target
+    index: usize,
+}
+
+impl Targeting {
+    /// Creates a new targeting cursor.
+    ///
+    /// # Arguments
+    /// * `origin` - Position the shot or throw starts from
+    /// * `targets` - Targets to cycle through, closest first
+    /// * `range` - Maximum distance in tiles to the cursor
+    ///
+    /// # Returns
+    /// A new Targeting instance with the cursor on the first target, or on
+    /// the origin if there are no targets
+    pub fn new(origin: (i32, i32), targets: Vec<(i32, i32)>, range: i32) -> Self {
+        Targeting {
+            origin,
+            cursor: targets.first().copied().unwrap_or(origin),
+            targets,
+            range,
+            index: 0,
+        }
+    }
+
+    /// Moves the cursor to the next target, wrapping around at the end.
+    pub fn next_target(&mut self) {
+        if self.targets.is_empty() {
+            return;
+        }
+        self.index = (self.index + 1) % self.targets.len();
+        self.cursor = self.targets[self.index];
+    }
+
+    /// Moves the cursor by one step, staying within range of the origin.
+    ///
+    /// # Arguments
+    /// * `dx` - Step on the x-axis
+    /// * `dy` - Step on the y-axis
+    pub fn move_cursor(&mut self, dx: i32, dy: i32) {
+        let (x, y) = (self.cursor.0 + dx, self.cursor.1 + dy);
+        let distance = (x - self.origin.0).abs().max((y - self.origin.1).abs());
+        if distance <= self.range {
+            self.cursor = (x, y);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cursor_cycles_targets_and_stays_in_range() {
+        let mut targeting = Targeting::new((5, 5), vec![(6, 5), (8, 8)], 3);
+        assert_eq!(targeting.cursor, (6, 5));
+        targeting.next_target();
+        assert_eq!(targeting.cursor, (8, 8));
+        targeting.next_target();
+        assert_eq!(targeting.cursor, (6, 5));
+
+        targeting.move_cursor(1, 0);
+        targeting.move_cursor(1, 0);
+        assert_eq!(targeting.cursor, (8, 5));
+        targeting.move_cursor(1, 0);
+        assert_eq!(targeting.cursor, (8, 5));
+
+        let mut empty = Targeting::new((5, 5), Vec::new(), 3);
+        assert_eq!(empty.cursor, (5, 5));
+        empty.next_target();
+        assert_eq!(empty.cursor, (5, 5));
+    }
+}
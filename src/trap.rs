@@ -0,0 +1,146 @@
+//! Trap module for hidden features that trigger when stepped on.
+
+/// Represents the different kinds of traps.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TrapKind {
+    /// Spikes that deal physical damage
+    Spike,
+    /// Magic rune that moves the victim to a random place on the level
+    Teleport,
+    /// Vent that poisons the victim
+    PoisonGas,
+    /// Bell that alerts every monster on the level
+    Alarm,
+}
+
+impl TrapKind {
+    /// All trap kinds.
+    pub const ALL: [TrapKind; 4] = [
+        TrapKind::Spike,
+        TrapKind::Teleport,
+        TrapKind::PoisonGas,
+        TrapKind::Alarm,
+    ];
+
+    /// Gets the display name of the trap.
+    ///
+    /// # Returns
+    /// A short name of the trap
+    pub fn name(&self) -> &'static str {
+        match self {
+            TrapKind::Spike => "spike trap",
+            TrapKind::Teleport => "teleport trap",
+            TrapKind::PoisonGas => "poison gas trap",
+            TrapKind::Alarm => "alarm trap",
+        }
+    }
+}
+
+/// Represents a trap placed on the map.
+#[derive(Debug, Clone)]
+pub struct Trap {
+    /// X-coordinate on the map
+    pub x: i32,
+    /// Y-coordinate on the map
+    pub y: i32,
+    /// Kind of the trap
+    pub kind: TrapKind,
+    /// Whether the player has not found the trap yet
+    pub hidden: bool,
+}
+
+impl Trap {
+    /// Creates a new hidden trap.
+    ///
+    /// # Arguments
+    /// * `x` - The x-coordinate
+    /// * `y` - The y-coordinate
+    /// * `kind` - The kind of trap
+    ///
+    /// # Returns
+    /// A new Trap instance that is not yet discovered
+    pub fn new(x: i32, y: i32, kind: TrapKind) -> Self {
+        Trap {
+            x,
+            y,
+            kind,
+            hidden: true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{item::MapItem, rng::Rng, Game, Item, Map, Monster};
+
+    /// Creates a game in a corridor with the player at its west end.
+    fn corridor_game() -> Game {
+        let map = Map::from_ascii("#########\n#.......#\n#########").unwrap();
+        let mut game = Game::new("Ann".to_string(), map);
+        game.rng = Rng::new(5);
+        game.player.x = 1;
+        game.player.y = 1;
+        game
+    }
+
+    #[test]
+    fn test_hidden_traps_trigger_once_and_can_be_found() {
+        let mut game = corridor_game();
+        game.traps.push(Trap::new(2, 1, TrapKind::Spike));
+        let health = game.player.health;
+
+        assert!(game.move_player(1, 0));
+        assert!(!game.traps[0].hidden);
+        assert!(game.player.health < health);
+        assert!(game
+            .messages
+            .contains(&"Spikes shoot out of the floor!".to_string()));
+
+        let health = game.player.health;
+        game.move_player(-1, 0);
+        game.move_player(1, 0);
+        assert_eq!(game.player.health, health);
+        assert_eq!(
+            game.messages.last().unwrap(),
+            "You carefully step around the spike trap."
+        );
+
+        game.traps.push(Trap::new(4, 1, TrapKind::Alarm));
+        game.traps.push(Trap::new(7, 1, TrapKind::PoisonGas));
+        game.player.speed = 50;
+        assert!(game.search());
+        assert!(!game.traps[1].hidden);
+        assert!(game.traps[2].hidden);
+        assert_eq!(game.messages.last().unwrap(), "You discover an alarm trap!");
+    }
+
+    #[test]
+    fn test_teleport_traps_only_move_to_reachable_empty_floor() {
+        let map = Map::from_ascii("#########\n#...#...#\n#########").unwrap();
+        let mut game = Game::new("Ann".to_string(), map);
+        game.player.x = 1;
+        game.player.y = 1;
+        game.traps.push(Trap::new(2, 1, TrapKind::Teleport));
+        game.items.push(MapItem {
+            x: 3,
+            y: 1,
+            item: Item::create_apple(),
+        });
+
+        for seed in 0..20 {
+            game.rng = Rng::new(seed);
+            game.traps[0].hidden = true;
+            game.player.x = 1;
+            assert!(game.move_player(1, 0));
+            assert_eq!((game.player.x, game.player.y), (1, 1));
+        }
+
+        game.traps[0].hidden = true;
+        game.player.x = 3;
+        game.monsters.push(Monster::create_rat(1, 1));
+        assert!(game.move_player(-1, 0));
+        assert_eq!((game.player.x, game.player.y), (2, 1));
+        assert_eq!(game.messages.last().unwrap(), "The trap fizzles.");
+    }
+}
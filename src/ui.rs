@@ -1,85 +1,267 @@
 //! User Interface module for terminal-based rendering and interaction.
-
-use crate::{get_terminal_size, map::Tile, Game};
-use std::io::Write;
+//!
+//! All drawing goes through a [`Backend`], the terminal by default.
+
+use crate::{
+    affix::describe_bonus,
+    backend::{AnsiBackend, Backend, Cell, Color},
+    character::{Background, Class},
+    command::Command,
+    equipment::Slot,
+    highscore::HallOfFame,
+    hunger::HungerState,
+    keymap::Keymap,
+    light::LightLevel,
+    options::{ColorMode, Options},
+    targeting::Targeting,
+    Game, Monster,
+};
+
+/// Screen row of the first map line.
+const MAP_TOP: u16 = 3;
+/// Screen column of the first map column.
+const MAP_LEFT: u16 = 4;
+/// Delay between two frames of a projectile animation.
+const PROJECTILE_FRAME: std::time::Duration = std::time::Duration::from_millis(40);
 
 /// Main UI structure handling terminal rendering and user interaction.
-pub struct UI {
+///
+/// Screen rows and columns are 1-based, as in ANSI escape codes.
+pub struct UI<B: Backend = AnsiBackend> {
+    /// Device everything is drawn on
+    backend: B,
     width: u16,
     height: u16,
+    /// Whether the map is drawn in colour
+    color: ColorMode,
+    /// How long dialog messages stay on screen
+    message_delay: std::time::Duration,
 }
 
 /// Available content types for the UI system.
+#[derive(Clone, Copy)]
 pub enum Content<'a> {
     /// Main menu display with title and options
     MainMenu,
     /// Active game screen with map, stats and messages
     Game(&'a Game),
+    /// Character creation screen for picking a class
+    ChooseClass,
+    /// Character creation screen for picking a background
+    ChooseBackground,
+    /// Game screen with a targeting cursor, the projectile path and the
+    /// keys to aim with
+    Targeting(&'a Game, &'a Targeting, &'a Keymap),
+    /// Game screen with a look cursor, the description of the position under
+    /// it in place of the messages and the keys to look around with
+    Look(&'a Game, &'a Targeting, &'a Keymap),
+    /// Everything known about a monster: stats, status effects and abilities
+    MonsterInfo(&'a Monster),
+    /// Worn items by slot and the stat change of every wearable inventory
+    /// item
+    Equipment(&'a Game),
+    /// Every inventory item with its number and what picking one does, e.g.
+    /// `"throw"`
+    Inventory(&'a Game, &'a str),
+    /// Death screen summarising the run and its rank in the Hall of Fame
+    GameOver(&'a Game, Option<usize>),
+    /// List of the best recorded runs
+    HallOfFame(&'a HallOfFame),
+    /// Game screen with the in-game menu on top
+    PauseMenu(&'a Game),
+    /// List of all commands with their key bindings
+    Help(&'a Keymap),
+    /// Save slot selection with a title and the name and summary of every slot
+    ChooseSlot(&'a str, &'a [(String, String)]),
+    /// Settings screen listing every option with its value
+    Settings(&'a Options),
+    /// Game screen of a replay with a status line in place of the prompt
+    Replay(&'a Game, &'a str),
     /// Empty content, showing only the frame
     Empty,
 }
 
 impl UI {
-    /// Creates a new UI instance with current terminal dimensions.
+    /// Creates a new UI instance drawing on the terminal.
     ///
-    /// # Panics
-    /// When terminal size cannot be determined.
+    /// Falls back to an 80x24 screen if the terminal size cannot be
+    /// determined.
     pub fn new() -> Self {
-        let (width, height) = get_terminal_size().unwrap();
-        UI { width, height }
+        UI::with_backend(AnsiBackend::new())
+    }
+}
+
+impl<B: Backend> UI<B> {
+    /// Creates a new UI instance drawing on a backend.
+    ///
+    /// # Arguments
+    /// * `backend` - The device to draw on and read input from
+    ///
+    /// # Returns
+    /// A new UI sized to the backend, with the default options
+    pub fn with_backend(backend: B) -> Self {
+        let (width, height) = backend.size();
+        let options = Options::default();
+        UI {
+            backend,
+            width,
+            height,
+            color: options.color,
+            message_delay: options.message_speed.dialog_delay(),
+        }
+    }
+
+    /// Gets the backend, e.g. to inspect what was drawn.
+    pub fn backend(&self) -> &B {
+        &self.backend
+    }
+
+    /// Gets the backend for changes, e.g. to queue input.
+    pub fn backend_mut(&mut self) -> &mut B {
+        &mut self.backend
+    }
+
+    /// Writes text at a screen position, cutting it off at the screen edge.
+    ///
+    /// # Arguments
+    /// * `row` - Screen row of the first character
+    /// * `col` - Screen column of the first character
+    /// * `text` - The text to write
+    fn print_at(&mut self, row: u16, col: u16, text: &str) {
+        for (i, symbol) in text.chars().enumerate() {
+            self.print_cell(row, col + i as u16, Cell::new(symbol));
+        }
+    }
+
+    /// Writes a single cell at a screen position.
+    fn print_cell(&mut self, row: u16, col: u16, cell: Cell) {
+        if row > 0 && col > 0 {
+            self.backend.draw_cell(col - 1, row - 1, cell);
+        }
+    }
+
+    /// Moves the input cursor to a screen position.
+    fn move_cursor(&mut self, row: u16, col: u16) {
+        self.backend
+            .set_cursor(col.saturating_sub(1), row.saturating_sub(1));
+    }
+
+    /// Applies the display settings from the options.
+    ///
+    /// # Arguments
+    /// * `options` - The options to apply
+    pub fn apply_options(&mut self, options: &Options) {
+        self.color = options.color;
+        self.message_delay = options.message_speed.dialog_delay();
     }
 
     /// Draws the initial frame that remains constant throughout the application.
     ///
     /// Creates a box using Unicode box-drawing characters that fills the terminal.
-    pub fn draw_frame(&self) {
+    pub fn draw_frame(&mut self) {
         let horizontal_line = "─".repeat(self.width as usize - 2);
 
-        println!("┌{}┐", horizontal_line);
+        self.print_at(1, 1, &format!("┌{}┐", horizontal_line));
 
         let empty_line = format!("│{}│", " ".repeat(self.width as usize - 2));
-        for _ in 1..self.height - 1 {
-            println!("{}", empty_line);
+        for row in 2..self.height {
+            self.print_at(row, 1, &empty_line);
         }
 
-        print!("└{}┘", horizontal_line);
-        std::io::stdout().flush().unwrap();
+        self.print_at(self.height, 1, &format!("└{}┘", horizontal_line));
+        self.backend.flush();
     }
 
     /// Updates the content within the frame based on the provided content type.
     ///
     /// # Arguments
     /// * `content` - The type of content to display
-    pub fn update_content(&self, content: Content) {
+    pub fn update_content(&mut self, content: Content) {
         match content {
             Content::MainMenu => self.draw_main_menu(),
             Content::Game(game) => self.draw_game_screen(game),
+            Content::Targeting(game, targeting, keymap) => {
+                self.draw_game_screen(game);
+                self.draw_targeting(game, targeting, keymap);
+            }
+            Content::Look(game, targeting, keymap) => {
+                self.draw_game_screen(game);
+                self.draw_look(game, targeting, keymap);
+            }
+            Content::MonsterInfo(monster) => self.draw_monster_info(monster),
+            Content::Equipment(game) => self.draw_equipment(game),
+            Content::Inventory(game, action) => self.draw_inventory(game, action),
+            Content::ChooseClass => {
+                let options: Vec<(&str, &str)> = Class::ALL
+                    .iter()
+                    .map(|class| (class.name(), class.description()))
+                    .collect();
+                self.draw_choice_screen("Choose your class", &options);
+            }
+            Content::ChooseBackground => {
+                let options: Vec<(&str, &str)> = Background::ALL
+                    .iter()
+                    .map(|background| (background.name(), background.description()))
+                    .collect();
+                self.draw_choice_screen("Choose your background", &options);
+            }
+            Content::GameOver(game, rank) => self.draw_game_over(game, rank),
+            Content::HallOfFame(hall) => self.draw_hall_of_fame(hall),
+            Content::PauseMenu(game) => {
+                self.draw_game_screen(game);
+                self.draw_pause_menu();
+            }
+            Content::Help(keymap) => self.draw_help(keymap),
+            Content::Replay(game, status) => {
+                self.draw_game_screen(game);
+                self.draw_status_line(status);
+            }
+            Content::Settings(options) => self.draw_settings(options),
+            Content::ChooseSlot(title, slots) => {
+                let options: Vec<(&str, &str)> = slots
+                    .iter()
+                    .map(|(name, summary)| (name.as_str(), summary.as_str()))
+                    .collect();
+                self.draw_choice_screen(title, &options);
+            }
             Content::Empty => {
+                let empty_line = format!("│{}│", " ".repeat(self.width as usize - 2));
                 for row in 2..self.height - 1 {
-                    print!(
-                        "\x1B[{};{}H│{}│",
-                        row,
-                        1,
-                        " ".repeat(self.width as usize - 2)
-                    );
+                    self.print_at(row, 1, &empty_line);
                 }
-                std::io::stdout().flush().unwrap();
             }
         }
-        std::io::stdout().flush().unwrap();
+        self.backend.flush();
     }
 
-    /// Shows a centered dialog message for a specified duration.
+    /// Shows a centered dialog message for the duration set by the message
+    /// speed option.
     ///
     /// # Arguments
     /// * `message` - The message to display in the dialog
-    pub fn show_dialog(&self, message: &str) {
-        let col = (self.width as usize / 2) - (message.len() / 2);
+    pub fn show_dialog(&mut self, message: &str) {
+        let col = (self.width as usize / 2).saturating_sub(message.chars().count() / 2);
         let row = self.height / 2;
 
-        print!("\x1B[{};{}H{}", row, col, message);
-        std::io::stdout().flush().unwrap();
-        std::thread::sleep(std::time::Duration::from_secs(2));
+        self.print_at(row, col as u16, message);
+        self.move_cursor(row, (col + message.chars().count()) as u16);
+        self.backend.sleep(self.message_delay);
+    }
+
+    /// Shows a centered question and waits for the answer.
+    ///
+    /// # Arguments
+    /// * `message` - The question to show
+    ///
+    /// # Returns
+    /// The trimmed input of the player
+    pub fn prompt(&mut self, message: &str) -> String {
+        let col = (self.width as usize / 2).saturating_sub(message.chars().count() / 2);
+        let row = self.height / 2;
+
+        self.print_at(row, col as u16, message);
+        self.move_cursor(row, (col + message.chars().count()) as u16);
+        self.get_input()
     }
 
     /// Draws the main menu content with ASCII art title and options.
@@ -88,7 +270,7 @@ impl UI {
     /// - ASCII art title
     /// - Version number
     /// - Menu options
-    fn draw_main_menu(&self) {
+    fn draw_main_menu(&mut self) {
         let title = [
             r" ____  _   _ ____ _______   __",
             r"|  _ \| | | / ___|_   _\ \ / /",
@@ -101,13 +283,13 @@ impl UI {
 
         for (i, line) in title.iter().enumerate() {
             let padding = (self.width as usize - line.len()) / 2;
-            print!("\x1B[{};{}H", content_start_row + i as u16, 1);
-            print!(
+            let text = format!(
                 "│{}{}{}│",
                 " ".repeat(padding),
                 line,
                 " ".repeat(self.width as usize - padding - line.len() - 2)
             );
+            self.print_at(content_start_row + i as u16, 1, &text);
         }
 
         let version = format!("v{}", env!("CARGO_PKG_VERSION"));
@@ -118,142 +300,619 @@ impl UI {
             "",
             "1. New Game",
             "2. Load Game",
-            "3. Exit",
+            "3. Hall of Fame",
+            "4. Settings",
+            "5. Exit",
         ];
 
         for (i, item) in menu_items.iter().enumerate() {
             let padding = (self.width as usize - item.len()) / 2;
-            print!(
-                "\x1B[{};{}H",
-                content_start_row + title.len() as u16 + i as u16,
-                1
-            );
-            print!(
+            let text = format!(
                 "│{}{}{}│",
                 " ".repeat(padding),
                 item,
                 " ".repeat(self.width as usize - padding - item.len() - 2)
             );
+            self.print_at(content_start_row + (title.len() + i) as u16, 1, &text);
         }
 
+        self.draw_input_prompt();
+    }
+
+    /// Draws the `> ` input prompt centered above the bottom of the frame.
+    fn draw_input_prompt(&mut self) {
         let input_row = self.height - 2;
         let input_col = self.width / 2;
-        print!("\x1B[{};{}H> ", input_row, input_col);
+        self.print_at(input_row, input_col, "> ");
+        self.move_cursor(input_row, input_col + 2);
+    }
+
+    /// Draws a numbered list of options to choose from.
+    ///
+    /// # Arguments
+    /// * `title` - Heading shown above the options
+    /// * `options` - Name and description of every option
+    fn draw_choice_screen(&mut self, title: &str, options: &[(&str, &str)]) {
+        let mut lines = vec![title.to_string(), String::new()];
+        for (i, (name, description)) in options.iter().enumerate() {
+            lines.push(format!("{}. {:<10} {}", i + 1, name, description));
+        }
+        self.draw_text_screen(&lines);
+    }
+
+    /// Draws the in-game menu as a box on top of the game screen.
+    fn draw_pause_menu(&mut self) {
+        let lines = [
+            "P A U S E D",
+            "",
+            "1. Resume",
+            "2. Save",
+            "3. Options",
+            "4. Help",
+            "5. Quit to menu",
+        ];
+        let inner_width = 24;
+        let top = self.height / 2 - lines.len() as u16 / 2 - 1;
+        let col = (self.width - inner_width as u16) / 2;
+
+        self.print_at(top, col, &format!("╔{}╗", "═".repeat(inner_width)));
+        for (i, line) in lines.iter().enumerate() {
+            let padding = (inner_width - line.len()) / 2;
+            let text = format!(
+                "║{}{}║",
+                " ".repeat(padding),
+                line.to_string().pad_right(inner_width - padding)
+            );
+            self.print_at(top + 1 + i as u16, col, &text);
+        }
+        let bottom = format!("╚{}╝", "═".repeat(inner_width));
+        self.print_at(top + 1 + lines.len() as u16, col, &bottom);
+        self.move_cursor(self.height - 1, 11);
+    }
+
+    /// Draws the list of all commands with their active key bindings.
+    ///
+    /// # Arguments
+    /// * `keymap` - The active key bindings
+    fn draw_help(&mut self, keymap: &Keymap) {
+        let mut lines = vec![
+            format!("C O M M A N D S  ({} keys)", keymap.preset.name()),
+            String::new(),
+        ];
+        let diagonals: Vec<&str> = Command::ALL[4..8]
+            .iter()
+            .flat_map(|&command| keymap.keys(command))
+            .collect();
+
+        for (index, command) in Command::ALL.iter().enumerate() {
+            let keys = match index {
+                4 => diagonals.clone(),
+                5..=7 => continue,
+                _ => keymap.keys(*command),
+            };
+            if !keys.is_empty() {
+                lines.push(format!("{:<10} {}", keys.join(" "), command.description()));
+            }
+        }
+        lines.push(String::new());
+        lines.push("Press Enter to go back".to_string());
+        self.draw_text_screen(&lines);
+    }
+
+    /// Draws the settings screen with the current value of every option.
+    ///
+    /// # Arguments
+    /// * `options` - The options to show
+    fn draw_settings(&mut self, options: &Options) {
+        let labels = [
+            "Colours",
+            "Key preset",
+            "Message speed",
+            "Auto-save",
+            "Confirm quit",
+        ];
+        let mut lines = vec!["S E T T I N G S".to_string(), String::new()];
+        for (i, (label, name)) in labels.iter().zip(Options::NAMES).enumerate() {
+            lines.push(format!("{}. {:<14} {}", i + 1, label, options.value(name)));
+        }
+        lines.push(String::new());
+        lines.push("Enter a number to change it, Enter to go back".to_string());
+        self.draw_text_screen(&lines);
+    }
+
+    /// Draws the death screen with the summary of the finished run.
+    ///
+    /// # Arguments
+    /// * `game` - Reference to the finished game
+    /// * `rank` - 0-based rank of the run in the Hall of Fame, if it made it
+    fn draw_game_over(&mut self, game: &Game, rank: Option<usize>) {
+        let run = game.high_score();
+        let mut lines = vec![
+            "Y O U   D I E D".to_string(),
+            String::new(),
+            format!("{} the {}", run.name, run.class),
+            format!("{} on depth {}", run.cause, run.depth),
+            String::new(),
+            format!("Level: {}", run.level),
+            format!("Turns: {}", run.turns),
+            format!("Kills: {}", run.kills),
+            format!("Score: {}", run.score()),
+            String::new(),
+        ];
+        lines.push(match rank {
+            Some(rank) => format!("You entered the Hall of Fame at rank {}!", rank + 1),
+            None => "Your run did not make it into the Hall of Fame.".to_string(),
+        });
+        lines.push(String::new());
+        lines.push("Press Enter to return to the main menu".to_string());
+        self.draw_text_screen(&lines);
+    }
+
+    /// Draws the list of the best recorded runs.
+    ///
+    /// # Arguments
+    /// * `hall` - The Hall of Fame to show
+    fn draw_hall_of_fame(&mut self, hall: &HallOfFame) {
+        let mut lines = vec!["H A L L   O F   F A M E".to_string(), String::new()];
+        if hall.entries.is_empty() {
+            lines.push("No heroes have fallen yet.".to_string());
+        }
+        for (i, run) in hall.entries.iter().enumerate() {
+            lines.push(format!(
+                "{:>2}. {:>6}  {} the {}, level {}, {} on depth {}",
+                i + 1,
+                run.score(),
+                run.name,
+                run.class,
+                run.level,
+                run.cause,
+                run.depth
+            ));
+        }
+        lines.push(String::new());
+        lines.push("Press Enter to return to the main menu".to_string());
+        self.draw_text_screen(&lines);
+    }
+
+    /// Draws lines of text, left-aligned in a centered block.
+    ///
+    /// Lines wider than the frame are cut off.
+    ///
+    /// # Arguments
+    /// * `lines` - The lines to draw, top to bottom
+    fn draw_text_screen(&mut self, lines: &[String]) {
+        let inner_width = self.width as usize - 2;
+        let lines: Vec<String> = lines
+            .iter()
+            .map(|line| line.chars().take(inner_width).collect())
+            .collect();
+
+        let lines = &lines[..lines.len().min(self.height as usize - 3)];
+        let content_start_row = (self.height / 2)
+            .saturating_sub(lines.len() as u16 / 2)
+            .max(2);
+        let text_width = lines
+            .iter()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0);
+        let padding = (inner_width - text_width) / 2;
+
+        for (i, line) in lines.iter().enumerate() {
+            let text = format!(
+                "│{}{}│",
+                " ".repeat(padding),
+                line.pad_right(inner_width - padding)
+            );
+            self.print_at(content_start_row + i as u16, 1, &text);
+        }
+
+        self.draw_input_prompt();
     }
 
     /// Draws the main game screen with map, stats, messages and command line.
     ///
     /// # Arguments
     /// * `game` - Reference to the current game state
-    fn draw_game_screen(&self, game: &Game) {
+    fn draw_game_screen(&mut self, game: &Game) {
         let map_width = ((self.width as usize - 6) * 2) / 3;
         let stats_width = (self.width as usize - 6) - map_width - 3;
+        let mut row = 2;
 
-        print!("\x1B[2;1H");
-        println!(
+        let top = format!(
             "│ ╔{}╗ ╔{}╗ │",
             "═".repeat(map_width),
             "═".repeat(stats_width)
         );
+        self.print_at(row, 1, &top);
+        row += 1;
 
-        let remaining_space = map_width - game.map.width as usize;
+        let remaining_space = map_width.saturating_sub(game.map.width as usize);
         let padding = " ".repeat(remaining_space);
 
         for y in 0..game.map.height as usize {
-            print!("│ ║");
-
-            for x in 0..game.map.width as usize {
-                let tile = game
-                    .map
-                    .get_tile(x as i32, y as i32)
-                    .unwrap_or(&Tile::Empty);
-                let symbol = match tile {
-                    Tile::Floor => '.',
-                    Tile::Wall => '#',
-                    Tile::Door => '+',
-                    Tile::Empty => ' ',
-                };
-                print!("{}", symbol);
+            self.print_at(row, 1, "│ ║");
+            for x in 0..game.map.width {
+                let cell = self.shade(game, x, y as i32);
+                self.print_cell(row, MAP_LEFT + x as u16, cell);
             }
-            print!("{}║ ║", padding);
 
             let stat_line = match y {
-                0 => "Stats:".to_string(),
-                1 => format!("HP: {}/100", game.player.health),
-                2 => format!("Level: {}", game.player.level),
-                3 => format!(
+                0 => format!("{} the {}", game.player.name, game.player.class.name()),
+                1 => format!("HP: {}/{}", game.player.health, game.player.max_health),
+                2 => format!("MP: {}/{}", game.player.mana, game.player.max_mana),
+                3 => format!("Level: {}", game.player.level),
+                4 => format!(
                     "XP: {}/{}",
                     game.player.experience, game.player.experience_to_next_level
                 ),
-                4 => format!("ATK: {}", game.player.attack),
-                5 => format!("DEF: {}", game.player.defense),
-                _ => String::new(),
+                5 => format!("ATK: {}", game.player.attack),
+                6 => format!("DEF: {}", game.player.defense),
+                7 => format!("Depth: {}", game.depth),
+                8 => {
+                    let hunger = game.player.hunger();
+                    game.player
+                        .status_effects
+                        .iter()
+                        .map(|effect| effect.kind.name())
+                        .chain((hunger != HungerState::NotHungry).then(|| hunger.name()))
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                }
+                9 => match game.player.equipment.get(Slot::MainHand) {
+                    Some(weapon) => format!("Wielding: {}", weapon.display_name()),
+                    None => "Wielding: nothing".to_string(),
+                },
+                10 => match &game.player.equipped_light {
+                    Some(light) => format!("Light: {}", light.display_name()),
+                    None => "Light: none".to_string(),
+                },
+                11 => "Inventory:".to_string(),
+                _ => inventory_line(game, y - 12, (game.map.height as usize).saturating_sub(12)),
             };
-
-            if !stat_line.is_empty() {
-                let stat_padding = (stats_width - stat_line.len()) / 2;
-                print!(
-                    "{}{}{}",
-                    " ".repeat(stat_padding),
-                    stat_line,
-                    " ".repeat(stats_width - stat_line.len() - stat_padding)
-                );
-            } else {
-                print!("{}", " ".repeat(stats_width));
-            }
-            println!("║ │");
+            let stat_line: String = stat_line.chars().take(stats_width).collect();
+            let length = stat_line.chars().count();
+            let stat_padding = (stats_width - length) / 2;
+
+            let text = format!(
+                "{}║ ║{}{}{}║ │",
+                padding,
+                " ".repeat(stat_padding),
+                stat_line,
+                " ".repeat(stats_width - length - stat_padding)
+            );
+            self.print_at(row, MAP_LEFT + game.map.width as u16, &text);
+            row += 1;
         }
 
-        println!(
+        let bottom = format!(
             "│ ╚{}╝ ╚{}╝ │",
             "═".repeat(map_width),
             "═".repeat(stats_width)
         );
+        self.print_at(row, 1, &bottom);
+        row += 1;
 
-        println!(
+        let header = format!(
             "│ {}│",
             format!("Messages{}", "─".repeat(self.width as usize - 11))
                 .pad_right(self.width as usize - 3)
         );
-
-        for _ in 0..(self.height - 20) {
-            println!("│{}│", " ".repeat(self.width as usize - 2));
+        self.print_at(row, 1, &header);
+        row += 1;
+
+        let message_rows = self.height.saturating_sub(20) as usize;
+        let skipped = game.messages.len().saturating_sub(message_rows);
+        let mut messages = game.messages.iter().skip(skipped);
+        for _ in 0..message_rows {
+            let line = messages.next().cloned().unwrap_or_default();
+            let text = format!("│ {}│", line.pad_right(self.width as usize - 3));
+            self.print_at(row, 1, &text);
+            row += 1;
         }
 
-        print!("\x1B[{};1H", self.height - 1);
-        print!(
+        let command_line = format!(
             "│ {}│",
             "Command: ".to_string().pad_right(self.width as usize - 3)
         );
+        self.print_at(self.height - 1, 1, &command_line);
+        self.move_cursor(self.height - 1, 11);
+        self.backend.flush();
+    }
+
+    /// Draws the targeting cursor and the expected projectile path.
+    ///
+    /// # Arguments
+    /// * `game` - Reference to the current game state
+    /// * `targeting` - The active targeting cursor
+    /// * `keymap` - The active key bindings
+    fn draw_targeting(&mut self, game: &Game, targeting: &Targeting, keymap: &Keymap) {
+        for &(x, y) in &game.projectile_path(targeting.cursor, targeting.range) {
+            if (x, y) != targeting.cursor {
+                self.draw_map_cell(x, y, '*');
+            }
+        }
+
+        let (x, y) = targeting.cursor;
+        let cursor = Cell {
+            reversed: true,
+            ..Cell::new(game.symbol_at(x, y))
+        };
+        self.print_map_cell(x, y, cursor);
+
+        let target = game
+            .monster_at(x, y)
+            .map(|i| game.monsters[i].name.as_str())
+            .unwrap_or("nothing");
+        let key = |command| keymap.keys(command).first().copied().unwrap_or("-");
+        let text = format!(
+            "│ {}│",
+            format!(
+                "Target: {} - [{}] next, {} move, [Enter] confirm, [{}] cancel: ",
+                target,
+                key(Command::NextTarget),
+                keymap.direction_hint(),
+                key(Command::Menu)
+            )
+            .pad_right(self.width as usize - 3)
+        );
+        self.print_at(self.height - 1, 1, &text);
+        self.move_cursor(self.height - 1, 60.min(self.width - 2));
+        self.backend.flush();
+    }
+
+    /// Draws the look cursor and describes the position under it.
+    ///
+    /// The description replaces the messages, the key hints the command line.
+    ///
+    /// # Arguments
+    /// * `game` - Reference to the current game state
+    /// * `targeting` - The cursor moved across the map
+    /// * `keymap` - The active key bindings
+    fn draw_look(&mut self, game: &Game, targeting: &Targeting, keymap: &Keymap) {
+        let (x, y) = targeting.cursor;
+        let cursor = Cell {
+            reversed: true,
+            ..Cell::new(game.symbol_at(x, y))
+        };
+        self.print_map_cell(x, y, cursor);
+
+        let first_row = MAP_TOP + game.map.height as u16 + 2;
+        let mut lines = game.describe_at(x, y).into_iter();
+        for row in first_row..self.height - 1 {
+            let line = lines.next().unwrap_or_default();
+            let text = format!("│ {}│", line.pad_right(self.width as usize - 3));
+            self.print_at(row, 1, &text);
+        }
+
+        let key = |command| keymap.keys(command).first().copied().unwrap_or("-");
+        let details = match game.monster_at(x, y) {
+            Some(i) if game.can_see_monster(&game.monsters[i]) => "[Enter] details, ",
+            _ => "",
+        };
+        let status = format!(
+            "Look - [{}] next, {} move, {}[{}] back: ",
+            key(Command::NextTarget),
+            keymap.direction_hint(),
+            details,
+            key(Command::Menu)
+        );
+        self.draw_status_line(&status);
+        self.backend.flush();
+    }
+
+    /// Draws everything known about a monster.
+    ///
+    /// # Arguments
+    /// * `monster` - The monster to describe
+    fn draw_monster_info(&mut self, monster: &Monster) {
+        let mut lines = vec![
+            format!("{} ({})", monster.name, monster.glyph),
+            String::new(),
+            format!(
+                "Health:     {}/{}, {}",
+                monster.health.max(0),
+                monster.max_health,
+                monster.health_estimate()
+            ),
+            format!("Attack:     {}", monster.attack),
+            format!("Defense:    {}", monster.defense),
+            format!("Experience: {}", monster.experience),
+            String::new(),
+            "Status effects:".to_string(),
+        ];
+        if monster.status_effects.is_empty() {
+            lines.push("  none".to_string());
+        }
+        for effect in &monster.status_effects {
+            lines.push(format!(
+                "  {} for {} turns",
+                effect.kind.name(),
+                effect.turns
+            ));
+        }
+        lines.push(String::new());
+        lines.push("Abilities:".to_string());
+        for ability in monster.abilities() {
+            lines.push(format!("  {}", ability));
+        }
+        lines.push(String::new());
+        lines.push("Press Enter to go back".to_string());
+        self.draw_text_screen(&lines);
+    }
+
+    /// Draws the worn items and what wearing each inventory item would
+    /// change.
+    ///
+    /// # Arguments
+    /// * `game` - Reference to the current game state
+    fn draw_equipment(&mut self, game: &Game) {
+        let equipment = &game.player.equipment;
+        let mut lines = vec!["E Q U I P M E N T".to_string(), String::new()];
+        for slot in Slot::ALL {
+            let worn = match equipment.get(slot) {
+                Some(item) => item.display_name(),
+                None => match equipment.get(Slot::MainHand) {
+                    Some(weapon) if slot == Slot::OffHand && weapon.is_two_handed() => {
+                        "(both hands)".to_string()
+                    }
+                    _ => "-".to_string(),
+                },
+            };
+            lines.push(format!("{:<11} {}", slot.name(), worn));
+        }
+
+        lines.push(String::new());
+        lines.push("Inventory:".to_string());
+        let mut wearable = 0;
+        for (i, item) in game.player.inventory.iter().enumerate() {
+            if item.slot().is_none() {
+                continue;
+            }
+            let change = match equipment.change(item) {
+                Ok(change) if describe_bonus(change).is_empty() => "no change".to_string(),
+                Ok(change) => describe_bonus(change),
+                Err(e) => e,
+            };
+            lines.push(format!(
+                "{}. {} ({})",
+                i + 1,
+                game.identification.name(item),
+                change
+            ));
+            wearable += 1;
+        }
+        if wearable == 0 {
+            lines.push("  nothing to wear".to_string());
+        }
+        lines.push(String::new());
+        lines.push("Enter a number to equip the item, Enter to go back".to_string());
+        self.draw_text_screen(&lines);
+    }
+
+    /// Draws the inventory screen listing every item with its number.
+    ///
+    /// # Arguments
+    /// * `game` - Reference to the current game state
+    /// * `action` - What is done with the picked item, e.g. `"use"`
+    fn draw_inventory(&mut self, game: &Game, action: &str) {
+        let mut lines = vec!["I N V E N T O R Y".to_string(), String::new()];
+        for (i, item) in game.player.inventory.iter().enumerate() {
+            lines.push(format!("{}. {}", i + 1, game.identification.name(item)));
+        }
+        if game.player.inventory.is_empty() {
+            lines.push("  nothing carried".to_string());
+        }
+        lines.push(String::new());
+        lines.push(format!(
+            "Enter a number to {} the item, Enter to go back",
+            action
+        ));
+        self.draw_text_screen(&lines);
+    }
+
+    /// Replaces the command line of the game screen with a status line.
+    ///
+    /// # Arguments
+    /// * `status` - The text to show
+    fn draw_status_line(&mut self, status: &str) {
+        let text = format!(
+            "│ {}│",
+            status.to_string().pad_right(self.width as usize - 3)
+        );
+        self.print_at(self.height - 1, 1, &text);
+        let col = (status.chars().count() as u16 + 3).min(self.width - 2);
+        self.move_cursor(self.height - 1, col);
+    }
+
+    /// Animates a projectile flying along a path on the map.
+    ///
+    /// # Arguments
+    /// * `game` - Reference to the current game state
+    /// * `path` - Positions the projectile passes, in order
+    /// * `glyph` - Symbol drawn for the projectile
+    pub fn animate_projectile(&mut self, game: &Game, path: &[(i32, i32)], glyph: char) {
+        for &(x, y) in path {
+            self.draw_map_cell(x, y, glyph);
+            self.backend.sleep(PROJECTILE_FRAME);
+            self.draw_map_cell(x, y, game.symbol_at(x, y));
+        }
+        self.backend.flush();
+    }
+
+    /// Draws a single symbol at a map position.
+    fn draw_map_cell(&mut self, x: i32, y: i32, symbol: char) {
+        let cell = self.paint(symbol);
+        self.print_map_cell(x, y, cell);
+    }
 
-        print!("\x1B[{};11H", self.height - 1);
+    /// Draws a cell at a map position, positions off the map are skipped.
+    fn print_map_cell(&mut self, x: i32, y: i32, cell: Cell) {
+        if x >= 0 && y >= 0 {
+            self.print_cell(MAP_TOP + y as u16, MAP_LEFT + x as u16, cell);
+        }
+    }
 
-        std::io::stdout().flush().unwrap();
+    /// Gets the cell a map position is drawn as, faint if the player does
+    /// not see it or it is poorly lit.
+    fn shade(&self, game: &Game, x: i32, y: i32) -> Cell {
+        let is_player = (x, y) == (game.player.x, game.player.y);
+        let lit = game.map.is_visible(x, y) && game.map.light[(x, y)] > LightLevel::Dim;
+        Cell {
+            dim: !lit && !is_player,
+            ..self.paint(game.symbol_at(x, y))
+        }
+    }
+
+    /// Gets the cell a map symbol is drawn as.
+    ///
+    /// # Returns
+    /// The symbol in its colour, or in the default colour when colours are
+    /// turned off
+    fn paint(&self, symbol: char) -> Cell {
+        let color = match symbol {
+            _ if self.color == ColorMode::Monochrome => Color::Default,
+            '@' => Color::White,
+            'a'..='z' | 'A'..='Z' => Color::Red,
+            ')' | '}' | '/' | '[' | '!' | '?' | '-' | '(' | '%' | '"' | '*' => Color::BrightYellow,
+            '=' | '+' | '\'' => Color::Yellow,
+            '^' => Color::Magenta,
+            '>' => Color::Cyan,
+            '.' => Color::Gray,
+            _ => Color::Default,
+        };
+        Cell {
+            color,
+            ..Cell::new(symbol)
+        }
     }
 
     /// Gets user input from the current cursor position.
     ///
     /// # Returns
     /// A trimmed string containing the user's input.
-    pub fn get_input(&self) -> String {
-        std::io::stdout().flush().unwrap();
-        let mut input = String::new();
-        std::io::stdin().read_line(&mut input).unwrap();
-        input.trim().to_string()
+    pub fn get_input(&mut self) -> String {
+        self.backend.read_key()
     }
-}
 
-impl Drop for UI {
-    /// Ensures proper cleanup of terminal state on UI destruction.
+    /// Gets user input if the player types it before a timeout.
+    ///
+    /// # Arguments
+    /// * `timeout` - How long to wait at most
     ///
-    /// - Waits briefly to show final messages
-    /// - Clears the screen
-    /// - Restores cursor visibility
+    /// # Returns
+    /// * `Some(String)` with the trimmed input if it came in time
+    /// * `None` otherwise
+    pub fn poll_input(&mut self, timeout: std::time::Duration) -> Option<String> {
+        self.backend.poll_key(timeout)
+    }
+}
+
+impl<B: Backend> Drop for UI<B> {
+    /// Waits for half the dialog duration to show final messages before the
+    /// backend cleans up the screen.
     fn drop(&mut self) {
-        std::thread::sleep(std::time::Duration::from_secs(1));
-        print!("\x1B[2J\x1B[H");
-        print!("\x1B[?25h");
-        std::io::stdout().flush().unwrap();
+        self.backend.sleep(self.message_delay / 2);
     }
 }
 
@@ -264,6 +923,23 @@ impl Default for UI {
     }
 }
 
+/// Gets one line of the inventory list in the stats panel.
+///
+/// # Arguments
+/// * `game` - Reference to the current game state
+/// * `row` - Index of the line within the list
+/// * `rows` - Number of lines available for the list
+fn inventory_line(game: &Game, row: usize, rows: usize) -> String {
+    let inventory = &game.player.inventory;
+    if inventory.len() > rows && row == rows - 1 {
+        return format!("... {} more", inventory.len() - row);
+    }
+    inventory
+        .get(row)
+        .map(|item| format!("{}. {}", row + 1, game.identification.name(item)))
+        .unwrap_or_default()
+}
+
 /// Trait for padding strings with spaces to a specific width.
 ///
 /// This trait provides functionality to right-pad strings with spaces
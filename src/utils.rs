@@ -14,6 +14,7 @@
 /// - System call failures
 /// - Invalid terminal dimensions (0 width or height)
 #[cfg(any(unix, windows))]
+#[allow(clippy::io_other_error)]
 pub fn get_terminal_size() -> Result<(u16, u16), std::io::Error> {
     #[cfg(unix)]
     {
@@ -0,0 +1,704 @@
+//! Vault module for hand-made special rooms authored as ASCII templates.
+//!
+//! The built-in templates are in `vaults.txt`, which also describes the
+//! format. A vault is stamped into a finished level where it only covers
+//! rock and walls, rotated and mirrored at random, and its doors are joined
+//! to the rest of the level by tunnels.
+
+use std::collections::{HashMap, VecDeque};
+
+use crate::{
+    grid::Grid,
+    loot,
+    map::{DoorState, Tile},
+    mapgen,
+    rng::Rng,
+    trap::TrapKind,
+    Map, Monster,
+};
+
+/// Templates of the built-in vaults.
+pub const VAULTS: &str = include_str!("vaults.txt");
+/// Number of random positions tried when placing a vault.
+const PLACEMENT_ATTEMPTS: usize = 100;
+/// Symbols with a fixed meaning in vault rows.
+const RESERVED_SYMBOLS: [char; 4] = ['#', '.', '+', ' '];
+/// Creates a monster at a position.
+type CreateMonster = fn(i32, i32) -> Monster;
+/// Monsters vault legends can place, by name.
+const MONSTERS: [(&str, CreateMonster); 3] = [
+    ("goblin", Monster::create_goblin),
+    ("rat", Monster::create_rat),
+    ("beetle", Monster::create_fire_beetle),
+];
+
+/// Represents how often a vault appears compared to others.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Rarity {
+    /// Appears often
+    Common,
+    /// Appears now and then
+    Uncommon,
+    /// Appears seldom
+    Rare,
+}
+
+impl Rarity {
+    /// All rarities, most common first.
+    pub const ALL: [Rarity; 3] = [Rarity::Common, Rarity::Uncommon, Rarity::Rare];
+
+    /// Gets the name used in templates.
+    ///
+    /// # Returns
+    /// The lowercase name of the rarity
+    pub fn name(&self) -> &'static str {
+        match self {
+            Rarity::Common => "common",
+            Rarity::Uncommon => "uncommon",
+            Rarity::Rare => "rare",
+        }
+    }
+
+    /// Gets the weight when picking one of several vaults.
+    ///
+    /// # Returns
+    /// The relative chance to be picked
+    pub fn weight(&self) -> i32 {
+        match self {
+            Rarity::Common => 6,
+            Rarity::Uncommon => 3,
+            Rarity::Rare => 1,
+        }
+    }
+}
+
+/// Represents what a legend symbol places on its floor tile.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Feature {
+    /// A monster by name, e.g. `goblin`
+    Monster(String),
+    /// The items rolled from a loot table
+    Item(String),
+    /// A chest filled from a loot table
+    Chest(String),
+    /// A hidden trap
+    Trap(TrapKind),
+}
+
+/// Represents a feature placed on the map by a vault.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Placed {
+    /// The x-coordinate on the map
+    pub x: i32,
+    /// The y-coordinate on the map
+    pub y: i32,
+    /// What to place there
+    pub feature: Feature,
+}
+
+/// Represents a rotation by quarter turns clockwise, after an optional
+/// left-right mirroring.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Transform {
+    /// Number of quarter turns clockwise, 0 to 3
+    pub quarter_turns: u8,
+    /// Whether the rows are mirrored before turning
+    pub mirrored: bool,
+}
+
+impl Transform {
+    /// All eight orientations of a template.
+    pub const ALL: [Transform; 8] = [
+        Transform::new(0, false),
+        Transform::new(1, false),
+        Transform::new(2, false),
+        Transform::new(3, false),
+        Transform::new(0, true),
+        Transform::new(1, true),
+        Transform::new(2, true),
+        Transform::new(3, true),
+    ];
+
+    /// Creates a new transform.
+    ///
+    /// # Arguments
+    /// * `quarter_turns` - Quarter turns clockwise, taken modulo 4
+    /// * `mirrored` - Whether to mirror before turning
+    pub const fn new(quarter_turns: u8, mirrored: bool) -> Self {
+        Transform {
+            quarter_turns: quarter_turns % 4,
+            mirrored,
+        }
+    }
+
+    /// Applies the transform to a rectangular grid.
+    ///
+    /// # Arguments
+    /// * `rows` - The grid, indexed `[y][x]`
+    ///
+    /// # Returns
+    /// The turned grid; a quarter turn swaps width and height
+    pub fn apply<T: Copy>(&self, rows: &[Vec<T>]) -> Vec<Vec<T>> {
+        let mut grid: Vec<Vec<T>> = rows.to_vec();
+        if self.mirrored {
+            for row in &mut grid {
+                row.reverse();
+            }
+        }
+        for _ in 0..self.quarter_turns {
+            let (width, height) = (grid.first().map_or(0, Vec::len), grid.len());
+            grid = (0..width)
+                .map(|x| (0..height).rev().map(|y| grid[y][x]).collect())
+                .collect();
+        }
+        grid
+    }
+}
+
+/// Represents a hand-made special room.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Vault {
+    /// Name of the vault
+    pub name: String,
+    /// First depth the vault appears on
+    pub min_depth: i32,
+    /// Last depth the vault appears on, if any
+    pub max_depth: Option<i32>,
+    /// How often the vault is picked
+    pub rarity: Rarity,
+    /// Meaning of the symbols besides `#`, `.`, `+` and space
+    pub legend: HashMap<char, Feature>,
+    /// The rows of the template, all of the same width
+    pub rows: Vec<Vec<char>>,
+}
+
+impl Vault {
+    /// Checks whether the vault may appear on a depth.
+    pub fn allows_depth(&self, depth: i32) -> bool {
+        depth >= self.min_depth && self.max_depth.is_none_or(|max| depth <= max)
+    }
+
+    /// Creates a turned and/or mirrored copy of the vault.
+    ///
+    /// # Arguments
+    /// * `transform` - The orientation to use
+    ///
+    /// # Returns
+    /// A copy with the rows transformed
+    pub fn transformed(&self, transform: Transform) -> Vault {
+        Vault {
+            rows: transform.apply(&self.rows),
+            ..self.clone()
+        }
+    }
+
+    /// Checks whether a template cell is outside the vault.
+    fn is_outside(&self, x: i32, y: i32) -> bool {
+        x < 0
+            || y < 0
+            || self
+                .rows
+                .get(y as usize)
+                .and_then(|row| row.get(x as usize))
+                .is_none_or(|symbol| *symbol == ' ')
+    }
+
+    /// Finds the doors with a side facing out of the vault.
+    ///
+    /// # Returns
+    /// The template position of every such door and of the cell outside it
+    fn exits(&self) -> Vec<((i32, i32), (i32, i32))> {
+        let mut exits = Vec::new();
+        for (y, row) in self.rows.iter().enumerate() {
+            for (x, symbol) in row.iter().enumerate() {
+                if *symbol != '+' {
+                    continue;
+                }
+                let (x, y) = (x as i32, y as i32);
+                for (dx, dy) in [(0, -1), (0, 1), (-1, 0), (1, 0)] {
+                    if self.is_outside(x + dx, y + dy) {
+                        exits.push(((x, y), (x + dx, y + dy)));
+                    }
+                }
+            }
+        }
+        exits
+    }
+}
+
+/// Parses vault templates.
+///
+/// # Arguments
+/// * `text` - The templates, in the format described in `vaults.txt`
+///
+/// # Returns
+/// * `Ok(Vec<Vault>)` with every vault in the order given
+/// * `Err(String)` naming the line of the first mistake
+pub fn parse_vaults(text: &str) -> Result<Vec<Vault>, String> {
+    let mut vaults = Vec::new();
+    let mut current: Option<Vault> = None;
+    let mut in_map = false;
+    let mut last_line = 0;
+
+    for (index, raw) in text.lines().enumerate() {
+        let number = index + 1;
+        last_line = number;
+        let error = |message: String| format!("line {}: {}", number, message);
+        let line = raw.trim_end();
+
+        if in_map {
+            if line.trim() == "end" {
+                in_map = false;
+                if let Some(vault) = current.take() {
+                    vaults.push(finish(vault).map_err(error)?);
+                }
+            } else if let Some(vault) = current.as_mut() {
+                vault.rows.push(line.chars().collect());
+            }
+            continue;
+        }
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+
+        let (keyword, rest) = line
+            .trim()
+            .split_once(char::is_whitespace)
+            .map_or((line.trim(), ""), |(keyword, rest)| (keyword, rest.trim()));
+        if keyword == "vault" {
+            if let Some(vault) = &current {
+                return Err(error(format!("vault '{}' has no map", vault.name)));
+            }
+            if rest.is_empty() {
+                return Err(error("the vault needs a name".to_string()));
+            }
+            current = Some(Vault {
+                name: rest.to_string(),
+                min_depth: 1,
+                max_depth: None,
+                rarity: Rarity::Common,
+                legend: HashMap::new(),
+                rows: Vec::new(),
+            });
+            continue;
+        }
+
+        let Some(vault) = current.as_mut() else {
+            return Err(error(format!(
+                "expected 'vault <name>', found '{}'",
+                line.trim()
+            )));
+        };
+        match keyword {
+            "depth" => {
+                (vault.min_depth, vault.max_depth) = parse_depth(rest).map_err(error)?;
+            }
+            "rarity" => {
+                vault.rarity = Rarity::ALL
+                    .into_iter()
+                    .find(|rarity| rarity.name() == rest)
+                    .ok_or_else(|| {
+                        error(format!(
+                            "unknown rarity '{}', expected common, uncommon or rare",
+                            rest
+                        ))
+                    })?;
+            }
+            "legend" => {
+                let (symbol, feature) = parse_legend(rest).map_err(error)?;
+                if vault.legend.insert(symbol, feature).is_some() {
+                    return Err(error(format!("symbol '{}' is defined twice", symbol)));
+                }
+            }
+            "map" => in_map = true,
+            _ => {
+                return Err(error(format!(
+                    "unknown keyword '{}', expected depth, rarity, legend or map",
+                    keyword
+                )))
+            }
+        }
+    }
+
+    match current {
+        Some(vault) if in_map => Err(format!(
+            "line {}: the map of vault '{}' is missing 'end'",
+            last_line, vault.name
+        )),
+        Some(vault) => Err(format!(
+            "line {}: vault '{}' has no map",
+            last_line, vault.name
+        )),
+        None => Ok(vaults),
+    }
+}
+
+/// Parses a depth range like `3`, `2-5` or `4-`.
+fn parse_depth(text: &str) -> Result<(i32, Option<i32>), String> {
+    let invalid = || format!("invalid depth '{}', expected e.g. '3', '2-5' or '4-'", text);
+    let number = |part: &str| part.trim().parse::<i32>().ok().filter(|depth| *depth >= 1);
+
+    let (min, max) = match text.split_once('-') {
+        None => {
+            let depth = number(text).ok_or_else(invalid)?;
+            (depth, Some(depth))
+        }
+        Some((min, "")) => (number(min).ok_or_else(invalid)?, None),
+        Some((min, max)) => (
+            number(min).ok_or_else(invalid)?,
+            Some(number(max).ok_or_else(invalid)?),
+        ),
+    };
+    if max.is_some_and(|max| max < min) {
+        return Err(format!("depth range '{}' is empty", text));
+    }
+    Ok((min, max))
+}
+
+/// Parses the part of a legend line after `legend`.
+fn parse_legend(text: &str) -> Result<(char, Feature), String> {
+    let mut words = text.split_whitespace();
+    let symbol = match words.next().map(|word| (word, word.chars().count())) {
+        Some((word, 1)) => word.chars().next().unwrap_or(' '),
+        Some((word, _)) => {
+            return Err(format!("legend symbol '{}' must be one character", word));
+        }
+        None => return Err("expected 'legend <symbol> <kind> <name>'".to_string()),
+    };
+    if RESERVED_SYMBOLS.contains(&symbol) {
+        return Err(format!("symbol '{}' is reserved for the map", symbol));
+    }
+
+    let kind = words.next().unwrap_or("");
+    let name = words.collect::<Vec<_>>().join(" ");
+    if name.is_empty() {
+        return Err(format!(
+            "symbol '{}' needs a kind and a name, e.g. 'monster goblin'",
+            symbol
+        ));
+    }
+    let feature = match kind {
+        "monster" if create_monster(&name, 0, 0).is_some() => Feature::Monster(name),
+        "monster" => {
+            let names: Vec<&str> = MONSTERS.iter().map(|(known, _)| *known).collect();
+            let (last, others) = names.split_last().expect("there are monsters");
+            return Err(format!(
+                "unknown monster '{}', expected {} or {}",
+                name,
+                others.join(", "),
+                last
+            ));
+        }
+        "item" | "chest" if loot::find_table(&name).is_none() => {
+            return Err(format!("unknown loot table '{}'", name));
+        }
+        "item" => Feature::Item(name),
+        "chest" => Feature::Chest(name),
+        "trap" => TrapKind::ALL
+            .into_iter()
+            .find(|trap| trap.name() == format!("{} trap", name))
+            .map(Feature::Trap)
+            .ok_or_else(|| {
+                format!(
+                    "unknown trap '{}', expected spike, teleport, poison gas or alarm",
+                    name
+                )
+            })?,
+        _ => {
+            return Err(format!(
+                "unknown kind '{}', expected monster, item, chest or trap",
+                kind
+            ))
+        }
+    };
+    Ok((symbol, feature))
+}
+
+/// Checks a vault whose map is complete and pads its rows to one width.
+fn finish(mut vault: Vault) -> Result<Vault, String> {
+    while vault
+        .rows
+        .last()
+        .is_some_and(|row| row.iter().all(|c| *c == ' '))
+    {
+        vault.rows.pop();
+    }
+    if vault.rows.is_empty() {
+        return Err(format!("the map of vault '{}' is empty", vault.name));
+    }
+    let width = vault.rows.iter().map(Vec::len).max().unwrap_or(0);
+    for row in &mut vault.rows {
+        row.resize(width, ' ');
+    }
+
+    for (y, row) in vault.rows.iter().enumerate() {
+        for (x, symbol) in row.iter().enumerate() {
+            if !RESERVED_SYMBOLS.contains(symbol) && !vault.legend.contains_key(symbol) {
+                return Err(format!(
+                    "symbol '{}' in row {}, column {} of vault '{}' is not in the legend",
+                    symbol,
+                    y + 1,
+                    x + 1,
+                    vault.name
+                ));
+            }
+        }
+    }
+    if vault.exits().is_empty() {
+        return Err(format!(
+            "vault '{}' needs a door '+' that leads outside",
+            vault.name
+        ));
+    }
+    Ok(vault)
+}
+
+/// Parses the built-in vaults.
+///
+/// # Returns
+/// Every vault in `vaults.txt`
+pub fn builtin() -> Vec<Vault> {
+    parse_vaults(VAULTS).expect("the built-in vaults are valid")
+}
+
+/// Creates a monster by the name used in vault legends.
+///
+/// # Returns
+/// * `Some(Monster)` at the given position
+/// * `None` if no monster has this name
+pub fn create_monster(name: &str, x: i32, y: i32) -> Option<Monster> {
+    MONSTERS
+        .iter()
+        .find(|(known, _)| *known == name)
+        .map(|(_, create)| create(x, y))
+}
+
+/// Picks a vault for a depth, weighted by rarity.
+///
+/// # Arguments
+/// * `vaults` - The vaults to pick from
+/// * `depth` - Depth of the level
+/// * `rng` - Random number generator
+///
+/// # Returns
+/// * `Some(&Vault)` allowed on the depth
+/// * `None` if no vault is allowed there
+pub fn choose<'a>(vaults: &'a [Vault], depth: i32, rng: &mut Rng) -> Option<&'a Vault> {
+    let allowed: Vec<&Vault> = vaults.iter().filter(|v| v.allows_depth(depth)).collect();
+    let total: i32 = allowed.iter().map(|vault| vault.rarity.weight()).sum();
+    if total <= 0 {
+        return None;
+    }
+
+    let mut pick = rng.range(0, total);
+    for vault in allowed {
+        if pick < vault.rarity.weight() {
+            return Some(vault);
+        }
+        pick -= vault.rarity.weight();
+    }
+    None
+}
+
+/// Stamps a vault into a finished map in a random orientation.
+///
+/// The vault only covers rock and walls. Every door leading outside is
+/// joined to the nearest walkable tile by a tunnel. If no position keeps the
+/// map connected, the map is left as it was.
+///
+/// # Arguments
+/// * `map` - The map to place the vault into
+/// * `vault` - The vault to place
+/// * `rng` - Random number generator
+///
+/// # Returns
+/// * `Some(Vec<Placed>)` with the features of the legend to place
+/// * `None` if the vault did not fit
+pub fn place(map: &mut Map, vault: &Vault, rng: &mut Rng) -> Option<Vec<Placed>> {
+    let original = map.tiles.clone();
+    for _ in 0..PLACEMENT_ATTEMPTS {
+        let transform = Transform::ALL[rng.range(0, Transform::ALL.len() as i32) as usize];
+        let vault = vault.transformed(transform);
+        let (width, height) = (vault.rows[0].len() as i32, vault.rows.len() as i32);
+        if width > map.width - 2 || height > map.height - 2 {
+            continue;
+        }
+        let left = rng.range(1, map.width - width);
+        let top = rng.range(1, map.height - height);
+        if !fits(map, &vault, left, top) {
+            continue;
+        }
+
+        let placed = stamp(map, &vault, left, top);
+        if connect_exits(map, &vault, left, top) && mapgen::regions(map).len() == 1 {
+            mapgen::surround_with_walls(map);
+            return Some(placed);
+        }
+        map.tiles = original.clone();
+    }
+    None
+}
+
+/// Checks whether every cell of the vault would cover rock or wall.
+fn fits(map: &Map, vault: &Vault, left: i32, top: i32) -> bool {
+    (0..vault.rows.len() as i32).all(|y| {
+        (0..vault.rows[0].len() as i32).all(|x| {
+            vault.is_outside(x, y)
+                || matches!(
+                    map.get_tile(left + x, top + y),
+                    Some(Tile::Empty | Tile::Wall)
+                )
+        })
+    })
+}
+
+/// Writes the tiles of the vault into the map.
+///
+/// # Returns
+/// The features of the legend at their map positions
+fn stamp(map: &mut Map, vault: &Vault, left: i32, top: i32) -> Vec<Placed> {
+    let mut placed = Vec::new();
+    for (y, row) in vault.rows.iter().enumerate() {
+        for (x, symbol) in row.iter().enumerate() {
+            let (x, y) = (left + x as i32, top + y as i32);
+            let tile = match symbol {
+                ' ' => continue,
+                '#' => Tile::Wall,
+                '+' => Tile::Door(DoorState::Closed),
+                _ => Tile::Floor,
+            };
+            map.set_tile(x, y, tile);
+            if let Some(feature) = vault.legend.get(symbol) {
+                placed.push(Placed {
+                    x,
+                    y,
+                    feature: feature.clone(),
+                });
+            }
+        }
+    }
+    placed
+}
+
+/// Digs a tunnel from every exit of the vault to the nearest walkable tile
+/// outside of it, going around the vault.
+///
+/// # Returns
+/// `true` if every exit could be joined
+fn connect_exits(map: &mut Map, vault: &Vault, left: i32, top: i32) -> bool {
+    let inside = |x: i32, y: i32| !vault.is_outside(x - left, y - top);
+    for (_, (ox, oy)) in vault.exits() {
+        let start = (left + ox, top + oy);
+        let mut previous = Grid::new(map.width, map.height, None);
+        let mut queue = VecDeque::from([start]);
+        let mut goal = None;
+        while let Some((x, y)) = queue.pop_front() {
+            if mapgen::is_passable(map, x, y) {
+                goal = Some((x, y));
+                break;
+            }
+            for (dx, dy) in [(0, -1), (0, 1), (-1, 0), (1, 0)] {
+                let (nx, ny) = (x + dx, y + dy);
+                let on_map = nx > 0 && ny > 0 && nx < map.width - 1 && ny < map.height - 1;
+                if on_map && !inside(nx, ny) && (nx, ny) != start && previous[(nx, ny)].is_none() {
+                    previous[(nx, ny)] = Some((x, y));
+                    queue.push_back((nx, ny));
+                }
+            }
+        }
+
+        let Some(mut position) = goal else {
+            return false;
+        };
+        while let Some(next) = previous[position] {
+            map.set_tile(next.0, next.1, Tile::Floor);
+            position = next;
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Room;
+
+    #[test]
+    fn test_parse_builtin_and_errors() {
+        let vaults = builtin();
+        assert!(vaults.len() >= 4);
+        let nest = &vaults[0];
+        assert_eq!(nest.name, "Rat Nest");
+        assert_eq!((nest.min_depth, nest.max_depth), (1, Some(4)));
+        assert_eq!(nest.legend[&'r'], Feature::Monster("rat".to_string()));
+        assert!(vaults.iter().all(|vault| vault
+            .rows
+            .iter()
+            .all(|row| row.len() == vault.rows[0].len())));
+
+        let parse = |text: &str| parse_vaults(text).unwrap_err();
+        assert_eq!(
+            parse("vault A\nlegend o monster orc\nmap\n+o\nend"),
+            "line 2: unknown monster 'orc', expected goblin, rat or beetle"
+        );
+        assert_eq!(
+            parse("vault A\ndepth 5-3"),
+            "line 2: depth range '5-3' is empty"
+        );
+        assert_eq!(
+            parse("vault A\nmap\n#.#\n+x#\nend"),
+            "line 5: symbol 'x' in row 2, column 2 of vault 'A' is not in the legend"
+        );
+        assert_eq!(
+            parse("vault A\nmap\n###\n#.#\n###\nend"),
+            "line 6: vault 'A' needs a door '+' that leads outside"
+        );
+        assert_eq!(
+            parse("vault A\nmap\n+.\n"),
+            "line 3: the map of vault 'A' is missing 'end'"
+        );
+        assert_eq!(
+            parse("legend g monster goblin"),
+            "line 1: expected 'vault <name>', found 'legend g monster goblin'"
+        );
+    }
+
+    #[test]
+    fn test_transform_turns_and_mirrors() {
+        let rows = vec![vec!['a', 'b', 'c'], vec!['d', 'e', 'f']];
+        assert_eq!(Transform::new(0, false).apply(&rows), rows);
+        assert_eq!(
+            Transform::new(1, false).apply(&rows),
+            vec![vec!['d', 'a'], vec!['e', 'b'], vec!['f', 'c']]
+        );
+        assert_eq!(
+            Transform::new(2, true).apply(&rows),
+            vec![vec!['d', 'e', 'f'], vec!['a', 'b', 'c']]
+        );
+        assert_eq!(Transform::new(4, false), Transform::new(0, false));
+    }
+
+    #[test]
+    fn test_place_keeps_map_connected() {
+        for (seed, vault) in builtin().iter().enumerate() {
+            let mut map = Map::new(40, 15);
+            map.place_room(Room::at(2, 2, 8, 6));
+            let mut rng = Rng::new(seed as u64);
+            let placed = place(&mut map, vault, &mut rng).expect(&vault.name);
+
+            assert_eq!(mapgen::regions(&map).len(), 1, "{}", vault.name);
+            assert_eq!(
+                placed.len(),
+                vault
+                    .rows
+                    .iter()
+                    .flatten()
+                    .filter(|c| vault.legend.contains_key(c))
+                    .count()
+            );
+            for spot in placed {
+                assert_eq!(map.get_tile(spot.x, spot.y), Some(&Tile::Floor));
+            }
+        }
+    }
+}
@@ -0,0 +1,106 @@
+//! Plays games through the headless engine API with fixed seeds.
+
+use kd_rusty_crawler::{
+    bot::{self, Bot},
+    character::{Background, Class},
+    command::{Command, Direction},
+    engine::Event,
+    rng::Rng,
+    trap::{Trap, TrapKind},
+    Game, GameState, Map, Player,
+};
+
+/// Starts a game for a warrior with a fixed seed.
+fn new_game(seed: u64) -> Game {
+    let player = Player::create("Bot".to_string(), Class::Warrior, Background::Soldier);
+    Game::generate(player, Rng::new(seed))
+}
+
+/// Lets a fresh bot play a number of turns.
+fn play(game: &mut Game, turns: u32) -> Vec<Event> {
+    bot::play(game, &mut Bot::new(), turns)
+}
+
+#[test]
+fn test_same_seed_plays_the_same_game() {
+    let mut first = new_game(7);
+    let mut second = new_game(7);
+    assert_eq!(first.observe(), second.observe());
+
+    let events = play(&mut first, 300);
+    assert_eq!(play(&mut second, 300), events);
+    assert_eq!(first.observe(), second.observe());
+    assert!(events.iter().any(|e| matches!(e, Event::Moved { .. })));
+}
+
+#[test]
+fn test_bot_explores_and_descends() {
+    let mut game = new_game(42);
+    let events = play(&mut game, 2000);
+    let obs = game.observe();
+
+    let turns = events
+        .iter()
+        .filter(|e| matches!(e, Event::TurnEnded { .. }))
+        .count();
+    assert_eq!(turns as u32, obs.turns);
+    assert!(
+        obs.depth > 1 || obs.state == GameState::GameOver,
+        "stuck on depth {} after {} turns",
+        obs.depth,
+        obs.turns
+    );
+    if obs.depth > 1 {
+        assert!(events.contains(&Event::LevelEntered { depth: 2 }));
+    }
+    if obs.state == GameState::GameOver {
+        let cause = game.cause_of_death.clone().unwrap();
+        assert!(events.contains(&Event::GameOver { cause }));
+    }
+}
+
+#[test]
+fn test_rejected_commands_use_no_turn() {
+    let mut game = new_game(1);
+
+    let events = game.apply(Command::Equip(Some(99)));
+    assert_eq!(
+        events,
+        vec![Event::Message("Invalid Inventory-Index".to_string())]
+    );
+    assert!(game
+        .apply(Command::Open(None))
+        .iter()
+        .all(|e| !matches!(e, Event::TurnEnded { .. })));
+    assert_eq!(game.turns, 0);
+
+    assert!(game
+        .apply(Command::Wait)
+        .contains(&Event::TurnEnded { turn: 1 }));
+
+    game.log("Logged outside of a command.");
+    game.pause();
+    assert!(game.apply(Command::Wait).is_empty());
+    assert_eq!(game.observe().turns, 1);
+}
+
+#[test]
+fn test_teleport_traps_report_the_jump() {
+    let map = Map::from_ascii("#######\n#.....#\n#######").unwrap();
+    let mut game = Game::new("Bot".to_string(), map);
+    game.player.x = 1;
+    game.player.y = 1;
+    game.traps.push(Trap::new(2, 1, TrapKind::Teleport));
+
+    let events = game.apply(Command::Move(Direction::East));
+    let moves: Vec<&Event> = events
+        .iter()
+        .filter(|e| matches!(e, Event::Moved { .. }))
+        .collect();
+    let (x, y) = (game.player.x, game.player.y);
+    assert_eq!(
+        moves,
+        [&Event::Moved { x: 2, y: 1 }, &Event::Moved { x, y }]
+    );
+    assert!(events.contains(&Event::Message("The world blurs around you.".to_string())));
+}
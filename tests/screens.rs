@@ -0,0 +1,159 @@
+//! Snapshot tests drawing screens on the in-memory backend.
+
+use kd_rusty_crawler::{
+    backend::{Color, MemoryBackend},
+    options::{ColorMode, MessageSpeed, Options},
+    ui::Content,
+    Game, Item, Map, Room, UI,
+};
+
+/// Creates a UI on an in-memory screen that never waits.
+fn memory_ui(width: u16, height: u16, color: ColorMode) -> UI<MemoryBackend> {
+    let mut ui = UI::with_backend(MemoryBackend::new(width, height));
+    ui.apply_options(&Options {
+        color,
+        message_speed: MessageSpeed::Instant,
+        ..Options::default()
+    });
+    ui.draw_frame();
+    ui
+}
+
+/// Creates a game in a single small room.
+fn room_game() -> Game {
+    let mut map = Map::new(9, 5);
+    map.create_room(Room::new(7, 5));
+    let mut game = Game::new("Bob".to_string(), map);
+    game.log("Welcome!");
+    game
+}
+
+#[test]
+fn test_main_menu_snapshot() {
+    let mut ui = memory_ui(40, 20, ColorMode::Color);
+    ui.update_content(Content::MainMenu);
+
+    let expected = r"┌──────────────────────────────────────┐
+│                                      │
+│                                      │
+│                                      │
+│      ____  _   _ ____ _______   __   │
+│     |  _ \| | | / ___|_   _\ \ / /   │
+│     | |_) | | | \___ \ | |  \ V /    │
+│     |  _ <| |_| |___) || |   | |     │
+│     |_| \_\\___/|____/ |_|   |_|     │
+│             C R A W L E R            │
+│                 VERSION              │
+│                                      │
+│              1. New Game             │
+│              2. Load Game            │
+│            3. Hall of Fame           │
+│              4. Settings             │
+│                5. Exit               │
+│                  >                   │
+│                                      │
+└──────────────────────────────────────┘";
+    let version = format!("v{}", env!("CARGO_PKG_VERSION"));
+    let expected = expected.replace("VERSION", &format!("{:<7}", version));
+    assert_eq!(ui.backend().text(), expected);
+    assert_eq!(ui.backend().cursor, (21, 17));
+}
+
+#[test]
+fn test_game_screen_snapshot() {
+    let mut ui = memory_ui(60, 24, ColorMode::Color);
+    ui.update_content(Content::Game(&room_game()));
+
+    let screen = ui.backend().text();
+    let lines: Vec<&str> = screen.lines().collect();
+    assert_eq!(
+        lines[1..9],
+        [
+            "│ ╔════════════════════════════════════╗ ╔═══════════════╗ │",
+            "│ ║ #######                            ║ ║Bob the Warrior║ │",
+            "│ ║ #.....#                            ║ ║  HP: 100/100  ║ │",
+            "│ ║ #..@..#                            ║ ║   MP: 10/10   ║ │",
+            "│ ║ #.....#                            ║ ║   Level: 1    ║ │",
+            "│ ║ #######                            ║ ║   XP: 0/100   ║ │",
+            "│ ╚════════════════════════════════════╝ ╚═══════════════╝ │",
+            "│ Messages─────────────────────────────────────────────────│",
+        ]
+    );
+    assert_eq!(
+        lines[9],
+        "│ Welcome!                                                 │"
+    );
+    assert!(lines[22].starts_with("│ Command: "));
+
+    let backend = ui.backend();
+    assert_eq!(backend.cell(7, 4).unwrap().color, Color::White);
+    assert_eq!(backend.cell(6, 4).unwrap().color, Color::Gray);
+    assert_eq!(backend.cursor, (10, 22));
+}
+
+#[test]
+fn test_monochrome_and_input() {
+    let mut ui = memory_ui(40, 12, ColorMode::Monochrome);
+    ui.update_content(Content::Game(&room_game()));
+    assert_eq!(ui.backend().cell(7, 4).unwrap().color, Color::Default);
+
+    ui.backend_mut().push_input("y");
+    assert_eq!(ui.prompt("Quit?"), "y");
+    assert!(ui.backend().row(5).contains("Quit?"));
+    assert_eq!(ui.get_input(), "");
+}
+
+#[test]
+fn test_equipment_screen_shows_stat_changes() {
+    let mut game = room_game();
+    let player = &mut game.player;
+    player.inventory = vec![
+        Item::create_sword(),
+        Item::create_wooden_shield(),
+        Item::create_apple(),
+        Item::create_battle_axe(),
+    ];
+    player.add_item(0).unwrap();
+    player.add_item(0).unwrap();
+
+    let mut ui = memory_ui(70, 24, ColorMode::Color);
+    ui.update_content(Content::Equipment(&game));
+    let screen = ui.backend().text();
+
+    assert!(screen.contains("Main hand   Sword"));
+    assert!(screen.contains("Off hand    Wooden Shield"));
+    assert!(screen.contains("Head        -"));
+    assert!(screen.contains("2. Battle Axe (+5 attack, -2 defense)"));
+    assert!(!screen.contains("Apple"));
+}
+
+#[test]
+fn test_inventory_screen_lists_every_item() {
+    let mut game = room_game();
+    game.player.inventory = vec![
+        Item::create_sword(),
+        Item::create_wooden_shield(),
+        Item::create_apple(),
+        Item::create_battle_axe(),
+        Item::create_torch(),
+    ];
+
+    let mut ui = memory_ui(56, 14, ColorMode::Color);
+    ui.update_content(Content::Inventory(&game, "throw"));
+    let screen = ui.backend().text();
+    let lines: Vec<&str> = screen.lines().collect();
+    assert_eq!(
+        lines[2..11],
+        [
+            "│  I N V E N T O R Y                                   │",
+            "│                                                      │",
+            "│  1. Sword                                            │",
+            "│  2. Wooden Shield                                    │",
+            "│  3. Apple                                            │",
+            "│  4. Battle Axe                                       │",
+            "│  5. Torch (400 turns)                                │",
+            "│                                                      │",
+            "│  Enter a number to throw the item, Enter to go back  │",
+        ]
+    );
+}